@@ -1,36 +1,351 @@
-use crate::state::SharedState;
+use crate::error::{Result, RmmError};
+use crate::platform;
+use crate::shutdown::{self, ShutdownSignal};
+use crate::sources::{ActivitySignal, LatestInput, ListenerSource};
+use crate::state::{AppState, InputKind, SharedState};
 use rdev::{listen, Event, EventType};
-use std::time::Instant;
-use tracing::{debug, error, info};
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 
-pub fn start_monitoring(state: SharedState) {
-    std::thread::spawn(move || {
-        info!("Starting activity monitoring");
+/// How often the attached input devices are counted
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(5);
 
-        let callback = move |event: Event| match event.event_type {
-            EventType::KeyPress(key) => {
-                info!("Key pressed: {:?}", key);
-                if let Ok(mut state) = state.lock() {
-                    state.last_activity = Instant::now();
+/// Consecutive fast samples needed before the user counts as present, so a
+/// single synthetic jump doesn't trigger it
+const SUSTAINED_SAMPLES: u32 = 3;
+
+/// Restart policy for the `rdev` listener
+pub const LISTEN_RETRY: ListenRetry = ListenRetry {
+    initial_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(60),
+    max_failures: 10,
+};
+
+/// Cursor speed in pixels per second between two samples
+pub fn velocity(from: (f64, f64), to: (f64, f64), elapsed: Duration) -> Option<f64> {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return None;
+    }
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    Some((dx * dx + dy * dy).sqrt() / secs)
+}
+
+/// Detects sustained fast mouse movement, which means the user is definitely
+/// at the machine, and holds moves off for a cooldown afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct VelocityTracker {
+    /// Pixels per second; `None` disables detection
+    threshold: Option<f64>,
+    cooldown: Duration,
+    last: Option<((f64, f64), Instant)>,
+    fast_samples: u32,
+    present_until: Option<Instant>,
+}
+
+impl VelocityTracker {
+    pub fn new(threshold: Option<f64>, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            ..Self::default()
+        }
+    }
+
+    /// Record a cursor position; returns the velocity since the previous sample.
+    pub fn sample(&mut self, position: (f64, f64), now: Instant) -> Option<f64> {
+        let speed = self
+            .last
+            .and_then(|(from, at)| velocity(from, position, now.saturating_duration_since(at)));
+        self.last = Some((position, now));
+
+        let Some(threshold) = self.threshold else {
+            return speed;
+        };
+        match speed {
+            Some(speed) if speed >= threshold => {
+                self.fast_samples += 1;
+                if self.fast_samples >= SUSTAINED_SAMPLES {
+                    self.present_until = Some(now + self.cooldown);
                 }
             }
-            EventType::MouseMove { x, y } => {
-                debug!("Mouse moved to: ({}, {})", x, y);
-                if let Ok(mut state) = state.lock() {
-                    state.last_activity = Instant::now();
-                }
+            Some(_) => self.fast_samples = 0,
+            None => {}
+        }
+        speed
+    }
+
+    /// Whether a recent burst of fast movement still marks the user present
+    pub fn is_present(&self, now: Instant) -> bool {
+        self.present_until.is_some_and(|until| now < until)
+    }
+}
+
+/// Whether the listener saw the cursor arrive at a move we issued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EchoStatus {
+    /// No move is being tracked
+    Idle,
+    /// Still waiting for the event
+    Pending,
+    Observed,
+    /// The window passed without a matching event
+    TimedOut,
+}
+
+/// Correlates an issued move with the `MouseMove` event the listener reports
+/// for it, as a check that the OS actually delivered the move.
+#[derive(Debug, Clone, Default)]
+pub struct MoveEcho {
+    expected: Option<((i32, i32), Instant)>,
+    tolerance: i32,
+    observed: bool,
+}
+
+impl MoveEcho {
+    /// Start tracking a move to `target`, replacing any previous one.
+    pub fn expect(&mut self, target: (i32, i32), tolerance: i32, now: Instant) {
+        self.expected = Some((target, now));
+        self.tolerance = tolerance.abs();
+        self.observed = false;
+    }
+
+    /// Feed a cursor position from the listener; returns whether it matched
+    /// the tracked move.
+    pub fn observe(&mut self, position: (i32, i32)) -> bool {
+        let Some((target, _)) = self.expected else {
+            return false;
+        };
+        let matched = (position.0 - target.0).abs() <= self.tolerance
+            && (position.1 - target.1).abs() <= self.tolerance;
+        self.observed |= matched;
+        matched
+    }
+
+    pub fn status(&self, now: Instant, window: Duration) -> EchoStatus {
+        match self.expected {
+            None => EchoStatus::Idle,
+            Some(_) if self.observed => EchoStatus::Observed,
+            Some((_, issued)) if now.saturating_duration_since(issued) > window => {
+                EchoStatus::TimedOut
             }
-            EventType::ButtonPress(button) => {
-                info!("Mouse button pressed: {:?}", button);
+            Some(_) => EchoStatus::Pending,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.expected = None;
+        self.observed = false;
+    }
+}
+
+/// Whether the OS presence sensor says the user is at the machine. `None`
+/// when unsupported or unknown.
+pub fn user_present() -> Option<bool> {
+    platform::user_present()
+}
+
+/// Whether moves should be suppressed for the reported presence. Only a
+/// definite "present" suppresses; absent or unknown fall back to input idle time.
+/// `present` is only queried when `respect_presence_api` is set.
+pub fn should_pause_for_presence(
+    respect_presence_api: bool,
+    present: impl FnOnce() -> Option<bool>,
+) -> bool {
+    respect_presence_api && present() == Some(true)
+}
+
+/// An input device hot-plug event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceEvent {
+    Added,
+    Removed,
+}
+
+/// Compare two device counts and report what changed. Unknown counts never
+/// produce an event.
+pub fn device_event_between(previous: Option<u32>, current: Option<u32>) -> Option<DeviceEvent> {
+    match (previous?, current?) {
+        (before, after) if after > before => Some(DeviceEvent::Added),
+        (before, after) if after < before => Some(DeviceEvent::Removed),
+        _ => None,
+    }
+}
+
+/// Plugging in or removing a keyboard or mouse usually means the user is at
+/// the machine, so treat it as input.
+pub fn on_device_event(state: &mut AppState, event: DeviceEvent, now: Instant) {
+    info!("Input device {:?}, marking user active", event);
+    state.record_input(InputKind::Device, now);
+}
+
+/// Poll the input device count and report hot-plug events. Does nothing on
+/// platforms where the count is unavailable.
+fn start_device_monitoring(state: SharedState) {
+    let Some(initial) = platform::input_device_count() else {
+        debug!("Input device hot-plug detection unavailable");
+        return;
+    };
+    std::thread::spawn(move || {
+        let mut previous = Some(initial);
+        loop {
+            std::thread::sleep(DEVICE_POLL_INTERVAL);
+            let current = platform::input_device_count();
+            if let Some(event) = device_event_between(previous, current) {
                 if let Ok(mut state) = state.lock() {
-                    state.last_activity = Instant::now();
+                    on_device_event(&mut state, event, Instant::now());
                 }
             }
-            _ => {}
+            // Keep the last known count across failed reads
+            previous = current.or(previous);
+        }
+    });
+}
+
+/// Handle a `MouseMove` from the listener. Returns the activity to report,
+/// if any: moves caused by our own synthetic input (see
+/// [`AppState::ignore_own_input`]) only confirm the move.
+pub fn on_mouse_move(
+    state: &mut AppState,
+    position: (f64, f64),
+    now: Instant,
+) -> Option<ActivitySignal> {
+    state
+        .move_echo
+        .observe((position.0.round() as i32, position.1.round() as i32));
+    if state.is_own_input(now) {
+        return None;
+    }
+    state.velocity.sample(position, now);
+    Some(ActivitySignal::new(InputKind::Mouse, now))
+}
+
+/// Handle a `KeyPress` from the listener. The F15 tap of the "key"
+/// keep-awake mode isn't user activity.
+pub fn on_key_press(state: &AppState, now: Instant) -> Option<ActivitySignal> {
+    (!state.is_own_input(now)).then_some(ActivitySignal::new(InputKind::Keyboard, now))
+}
+
+fn report(input: &LatestInput, signal: Option<ActivitySignal>) {
+    if let Some(signal) = signal {
+        input.report(signal);
+    }
+}
+
+/// How a listener that returned or failed is restarted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListenRetry {
+    /// Wait before the first restart, doubled on each consecutive failure
+    pub initial_delay: Duration,
+    /// Longest wait between restarts. A listener that ran at least this long
+    /// before stopping starts the failure count over.
+    pub max_delay: Duration,
+    /// Consecutive failures after which monitoring is given up
+    pub max_failures: u32,
+}
+
+impl ListenRetry {
+    /// Wait before restarting after `failures` consecutive failures (1-based)
+    pub fn delay(&self, failures: u32) -> Duration {
+        let doublings = failures.saturating_sub(1).min(31);
+        self.initial_delay
+            .saturating_mul(1 << doublings)
+            .min(self.max_delay)
+    }
+}
+
+/// Run `listen` until shutdown is requested, restarting it with backoff
+/// whenever it returns or errors. Gives up with
+/// [`RmmError::ActivityMonitor`] after `retry.max_failures` consecutive
+/// failures.
+pub fn listen_with_retry<E: Debug>(
+    retry: ListenRetry,
+    signal: &ShutdownSignal,
+    mut listen: impl FnMut() -> std::result::Result<(), E>,
+) -> Result<()> {
+    let mut failures = 0;
+    loop {
+        let started = Instant::now();
+        let outcome = listen();
+        if signal.is_requested() {
+            return Ok(());
+        }
+        if started.elapsed() >= retry.max_delay {
+            failures = 0;
+        }
+        failures += 1;
+        let reason = match outcome {
+            Ok(()) => "listener stopped".to_string(),
+            Err(e) => format!("{:?}", e),
         };
+        if failures >= retry.max_failures {
+            return Err(RmmError::ActivityMonitor(format!(
+                "giving up after {} consecutive failures, last: {}",
+                failures, reason
+            )));
+        }
+        let delay = retry.delay(failures);
+        warn!(
+            "Activity monitoring stopped ({}), restarting in {:?} (attempt {})",
+            reason,
+            delay,
+            failures + 1
+        );
+        if signal.sleep(delay) {
+            return Ok(());
+        }
+    }
+}
+
+/// Start the `rdev` listener (and device hot-plug detection). Keyboard and
+/// mouse input is reported through the returned source; button state is
+/// tracked directly in `state`. The listener is restarted if it stops.
+pub fn start_monitoring(state: SharedState) -> ListenerSource {
+    start_device_monitoring(state.clone());
+    let (source, input) = ListenerSource::pair();
 
-        if let Err(e) = listen(callback) {
-            error!("Error in activity monitoring: {:?}", e);
+    std::thread::spawn(move || {
+        info!("Starting activity monitoring");
+        let result = listen_with_retry(LISTEN_RETRY, shutdown::signal(), || {
+            listen(event_handler(state.clone(), input.clone()))
+        });
+        if let Err(e) = result {
+            error!("Error in activity monitoring: {}", e);
         }
     });
+
+    source
+}
+
+/// Callback for `rdev::listen`, built afresh for every (re)start
+fn event_handler(state: SharedState, input: LatestInput) -> impl FnMut(Event) {
+    move |event: Event| match event.event_type {
+        EventType::KeyPress(_) => {
+            // Never which key: the log would turn into a keystroke record
+            debug!("Key pressed");
+            if let Ok(state) = state.lock() {
+                report(&input, on_key_press(&state, Instant::now()));
+            }
+        }
+        EventType::MouseMove { x, y } => {
+            debug!("Mouse moved to: ({}, {})", x, y);
+            if let Ok(mut state) = state.lock() {
+                report(&input, on_mouse_move(&mut state, (x, y), Instant::now()));
+            }
+        }
+        EventType::ButtonPress(button) => {
+            info!("Mouse button pressed: {:?}", button);
+            if let Ok(mut state) = state.lock() {
+                state.press_button(button, Instant::now());
+            }
+        }
+        EventType::ButtonRelease(button) => {
+            debug!("Mouse button released: {:?}", button);
+            if let Ok(mut state) = state.lock() {
+                state.release_button(button, Instant::now());
+            }
+        }
+        _ => {}
+    }
 }
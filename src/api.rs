@@ -0,0 +1,318 @@
+// Transport-independent request handling for the local control/status interface.
+
+use crate::config::{Config, SharedConfig};
+use crate::error::Result;
+use crate::events::EventKind;
+use crate::logging;
+use crate::mouse::{self, LazyController, MoveOutcome};
+use crate::platform;
+use crate::state::{Cause, SharedState};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// Lines returned by `/logs` when `lines` isn't given
+pub const DEFAULT_LOG_LINES: usize = 100;
+/// Upper bound on `/logs?lines=N`
+pub const MAX_LOG_LINES: usize = 1000;
+/// Content type of `/metrics`, the Prometheus text format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiRequest {
+    pub method: String,
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+}
+
+impl ApiRequest {
+    /// Build a request from a method and a target such as `/logs?lines=50`.
+    pub fn new(method: &str, target: &str) -> Self {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (key.to_string(), value.to_string())
+            })
+            .collect();
+        Self {
+            method: method.to_ascii_uppercase(),
+            path: path.to_string(),
+            query,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl ApiResponse {
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.into(),
+        }
+    }
+
+    pub fn json(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.into(),
+        }
+    }
+}
+
+/// Everything request handlers may need access to
+#[derive(Clone)]
+pub struct ApiContext {
+    pub state: SharedState,
+    pub config: SharedConfig,
+    /// File `POST /reload` reads, or [`Config::config_path`] when `None`
+    pub config_path: Option<PathBuf>,
+    pub log_path: PathBuf,
+    /// Probe for mouse control, normally [`crate::mouse::MouseController::is_available`]
+    pub mouse_available: fn() -> bool,
+    /// Performs `POST /move`, normally [`move_with_mouse`]
+    pub move_now: fn(SharedState, &Config) -> Result<MoveOutcome>,
+}
+
+/// Move the real mouse once, regardless of idle time
+pub fn move_with_mouse(state: SharedState, config: &Config) -> Result<MoveOutcome> {
+    mouse::move_now(state, config, &mut LazyController::default())
+}
+
+pub fn handle(ctx: &ApiContext, req: &ApiRequest) -> ApiResponse {
+    match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/healthz") => ApiResponse::text(200, "ok\n"),
+        (_, "/healthz") => ApiResponse::text(405, "Method not allowed\n"),
+        ("GET", "/readyz") => ready(ctx),
+        (_, "/readyz") => ApiResponse::text(405, "Method not allowed\n"),
+        ("GET", "/logs") => logs(ctx, req),
+        (_, "/logs") => ApiResponse::text(405, "Method not allowed\n"),
+        ("GET", "/events") => events(ctx),
+        (_, "/events") => ApiResponse::text(405, "Method not allowed\n"),
+        ("POST", "/reload") => reload(ctx),
+        (_, "/reload") => ApiResponse::text(405, "Method not allowed\n"),
+        ("GET", "/status") => status(ctx),
+        (_, "/status") => ApiResponse::text(405, "Method not allowed\n"),
+        ("POST", "/pause") => set_running(ctx, false),
+        (_, "/pause") => ApiResponse::text(405, "Method not allowed\n"),
+        ("POST", "/resume") => set_running(ctx, true),
+        (_, "/resume") => ApiResponse::text(405, "Method not allowed\n"),
+        ("POST", "/move") => move_once(ctx),
+        (_, "/move") => ApiResponse::text(405, "Method not allowed\n"),
+        ("GET", "/metrics") => metrics(ctx),
+        (_, "/metrics") => ApiResponse::text(405, "Method not allowed\n"),
+        _ => ApiResponse::text(404, "Not found\n"),
+    }
+}
+
+fn logs(ctx: &ApiContext, req: &ApiRequest) -> ApiResponse {
+    let lines = match req.query.get("lines") {
+        None => DEFAULT_LOG_LINES,
+        Some(value) => match value.parse::<usize>() {
+            Ok(n) => n.min(MAX_LOG_LINES),
+            Err(_) => return ApiResponse::text(400, "lines must be a non-negative integer\n"),
+        },
+    };
+    match logging::tail_lines(&ctx.log_path, lines) {
+        Ok(tail) => {
+            let mut body = tail.join("\n");
+            if !body.is_empty() {
+                body.push('\n');
+            }
+            ApiResponse::text(200, body)
+        }
+        Err(e) => ApiResponse::text(500, format!("Failed to read log: {}\n", e)),
+    }
+}
+
+/// Readiness checks that failed; empty when ready. Unknown permission state
+/// (platforms without the concept) counts as granted.
+pub fn readiness_failures(
+    mouse_available: bool,
+    accessibility_trusted: Option<bool>,
+    error_count: u32,
+    max_errors: u32,
+) -> Vec<&'static str> {
+    let mut failing = Vec::new();
+    if !mouse_available {
+        failing.push("mouse_control");
+    }
+    if accessibility_trusted == Some(false) {
+        failing.push("permissions");
+    }
+    if error_count >= max_errors {
+        failing.push("errors");
+    }
+    failing
+}
+
+/// 200 when RMM can do its job, 503 with the failing checks otherwise
+fn ready(ctx: &ApiContext) -> ApiResponse {
+    let Ok(error_count) = ctx.state.lock().map(|state| state.error_count) else {
+        return ApiResponse::text(500, "State unavailable\n");
+    };
+    let max_errors = ctx
+        .config
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .max_errors;
+    let failing = readiness_failures(
+        (ctx.mouse_available)(),
+        platform::accessibility_trusted(),
+        error_count,
+        max_errors,
+    );
+    let status = if failing.is_empty() { 200 } else { 503 };
+    let body = serde_json::json!({ "ready": failing.is_empty(), "failing": failing });
+    ApiResponse::json(status, body.to_string())
+}
+
+/// Recent events from the in-memory log, oldest first
+fn events(ctx: &ApiContext) -> ApiResponse {
+    match ctx.state.lock() {
+        Ok(state) => ApiResponse::json(200, state.events.to_json().to_string()),
+        Err(_) => ApiResponse::text(500, "State unavailable\n"),
+    }
+}
+
+/// Re-read the config file and apply it, replying with the changed fields
+/// Re-read the config file. 409 when there is no file to read: reloading
+/// never falls back to defaults.
+fn reload(ctx: &ApiContext) -> ApiResponse {
+    let error = |status, message: String| {
+        warn!("Config reload failed, keeping current config: {}", message);
+        let body = serde_json::json!({ "error": message });
+        ApiResponse::json(status, body.to_string())
+    };
+    let path = match ctx.config_path.clone().map_or_else(Config::config_path, Ok) {
+        Ok(path) => path,
+        Err(e) => return error(500, e.to_string()),
+    };
+    if !path.exists() {
+        return error(409, format!("{} does not exist", path.display()));
+    }
+    match Config::reload(&ctx.config, &path) {
+        Ok(changes) => {
+            info!("Config reloaded via API ({} change(s))", changes.len());
+            if let Ok(mut state) = ctx.state.lock() {
+                state
+                    .events
+                    .record(EventKind::Reload, format!("{} change(s)", changes.len()));
+            }
+            let body = serde_json::json!({ "changes": changes });
+            ApiResponse::json(200, body.to_string())
+        }
+        Err(e) => error(400, e.to_string()),
+    }
+}
+
+/// Running state, failures, move counts, seconds since the last move and uptime
+fn status(ctx: &ApiContext) -> ApiResponse {
+    let Ok(state) = ctx.state.lock() else {
+        return ApiResponse::text(500, "State unavailable\n");
+    };
+    let body = serde_json::json!({
+        "is_running": state.is_running,
+        "error_count": state.error_count,
+        "total_moves": state.total_moves(),
+        "failed_moves": state.failed_moves(),
+        "last_moved_secs_ago": state.last_moved.elapsed().as_secs(),
+        "uptime_secs": state.uptime(Instant::now()).as_secs(),
+    });
+    ApiResponse::json(200, body.to_string())
+}
+
+/// Counters and gauges in the Prometheus text exposition format
+fn metrics(ctx: &ApiContext) -> ApiResponse {
+    let Ok(state) = ctx.state.lock() else {
+        return ApiResponse::text(500, "State unavailable\n");
+    };
+    let samples: [(&str, &str, &str, u64); 5] = [
+        (
+            "rmm_moves_total",
+            "counter",
+            "Mouse moves that passed verification",
+            state.total_moves(),
+        ),
+        (
+            "rmm_move_failures_total",
+            "counter",
+            "Mouse moves that failed verification",
+            state.failed_moves(),
+        ),
+        (
+            "rmm_error_count",
+            "gauge",
+            "Consecutive failed moves",
+            u64::from(state.error_count),
+        ),
+        (
+            "rmm_uptime_seconds",
+            "gauge",
+            "Seconds since RMM started",
+            state.uptime(Instant::now()).as_secs(),
+        ),
+        (
+            "rmm_is_running",
+            "gauge",
+            "1 while moving the mouse is enabled, 0 while paused",
+            u64::from(state.is_running),
+        ),
+    ];
+    let body: String = samples
+        .iter()
+        .map(|(name, kind, help, value)| {
+            format!(
+                "# HELP {0} {2}\n# TYPE {0} {1}\n{0} {3}\n",
+                name, kind, help, value
+            )
+        })
+        .collect();
+    ApiResponse {
+        status: 200,
+        content_type: PROMETHEUS_CONTENT_TYPE,
+        body,
+    }
+}
+
+/// Pause or resume, replying with the running state afterwards
+fn set_running(ctx: &ApiContext, running: bool) -> ApiResponse {
+    let Ok(mut state) = ctx.state.lock() else {
+        return ApiResponse::text(500, "State unavailable\n");
+    };
+    if state.set_running(running, Cause::Ipc) {
+        info!(
+            "Monitoring {} via API",
+            if running { "resumed" } else { "paused" }
+        );
+    }
+    let body = serde_json::json!({ "is_running": state.is_running });
+    ApiResponse::json(200, body.to_string())
+}
+
+/// Move once now, replying with what happened
+fn move_once(ctx: &ApiContext) -> ApiResponse {
+    let config = ctx.config.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match (ctx.move_now)(Arc::clone(&ctx.state), &config) {
+        Ok(outcome) => {
+            let body = serde_json::json!({ "outcome": outcome.as_str() });
+            ApiResponse::json(200, body.to_string())
+        }
+        Err(e) => {
+            let body = serde_json::json!({ "error": e.to_string() });
+            ApiResponse::json(500, body.to_string())
+        }
+    }
+}
@@ -0,0 +1,293 @@
+// The resident run loop: activity monitoring, heartbeat and worker threads,
+// config hot reload and the optional status line, until shutdown.
+//
+// `main` builds logging and the config and hands over to [`run`] (or a
+// [`Runner`] when it has a tray to show); tests drive the same loop with a
+// mock backend, a short heartbeat, their own shutdown signal and, to control
+// how idle the user looks, their own `crate::clock`.
+
+use crate::api::{self, ApiContext};
+use crate::config::{Config, SharedConfig};
+use crate::error::Result;
+use crate::heartbeat::{self, Heartbeat, TickOutcome};
+use crate::http;
+use crate::logging;
+use crate::mouse::{LazyController, MouseBackend, MouseController};
+use crate::shutdown::{self, ShutdownSignal};
+use crate::sources::SharedSources;
+use crate::state::{Cause, SharedState};
+use crate::status;
+use crate::supervise;
+use crate::watch;
+use crate::worker::Worker;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Run RMM headless with the real mouse until [`shutdown::signal`] is
+/// requested.
+pub fn run(config: Config, state: SharedState) -> Result<()> {
+    Runner::new(config, state).run()
+}
+
+/// Something to run on the main thread while the loops work, such as the tray
+type MainThreadUi = Box<dyn FnOnce(SharedConfig)>;
+
+/// Options for [`run`]
+pub struct Runner<B = MouseController> {
+    config: Config,
+    state: SharedState,
+    controller: fn() -> Result<B>,
+    sources: Option<SharedSources>,
+    metrics_path: Option<PathBuf>,
+    stats_path: Option<PathBuf>,
+    watch_path: Option<PathBuf>,
+    log_path: PathBuf,
+    status_line: bool,
+    quiet: bool,
+    shutdown: &'static ShutdownSignal,
+    ui: Option<MainThreadUi>,
+}
+
+impl Runner {
+    pub fn new(config: Config, state: SharedState) -> Self {
+        Self::with_controller(config, state, MouseController::new)
+    }
+}
+
+impl<B: MouseBackend + 'static> Runner<B> {
+    /// Create mouse backends with `controller` instead of the real one
+    pub fn with_controller(
+        config: Config,
+        state: SharedState,
+        controller: fn() -> Result<B>,
+    ) -> Self {
+        let quiet = config.quiet;
+        Self {
+            config,
+            state,
+            controller,
+            sources: None,
+            metrics_path: None,
+            stats_path: None,
+            watch_path: None,
+            log_path: PathBuf::new(),
+            status_line: false,
+            quiet,
+            shutdown: shutdown::signal(),
+            ui: None,
+        }
+    }
+
+    /// Poll these instead of the sources for the configured `activity_mode`
+    pub fn with_sources(mut self, sources: SharedSources) -> Self {
+        self.sources = Some(sources);
+        self
+    }
+
+    /// Save the move statistics to `path` on every worker tick
+    pub fn with_metrics_path(mut self, path: PathBuf) -> Self {
+        self.metrics_path = Some(path);
+        self
+    }
+
+    /// Save the lifetime move counts to `path` on every worker tick,
+    /// including the last one at shutdown
+    pub fn with_stats_path(mut self, path: PathBuf) -> Self {
+        self.stats_path = Some(path);
+        self
+    }
+
+    /// Hot-reload the config file at `path`
+    pub fn watch_config(mut self, path: PathBuf) -> Self {
+        self.watch_path = Some(path);
+        self
+    }
+
+    /// Log file served by the HTTP API's `/logs`
+    pub fn with_log_path(mut self, path: PathBuf) -> Self {
+        self.log_path = path;
+        self
+    }
+
+    /// Redraw the status line on stdout instead of logging there
+    pub fn status_line(mut self, enabled: bool) -> Self {
+        self.status_line = enabled;
+        self
+    }
+
+    /// Skip the routine startup log lines even if the config doesn't ask to
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet |= quiet;
+        self
+    }
+
+    /// Stop when `signal` is requested instead of [`shutdown::signal`]
+    pub fn with_shutdown(mut self, signal: &'static ShutdownSignal) -> Self {
+        self.shutdown = signal;
+        self
+    }
+
+    /// Call `ui` on the calling thread once the loops are running, e.g. to
+    /// show the tray, and wait for shutdown after it returns. Without one the
+    /// run is headless.
+    pub fn with_ui(mut self, ui: impl FnOnce(SharedConfig) + 'static) -> Self {
+        self.ui = Some(Box::new(ui));
+        self
+    }
+
+    /// Start everything and block until shutdown is requested, then let the
+    /// loops finish and run the shutdown hooks.
+    pub fn run(self) -> Result<()> {
+        let Self {
+            config,
+            state,
+            controller,
+            sources,
+            metrics_path,
+            stats_path,
+            watch_path,
+            log_path,
+            status_line,
+            quiet,
+            shutdown: signal,
+            ui,
+        } = self;
+
+        if let Ok(mut state) = state.lock() {
+            state.start(&config);
+        }
+
+        // Start activity monitoring in background (uses shared `state`)
+        let sources =
+            sources.unwrap_or_else(|| heartbeat::activity_sources(config.activity_mode, &state));
+        logging::startup_info(
+            quiet,
+            format_args!("Activity monitoring started ({:?})", config.activity_mode),
+        );
+
+        let shared_config = Arc::new(RwLock::new(config.clone()));
+        if config.http_api.enabled {
+            let ctx = ApiContext {
+                state: Arc::clone(&state),
+                config: Arc::clone(&shared_config),
+                config_path: watch_path.clone(),
+                log_path,
+                mouse_available: MouseController::is_available,
+                move_now: api::move_with_mouse,
+            };
+            if let Err(e) = http::start(&config.http_api, ctx) {
+                warn!("Failed to start the HTTP API: {}", e);
+            }
+        }
+        if let Some(path) = watch_path {
+            watch::start(Arc::clone(&shared_config), Arc::clone(&state), path, signal);
+        }
+
+        // Heartbeat loop - check every heartbeat_interval seconds
+        let heartbeat_config = Arc::clone(&shared_config);
+        let heartbeat_state = Arc::clone(&state);
+        let heartbeat_sources = Arc::clone(&sources);
+        let heartbeat_loop =
+            supervise::spawn_supervised("heartbeat", config.restart_on_panic, move || {
+                // A panic in the previous run may have poisoned the state while
+                // holding it; this run starts from whatever it left behind
+                heartbeat_state.clear_poison();
+                // One controller for the life of the thread, reused by every tick.
+                // Created here rather than shared: it can't cross threads on macOS.
+                let mut heartbeat = Heartbeat::with_controller(
+                    Arc::clone(&heartbeat_config),
+                    Arc::clone(&heartbeat_state),
+                    LazyController::with_factory(controller),
+                )
+                .with_sources(Arc::clone(&heartbeat_sources))
+                .with_shutdown(signal);
+                let mut spent = Duration::ZERO;
+                // A tick that ran a burst of moves already used up part of the interval
+                while !signal.sleep(heartbeat.interval().saturating_sub(spent)) {
+                    let started = Instant::now();
+                    if heartbeat.tick() == TickOutcome::Quit {
+                        signal.request();
+                        return;
+                    }
+                    spent = started.elapsed();
+                }
+            });
+        logging::startup_info(
+            quiet,
+            format_args!(
+                "Heartbeat started ({}s interval)",
+                config.heartbeat_interval
+            ),
+        );
+
+        // Worker loop - housekeeping every worker_interval seconds
+        let worker_config = Arc::clone(&shared_config);
+        let worker_state = Arc::clone(&state);
+        let worker_loop =
+            supervise::spawn_supervised("worker", config.restart_on_panic, move || {
+                worker_state.clear_poison();
+                let mut worker = Worker::new(Arc::clone(&worker_config), Arc::clone(&worker_state))
+                    .with_sources(Arc::clone(&sources));
+                if let Some(path) = &metrics_path {
+                    worker = worker.with_metrics_path(path.clone());
+                }
+                if let Some(path) = &stats_path {
+                    worker = worker.with_stats_path(path.clone());
+                }
+                while !signal.sleep(worker.interval()) {
+                    worker.tick();
+                }
+                // One last pass so the latest stats are saved
+                worker.tick();
+            });
+        logging::startup_info(
+            quiet,
+            format_args!("Worker started ({}s interval)", config.worker_interval),
+        );
+
+        if status_line {
+            status::start_status_line(Arc::clone(&state), Arc::clone(&shared_config));
+        }
+
+        let loops = vec![heartbeat_loop, worker_loop];
+        if let Some(ui) = ui {
+            // The Cocoa run loop inside the tray never hands the main thread
+            // back, so shutdown is finished from another thread there
+            #[cfg(target_os = "macos")]
+            {
+                let state = Arc::clone(&state);
+                std::thread::spawn(move || {
+                    signal.wait();
+                    stop(&state, loops);
+                    std::process::exit(0);
+                });
+                ui(shared_config);
+                return Ok(());
+            }
+            #[cfg(not(target_os = "macos"))]
+            ui(shared_config);
+        }
+
+        // Keep the calling thread parked (the tray, if any, runs on its own
+        // thread) until Stop/Quit, then let the loops finish and return
+        signal.wait();
+        stop(&state, loops);
+        Ok(())
+    }
+}
+
+/// Wait for the background loops to finish after a shutdown request, then
+/// run the shutdown hooks
+fn stop(state: &SharedState, loops: Vec<JoinHandle<()>>) {
+    info!("Shutting down");
+    for handle in loops {
+        let _ = handle.join();
+    }
+    if let Ok(mut state) = state.lock() {
+        state.set_running(false, Cause::Shutdown);
+    }
+    shutdown::hooks().run();
+}
@@ -0,0 +1,73 @@
+// Observe-only audit trail for `audit_mode`.
+//
+// In audit mode RMM never moves or keeps the machine awake; each time it
+// would have acted, a JSON line describing the decision is appended to
+// `audit.log` next to `rmm.log` (and logged under the `rmm::audit` target).
+
+use crate::config::KeepAwakeMode;
+use crate::error::Result;
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+/// An action RMM decided on but did not carry out
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Decision {
+    /// Seconds the user had been idle
+    pub idle_secs: u64,
+    pub mode: KeepAwakeMode,
+    /// The relative cursor move that would have been made (mouse mode)
+    pub dx: i32,
+    pub dy: i32,
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    ts: String,
+    event: &'static str,
+    #[serde(flatten)]
+    decision: &'a Decision,
+}
+
+/// One audit line: a JSON object with the timestamp, `"event": "would_keep_awake"`
+/// and the decision's fields.
+pub fn format_record(decision: &Decision, at: DateTime<Local>) -> String {
+    let record = Record {
+        ts: at.to_rfc3339_opts(SecondsFormat::Secs, false),
+        event: "would_keep_awake",
+        decision,
+    };
+    serde_json::to_string(&record).unwrap_or_default()
+}
+
+/// Append one record for `decision` to the file at `path`.
+pub fn append(path: &Path, decision: &Decision, at: DateTime<Local>) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", format_record(decision, at))?;
+    Ok(())
+}
+
+fn audit_path() -> &'static OnceLock<PathBuf> {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    &PATH
+}
+
+/// Set the file [`record`] appends to. Only the first call has an effect.
+pub fn init(path: PathBuf) {
+    let _ = audit_path().set(path);
+}
+
+/// Log `decision` to the audit trail.
+pub fn record(decision: &Decision) {
+    let now = Local::now();
+    info!(target: "rmm::audit", "{}", format_record(decision, now));
+    if let Some(path) = audit_path().get() {
+        if let Err(e) = append(path, decision, now) {
+            warn!("Failed to write audit record to {}: {}", path.display(), e);
+        }
+    }
+}
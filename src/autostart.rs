@@ -0,0 +1,140 @@
+// Registering RMM as a login item when `auto_start` is set.
+//
+// On Linux this is an XDG autostart entry, on macOS a LaunchAgent plist and
+// on Windows a `Run` registry value. Managed machines may not want users
+// adding login items, so enabling only happens when explicitly permitted by
+// an environment variable or a sentinel file next to the config.
+
+use crate::config::Config;
+use crate::error::{Result, RmmError};
+use crate::platform;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Environment variable that permits enabling auto-start when set to `1` or `true`
+pub const ALLOW_ENV: &str = "RMM_ALLOW_AUTOSTART";
+
+/// File in the config directory whose presence permits enabling auto-start
+pub const ALLOW_FILE: &str = "allow_autostart";
+
+/// Name of the login item file inside the platform's login item directory
+#[cfg(target_os = "macos")]
+pub const LOGIN_ITEM_FILE: &str = "com.rmm.rmm.plist";
+#[cfg(not(target_os = "macos"))]
+pub const LOGIN_ITEM_FILE: &str = "rmm.desktop";
+
+/// Whether enabling auto-start is permitted, given the value of
+/// [`ALLOW_ENV`] and whether the [`ALLOW_FILE`] sentinel exists.
+pub fn is_permitted(env_value: Option<&str>, sentinel_exists: bool) -> bool {
+    let env_allows = env_value.is_some_and(|value| {
+        let value = value.trim();
+        value == "1" || value.eq_ignore_ascii_case("true")
+    });
+    env_allows || sentinel_exists
+}
+
+/// Whether `sync(enable)` should touch the login item at all. Disabling is
+/// always allowed; enabling needs permission.
+pub fn should_apply(enable: bool, permitted: bool) -> bool {
+    !enable || permitted
+}
+
+/// XDG desktop entry that starts `exe` at login
+pub fn desktop_entry(exe: &Path) -> String {
+    format!(
+        "[Desktop Entry]\nType=Application\nName=RMM\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\n",
+        exe.display()
+    )
+}
+
+/// LaunchAgent plist that starts `exe` at login
+pub fn launch_agent(exe: &Path) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.rmm.rmm</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        exe.display()
+    )
+}
+
+/// Content of [`LOGIN_ITEM_FILE`] for this platform
+pub fn login_item_content(exe: &Path) -> String {
+    if cfg!(target_os = "macos") {
+        launch_agent(exe)
+    } else {
+        desktop_entry(exe)
+    }
+}
+
+/// Make the login item file in `dir` match `enabled`: write it when enabled
+/// and missing or stale, remove it when disabled. Returns whether anything
+/// changed.
+pub fn sync_in(dir: &Path, enabled: bool, exe: &Path) -> Result<bool> {
+    let path = dir.join(LOGIN_ITEM_FILE);
+    if !enabled {
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(&path)?;
+        return Ok(true);
+    }
+    let content = login_item_content(exe);
+    if fs::read_to_string(&path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+    fs::create_dir_all(dir)?;
+    fs::write(&path, content)?;
+    Ok(true)
+}
+
+fn sentinel_exists() -> bool {
+    Config::config_path()
+        .ok()
+        .and_then(|path| path.parent().map(|dir| dir.join(ALLOW_FILE)))
+        .is_some_and(|sentinel| sentinel.exists())
+}
+
+/// Install or remove the login item for the running executable to match
+/// `enabled`. Enabling without permission is logged and skipped.
+pub fn sync(enabled: bool) -> Result<()> {
+    let permitted = is_permitted(std::env::var(ALLOW_ENV).ok().as_deref(), sentinel_exists());
+    if !should_apply(enabled, permitted) {
+        info!(
+            "auto_start is set but not permitted (set {}=1 or create {}), leaving login items alone",
+            ALLOW_ENV, ALLOW_FILE
+        );
+        return Ok(());
+    }
+    let exe = std::env::current_exe()?;
+
+    let Some(dir) = platform::login_item_dir() else {
+        // Windows: the Run key, left untouched when disabled and never added
+        if !platform::set_run_key(enabled, &exe) && enabled {
+            return Err(RmmError::Platform(
+                "Failed to add RMM to the Run registry key".into(),
+            ));
+        }
+        return Ok(());
+    };
+    if sync_in(&dir, enabled, &exe)? {
+        if enabled {
+            info!("Registered {} to start at login", exe.display());
+        } else {
+            info!("Removed the login item from {}", dir.display());
+        }
+    }
+    Ok(())
+}
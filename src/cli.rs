@@ -0,0 +1,54 @@
+use crate::error::{Result, RmmError};
+use std::path::PathBuf;
+
+/// Command-line options
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cli {
+    /// Run a single check-and-move based on OS idle time, then exit
+    pub once_and_exit: bool,
+    /// Print the active/inactive windows for the next 24 hours, then exit
+    pub show_schedule: bool,
+    /// Show a single, continuously updated status line instead of log output on stdout
+    pub status_line: bool,
+    /// Print the effective configuration as `RMM_*=value` lines, then exit
+    pub export_env: bool,
+    /// Skip the routine startup log lines (same as `quiet` in the config)
+    pub quiet: bool,
+    /// Config file to use instead of the default location
+    pub config: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Parse arguments, excluding the program name.
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut cli = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "--config" => {
+                    let path = args.next().ok_or_else(|| {
+                        RmmError::Config("--config needs a file path".to_string())
+                    })?;
+                    cli.config = Some(PathBuf::from(path.as_ref()));
+                }
+                "--once-and-exit" => cli.once_and_exit = true,
+                "--show-schedule" => cli.show_schedule = true,
+                "--status-line" => cli.status_line = true,
+                "--export-env" => cli.export_env = true,
+                "--quiet" => cli.quiet = true,
+                other => {
+                    return Err(RmmError::Config(format!("Unknown argument: {}", other)));
+                }
+            }
+        }
+        Ok(cli)
+    }
+
+    pub fn from_env() -> Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+}
@@ -0,0 +1,50 @@
+// Time source for the idle decision.
+//
+// `should_move` reads the time from the process-wide `Clock` in `shared()`.
+// It is the system's monotonic clock unless a test installs its own, e.g. one
+// it can move forward to make the user look idle.
+
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Instant;
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The real monotonic clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// The installed [`Clock`], replaceable at runtime
+pub struct SharedClock {
+    inner: RwLock<Arc<dyn Clock>>,
+}
+
+impl SharedClock {
+    /// Tell the time with `clock` from now on
+    pub fn install(&self, clock: Arc<dyn Clock>) {
+        *self.inner.write().unwrap_or_else(|e| e.into_inner()) = clock;
+    }
+
+    pub fn get(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.inner.read().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Process-wide clock, the system's until [`SharedClock::install`] is called
+pub fn shared() -> &'static SharedClock {
+    static CLOCK: OnceLock<SharedClock> = OnceLock::new();
+    CLOCK.get_or_init(|| SharedClock {
+        inner: RwLock::new(Arc::new(SystemClock)),
+    })
+}
+
+/// The current time according to the installed clock
+pub fn now() -> Instant {
+    shared().get().now()
+}
@@ -0,0 +1,205 @@
+use crate::config::{Config, KeepAwakeMode, SharedConfig};
+use crate::state::{Cause, SharedState};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Commands that change the running state, issued by the tray, hotkeys or IPC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Pause,
+    Resume,
+    Toggle,
+    /// Switch the keep-awake method; applied immediately, not debounced
+    SetMode(KeepAwakeMode),
+    /// Hold moves back for this long, or end the snooze with zero; applied
+    /// immediately
+    Snooze(Duration),
+    /// Change `heartbeat_interval` (seconds); applied immediately and saved
+    SetInterval(u64),
+}
+
+/// Heartbeat intervals offered in the tray (seconds)
+pub const INTERVAL_PRESETS: [u64; 4] = [30, 60, 120, 300];
+
+/// Snooze lengths offered in the tray
+pub const SNOOZE_MINUTES: [u64; 4] = [5, 15, 30, 60];
+
+/// Tray label of the pause/resume item: the action a click performs
+pub fn toggle_label(is_running: bool) -> &'static str {
+    if is_running {
+        "Pause"
+    } else {
+        "Resume"
+    }
+}
+
+/// Tray label of the snooze status item, which cancels the snooze when clicked
+pub fn snooze_label(remaining: Option<Duration>) -> String {
+    match remaining {
+        Some(left) => format!(
+            "Snoozed, {} min left (click to resume)",
+            left.as_secs().div_ceil(60)
+        ),
+        None => "Not snoozed".to_string(),
+    }
+}
+
+/// Applies running-state commands to the shared state.
+///
+/// Toggles arriving within `debounce` of each other are coalesced: only the
+/// final requested state is applied once the burst has settled.
+pub struct CommandProcessor {
+    state: SharedState,
+    debounce: Duration,
+    pending: Option<(bool, Cause, Instant)>,
+    transitions: u64,
+    config: Option<SharedConfig>,
+    save_path: Option<PathBuf>,
+}
+
+impl CommandProcessor {
+    pub fn new(state: SharedState, debounce: Duration) -> Self {
+        Self {
+            state,
+            debounce,
+            pending: None,
+            transitions: 0,
+            config: None,
+            save_path: None,
+        }
+    }
+
+    /// Apply settings changed by commands (keep-awake mode, heartbeat
+    /// interval) to the live `config`.
+    pub fn with_config(mut self, config: SharedConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Like [`CommandProcessor::with_config`], also writing the changed
+    /// config to `path`.
+    pub fn with_persistence(mut self, config: SharedConfig, path: PathBuf) -> Self {
+        self.save_path = Some(path);
+        self.with_config(config)
+    }
+
+    /// Queue a command from `cause` received at `now`. With a zero debounce it
+    /// is applied immediately. When a burst is coalesced, the last cause wins.
+    pub fn submit(&mut self, command: Command, cause: Cause, now: Instant) {
+        let current = match self.pending {
+            Some((running, _, _)) => running,
+            None => self.is_running(),
+        };
+        let target = match command {
+            Command::Pause => false,
+            Command::Resume => true,
+            Command::Toggle => !current,
+            Command::SetMode(mode) => {
+                self.set_mode(mode, cause);
+                return;
+            }
+            Command::SetInterval(secs) => {
+                self.set_interval(secs, cause);
+                return;
+            }
+            Command::Snooze(duration) => {
+                if let Ok(mut state) = self.state.lock() {
+                    state.snooze((!duration.is_zero()).then(|| now + duration));
+                }
+                return;
+            }
+        };
+        self.pending = Some((target, cause, now));
+
+        if self.debounce.is_zero() {
+            self.poll(now);
+        }
+    }
+
+    /// Apply the pending state if no further command arrived within the debounce window.
+    /// Returns the new running state when a transition actually happened.
+    pub fn poll(&mut self, now: Instant) -> Option<bool> {
+        let (target, cause, at) = self.pending?;
+        if now.duration_since(at) < self.debounce {
+            return None;
+        }
+        // Keep the toggle queued for the next poll if the state is unavailable
+        let Ok(mut state) = self.state.lock() else {
+            return None;
+        };
+        self.pending = None;
+
+        if !state.set_running(target, cause) {
+            return None;
+        }
+        self.transitions += 1;
+        info!("Monitoring {}", if target { "resumed" } else { "paused" });
+        Some(target)
+    }
+
+    fn set_mode(&mut self, mode: KeepAwakeMode, cause: Cause) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if state.keep_awake_mode == mode {
+            return;
+        }
+        info!(
+            from = state.keep_awake_mode.as_str(),
+            to = mode.as_str(),
+            cause = cause.as_str(),
+            "Keep-awake mode changed"
+        );
+        state.keep_awake_mode = mode;
+        drop(state);
+
+        self.update_config("keep-awake mode", "keep_awake_mode", &mode, |config| {
+            config.keep_awake_mode = mode
+        });
+    }
+
+    fn set_interval(&mut self, secs: u64, cause: Cause) {
+        if secs == 0 {
+            warn!("Ignoring a heartbeat interval of 0s");
+            return;
+        }
+        info!(secs, cause = cause.as_str(), "Heartbeat interval changed");
+        self.update_config(
+            "heartbeat interval",
+            "heartbeat_interval",
+            &secs,
+            |config| config.heartbeat_interval = secs,
+        );
+    }
+
+    /// Change the live config with `change` and, if configured to, save the
+    /// new `value` of `field` to the config file (and nothing else)
+    fn update_config<T: Serialize>(
+        &self,
+        what: &str,
+        field: &str,
+        value: &T,
+        change: impl FnOnce(&mut Config),
+    ) {
+        let Some(config) = &self.config else {
+            return;
+        };
+        change(&mut config.write().unwrap_or_else(|e| e.into_inner()));
+        if let Some(path) = &self.save_path {
+            if let Err(e) = Config::save_field(path, field, value) {
+                warn!("Failed to save {}: {}", what, e);
+            }
+        }
+    }
+
+    /// Number of running-state transitions actually applied.
+    pub fn transitions(&self) -> u64 {
+        self.transitions
+    }
+
+    fn is_running(&self) -> bool {
+        self.state.lock().map(|s| s.is_running).unwrap_or(false)
+    }
+}
@@ -1,55 +1,937 @@
 use crate::error::Result;
+use crate::movement::{MovementPattern, MovementStrategy};
+use crate::state::InputKind;
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
 use directories::ProjectDirs;
-use serde::{Deserialize, Serialize};
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Parse a duration such as `"30s"`, `"5m"`, `"1h"` or `"1h30m"` into seconds.
+/// A bare number is taken as seconds.
+pub fn parse_duration_secs(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse() {
+        return Some(secs);
+    }
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            _ => return None,
+        };
+        let value: u64 = digits.parse().ok()?;
+        total = total.checked_add(value.checked_mul(unit)?)?;
+        digits.clear();
+    }
+    // Trailing digits without a unit ("1h30") are ambiguous
+    if digits.is_empty() {
+        Some(total)
+    } else {
+        None
+    }
+}
+
+/// Seconds written either as a number or as a duration string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SecsOrText {
+    Secs(u64),
+    Text(String),
+}
+
+impl SecsOrText {
+    fn into_secs<E: serde::de::Error>(self) -> std::result::Result<u64, E> {
+        match self {
+            Self::Secs(secs) => Ok(secs),
+            Self::Text(text) => parse_duration_secs(&text)
+                .ok_or_else(|| E::custom(format!("invalid duration {:?}", text))),
+        }
+    }
+}
+
+fn deserialize_secs<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<u64, D::Error> {
+    SecsOrText::deserialize(deserializer)?.into_secs()
+}
+
+fn deserialize_opt_secs<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<u64>, D::Error> {
+    Option::<SecsOrText>::deserialize(deserializer)?
+        .map(SecsOrText::into_secs)
+        .transpose()
+}
+
+/// How RMM keeps the machine awake
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeepAwakeMode {
+    /// Move the mouse cursor
+    #[default]
+    Mouse,
+    /// Tap F15, a key nothing binds, for idle detectors that only watch the
+    /// keyboard or remote desktops that drop synthetic moves
+    Key,
+    /// Scroll one notch up and back down, for when pointer moves are distracting
+    Scroll,
+}
+
+impl KeepAwakeMode {
+    pub const ALL: [KeepAwakeMode; 3] = [
+        KeepAwakeMode::Mouse,
+        KeepAwakeMode::Key,
+        KeepAwakeMode::Scroll,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeepAwakeMode::Mouse => "mouse",
+            KeepAwakeMode::Key => "key",
+            KeepAwakeMode::Scroll => "scroll",
+        }
+    }
+}
+
+/// What to do when no display is connected (headless or unplugged machine)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoDisplayAction {
+    /// Keep trying to move as usual
+    #[default]
+    Ignore,
+    /// Skip heartbeats until a display appears again
+    Dormant,
+    /// Exit the application
+    Quit,
+}
+
+/// Where user activity is read from
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityMode {
+    /// Global input hooks (`rdev`), plus the OS idle time
+    #[default]
+    Listener,
+    /// Only poll the OS idle time, for systems where global hooks are
+    /// forbidden or crash
+    #[serde(alias = "system")]
+    Poll,
+}
+
+/// Velocity profile used when a move is split into several steps
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Accelerate then decelerate, like real pointer motion
+    #[default]
+    EaseInOut,
+}
+
+/// Text shown by the tray "About" dialog
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct TrayConfig {
+    pub about_title: String,
+    pub app_name: String,
+    pub author: String,
+    pub description: String,
+}
+
+impl Default for TrayConfig {
+    fn default() -> Self {
+        Self {
+            about_title: "About RMM".into(),
+            app_name: "RMM - Rust Mouse Monitor".into(),
+            author: "Red".into(),
+            description: "Created with LLM help for learning Rust concepts".into(),
+        }
+    }
+}
+
+/// Local HTTP control/status API (`GET /status`, `POST /pause`, ...)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct HttpApiConfig {
+    pub enabled: bool,
+    /// Address to listen on; only local clients can connect by default
+    pub bind: IpAddr,
+    pub port: u16,
+    /// Secret clients send as `Authorization: Bearer <token>`; the API isn't
+    /// served without one
+    pub token: Option<String>,
+    /// Names clients may use in the `Host` header besides `localhost`, the
+    /// loopback addresses and `bind`, for a non-loopback `bind`
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for HttpApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: 8765,
+            token: None,
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+impl TrayConfig {
+    /// Assemble the About dialog body from the configured text and runtime info.
+    pub fn about_text(&self, version: &str, uptime: Duration) -> String {
+        format!(
+            "{}\n\nAuthor: {}\n\n{}\n\nVersion: {}\nUptime: {}",
+            self.app_name,
+            self.author,
+            self.description,
+            version,
+            format_uptime(uptime)
+        )
+    }
+}
+
+/// Format a duration as e.g. "2d 3h 04m", "1h 05m" or "7m"
+pub fn format_uptime(uptime: Duration) -> String {
+    let total_minutes = uptime.as_secs() / 60;
+    let (days, hours, minutes) = (
+        total_minutes / 1440,
+        (total_minutes / 60) % 24,
+        total_minutes % 60,
+    );
+    if days > 0 {
+        format!("{}d {}h {:02}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Direction of the first move after startup
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InitialDirection {
+    #[default]
+    Positive,
+    Negative,
+    /// Pick either direction at random, so restarts are less predictable
+    Random,
+}
+
+impl InitialDirection {
+    /// Resolve to a move direction of `1` or `-1`.
+    pub fn resolve<R: Rng + ?Sized>(self, rng: &mut R) -> i32 {
+        match self {
+            Self::Positive => 1,
+            Self::Negative => -1,
+            Self::Random => {
+                if rng.gen_bool(0.5) {
+                    1
+                } else {
+                    -1
+                }
+            }
+        }
+    }
+}
+
+/// Screen rectangle (in pixels) that moves are confined to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Region {
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Nearest point inside the region
+    pub fn clamp(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        (
+            x.clamp(self.x, self.x + (self.width - 1).max(0)),
+            y.clamp(self.y, self.y + (self.height - 1).max(0)),
+        )
+    }
+}
+
+/// A recurring stretch of local time, such as a maintenance window during
+/// which RMM stays fully paused or the working hours it is limited to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    /// Earlier than `start` for a window that runs past midnight; equal to
+    /// `start` for the whole day
+    pub end: NaiveTime,
+    /// Days the window starts on; empty means every day
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let time = at.time();
+        let today = at.date().weekday();
+        let starts_on = |day: Weekday| self.days.is_empty() || self.days.contains(&day);
+        if self.start == self.end {
+            starts_on(today)
+        } else if self.start < self.end {
+            starts_on(today) && time >= self.start && time < self.end
+        } else {
+            // The late part started today, the early part yesterday
+            (time >= self.start && starts_on(today)) || (time < self.end && starts_on(today.pred()))
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(default = "Config::platform_defaults")]
 pub struct Config {
+    /// Layout version of the file this came from; 0 for files written before
+    /// versioning (see [`Config::migrate`])
+    #[serde(default)]
+    pub version: u32,
+    #[serde(deserialize_with = "deserialize_secs")]
     pub heartbeat_interval: u64,
+    #[serde(deserialize_with = "deserialize_secs")]
     pub worker_interval: u64,
+    #[serde(deserialize_with = "deserialize_secs")]
     pub inactivity_threshold: u64,
     pub movement_delta: i32,
+    /// Smallest movement (pixels) allowed at the point of use
+    pub min_movement_delta: i32,
+    /// Largest movement (pixels) allowed at the point of use
+    pub max_movement_delta: i32,
+    /// Hard cap (pixels per axis) on the final move, whatever computed it
+    pub max_move_distance: u32,
     pub max_errors: u32,
+    /// Wait after a failed move before trying again (milliseconds), doubled per
+    /// consecutive failure; 0 retries on the next heartbeat
+    pub failure_backoff_ms: u64,
+    /// Pause when failures reach `max_errors`, until resumed from the tray
+    pub disable_on_max_errors: bool,
+    /// Show a desktop notification when failures first reach `max_errors`
+    pub notifications_enabled: bool,
     pub auto_start: bool,
+    /// Pause/resume toggles closer together than this are coalesced (milliseconds)
+    pub toggle_debounce_ms: u64,
+    /// Upper bound on synthetic moves per minute across all triggers (0 = unlimited)
+    pub max_moves_per_minute: u32,
+    /// What to do while no display is connected
+    pub no_display_action: NoDisplayAction,
+    /// Per-input idle timeouts in seconds, overriding `inactivity_threshold` for that input kind
+    pub source_timeouts: BTreeMap<InputKind, u64>,
+    /// Number of intermediate steps per move (1 = jump straight to the target)
+    pub smooth_move_steps: u32,
+    pub easing: Easing,
+    pub pause_when_lid_closed: bool,
+    /// Only move while at least one display is powered on
+    pub require_display_on: bool,
+    /// Keep moving while the session is locked
+    pub move_when_locked: bool,
+    /// Don't move while the focused app is fullscreen (slideshow, video, game)
+    pub pause_on_fullscreen: bool,
+    /// Don't move while one of these processes has focus (case-insensitive)
+    pub blocklist_processes: Vec<String>,
+    /// Don't move while a mouse button is held, so drags aren't disrupted
+    pub skip_while_button_held: bool,
+    /// Treat an OS "user present" signal as activity, where the platform has one
+    pub respect_presence_api: bool,
+    /// Verify moves by the distance travelled instead of the absolute target position
+    pub verify_by_delta: bool,
+    /// Require the cursor to land exactly on target (no pixel tolerance)
+    pub strict_verify: bool,
+    /// Also alternate direction after a failed move, not only after a verified one
+    pub flip_on_failure: bool,
+    /// Vary the verify tolerance by up to this many pixels either way per check
+    pub verify_tolerance_jitter: u32,
+    /// Milliseconds to wait after a move before reading the cursor back
+    pub verify_delay_ms: u64,
+    /// Poll for the expected position for up to this many milliseconds instead
+    /// of a single check after `verify_delay_ms`
+    pub verify_timeout_ms: Option<u64>,
+    /// Milliseconds between position checks when `verify_timeout_ms` is set
+    pub verify_poll_ms: u64,
+    /// Also require the activity listener to report the move within this
+    /// many milliseconds, confirming the OS delivered the event
+    pub listener_verify_ms: Option<u64>,
+    /// Seconds to wait after resuming from sleep before moving again
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub post_wake_grace_secs: u64,
+    pub tray: TrayConfig,
+    pub http_api: HttpApiConfig,
+    /// Skip heartbeats while the 1-minute load average is above this (Unix only)
+    pub max_load_average: Option<f64>,
+    /// Keep the cursor inside this rectangle when moving
+    pub move_region: Option<Region>,
+    /// Times when RMM never acts, whatever the idle time
+    pub maintenance_windows: Vec<TimeWindow>,
+    /// Start of the working hours RMM is limited to (local time)
+    pub active_start: Option<NaiveTime>,
+    /// End of the working hours; earlier than `active_start` for an overnight shift
+    pub active_end: Option<NaiveTime>,
+    /// Days the working hours start on; empty means every day
+    pub active_days: Vec<Weekday>,
+    /// Seconds after the last keystroke before the user counts as idle, so
+    /// pauses while typing aren't mistaken for absence
+    #[serde(deserialize_with = "deserialize_opt_secs")]
+    pub keyboard_idle_threshold: Option<u64>,
+    /// Only move while the pointer is idle but the keyboard was used within
+    /// this many seconds (the user is at the desk typing)
+    #[serde(deserialize_with = "deserialize_opt_secs")]
+    pub typing_presence_window: Option<u64>,
+    pub initial_direction: InitialDirection,
+    /// Patterns to pick from at random for each move (empty = diagonal only)
+    pub pattern_set: Vec<MovementPattern>,
+    /// Nudge back and forth (`linear`) or circle around the cursor (`circle`)
+    pub movement_pattern: MovementStrategy,
+    /// After a move, follow up with tiny moves so this many happen per
+    /// heartbeat interval, evenly spaced (1 = a single move)
+    pub moves_per_interval: u32,
+    /// Where user activity is read from: the input listener or OS idle polling
+    #[serde(alias = "idle_source")]
+    pub activity_mode: ActivityMode,
+    /// Initial keep-awake method; can be switched at runtime from the tray
+    #[serde(alias = "simulation_mode")]
+    pub keep_awake_mode: KeepAwakeMode,
+    /// Observe only: never move or keep awake, record what would have been
+    /// done to `audit.log` instead
+    pub audit_mode: bool,
+    /// Skip the routine INFO lines logged at startup
+    pub quiet: bool,
+    /// Log verified moves at DEBUG instead of INFO
+    pub quiet_move_logs: bool,
+    /// In quiet mode, log a summary at INFO every this many verified moves (0 = never)
+    pub move_summary_every: u64,
+    /// Log "user active, no action needed" every this many consecutive skipped checks (0 = never)
+    pub liveness_log_every: u64,
+    /// Seed for all randomized behavior, for reproducible runs (unset = OS entropy)
+    pub random_seed: Option<u64>,
+    /// Truncate rmm.log to its last this many bytes when it grows larger
+    pub log_max_bytes: Option<u64>,
+    /// Cursor speed (pixels/second) that, sustained, marks the user as present
+    pub high_velocity_threshold: Option<f64>,
+    /// Seconds to hold moves off after sustained fast mouse movement
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub high_velocity_cooldown_secs: u64,
+    /// Restart the heartbeat thread if it panics
+    pub restart_on_panic: bool,
+    /// Pause while another process holds an advisory lock on this file
+    pub pause_lock_file: Option<PathBuf>,
+    /// After this much idle time, lock the screen and stop jiggling
+    #[serde(deserialize_with = "deserialize_opt_secs")]
+    pub lock_after_secs: Option<u64>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             heartbeat_interval: 10,
             worker_interval: 10,
             inactivity_threshold: 10,
             movement_delta: 10,
+            min_movement_delta: 1,
+            max_movement_delta: 200,
+            max_move_distance: 200,
             max_errors: 10,
+            failure_backoff_ms: 5000,
+            disable_on_max_errors: true,
+            notifications_enabled: true,
             auto_start: false,
+            toggle_debounce_ms: 300,
+            max_moves_per_minute: 30,
+            no_display_action: NoDisplayAction::Ignore,
+            source_timeouts: BTreeMap::new(),
+            smooth_move_steps: 1,
+            easing: Easing::EaseInOut,
+            pause_when_lid_closed: false,
+            require_display_on: false,
+            move_when_locked: false,
+            pause_on_fullscreen: false,
+            blocklist_processes: Vec::new(),
+            skip_while_button_held: true,
+            respect_presence_api: false,
+            verify_by_delta: false,
+            strict_verify: false,
+            flip_on_failure: false,
+            verify_tolerance_jitter: 0,
+            verify_delay_ms: 100,
+            verify_timeout_ms: None,
+            verify_poll_ms: 20,
+            listener_verify_ms: None,
+            post_wake_grace_secs: 60,
+            tray: TrayConfig::default(),
+            http_api: HttpApiConfig::default(),
+            max_load_average: None,
+            move_region: None,
+            maintenance_windows: Vec::new(),
+            active_start: None,
+            active_end: None,
+            active_days: Vec::new(),
+            keyboard_idle_threshold: None,
+            typing_presence_window: None,
+            initial_direction: InitialDirection::Positive,
+            pattern_set: Vec::new(),
+            movement_pattern: MovementStrategy::Linear,
+            moves_per_interval: 1,
+            activity_mode: ActivityMode::Listener,
+            keep_awake_mode: KeepAwakeMode::Mouse,
+            audit_mode: false,
+            quiet: false,
+            quiet_move_logs: false,
+            move_summary_every: 100,
+            liveness_log_every: 30,
+            random_seed: None,
+            log_max_bytes: None,
+            high_velocity_threshold: None,
+            high_velocity_cooldown_secs: 60,
+            restart_on_panic: false,
+            pause_lock_file: None,
+            lock_after_secs: None,
         }
     }
 }
 
+/// Serialize `value` and replace the file at `path` with it.
+///
+/// Serialization happens before anything is written, and the content goes to a
+/// temporary file next to `path` that is renamed over it, so a failure at any
+/// point leaves the existing file untouched (and no temporary file behind).
+/// The content is flushed to disk before the rename, and on Unix the file is
+/// readable by its owner only, since it may hold schedules or webhook URLs.
+pub fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let content = serde_json::to_string_pretty(value)?;
+    write_atomic(path, &content)
+}
+
+/// Replace the file at `path` with `content`, as [`write_json_atomic`] does
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| crate::error::RmmError::Config(format!("Not a file path: {:?}", path)))?;
+    let temp = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    let result = write_private(&temp, content.as_bytes()).and_then(|()| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    Ok(result?)
+}
+
+/// Create (or truncate) `path` with owner-only permissions and write `content`
+/// through to disk
+fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    // A leftover temp file from an earlier crash keeps its old mode otherwise
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content)?;
+    file.sync_all()
+}
+
+/// Old field names still accepted on load, as (field, alias)
+const FIELD_ALIASES: [(&str, &str); 2] = [
+    ("activity_mode", "idle_source"),
+    ("keep_awake_mode", "simulation_mode"),
+];
+
+/// Current layout version of the config file
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Prefix of environment variables that override config fields
+pub const ENV_PREFIX: &str = "RMM_";
+
+/// Overrides the config file location. Not a config field, so ignored as an override.
+pub const CONFIG_PATH_ENV: &str = "RMM_CONFIG";
+
+/// `value` in POSIX shell single quotes, unless it has nothing a shell would
+/// split or expand
+fn shell_quote(value: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_-.,:/@%+".contains(c);
+    if value.chars().all(plain) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Live configuration shared between the heartbeat and control interfaces
+pub type SharedConfig = Arc<RwLock<Config>>;
+
+/// One field that differs between two configurations
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ConfigChange {
+    pub field: String,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
 impl Config {
+    /// Per-input timeouts actually in effect: `source_timeouts` plus the
+    /// dedicated `keyboard_idle_threshold` (an explicit keyboard entry wins).
+    pub fn effective_source_timeouts(&self) -> BTreeMap<InputKind, u64> {
+        let mut timeouts = self.source_timeouts.clone();
+        if let Some(keyboard) = self.keyboard_idle_threshold {
+            timeouts.entry(InputKind::Keyboard).or_insert(keyboard);
+        }
+        timeouts
+    }
+
+    /// Defaults tuned for the current platform, used as the base that the
+    /// config file overrides. `Default` stays platform-neutral.
+    pub fn platform_defaults() -> Self {
+        let mut config = Self::default();
+        if cfg!(target_os = "macos") {
+            // The window server can take a while to report the new cursor position
+            config.verify_delay_ms = 250;
+        }
+        if cfg!(target_os = "windows") {
+            // Wake-from-sleep on Windows often replays input and reconnects displays slowly
+            config.post_wake_grace_secs = 120;
+        }
+        config
+    }
+
     pub fn load() -> Result<Self> {
-        let path = Self::config_path()?;
+        Self::load_at(&Self::config_path()?)
+    }
+
+    /// [`Config::load`] for the file at `path`: read it (or use defaults),
+    /// migrate an older file and save it back, then apply `RMM_*` overrides
+    /// and validate.
+    pub fn load_at(path: &Path) -> Result<Self> {
+        let mut config = Self::resolve(path)?;
+        if path.exists() && config.migrate() {
+            config.save_migrated(path);
+        }
+        config.apply_env_overrides(std::env::vars())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Bring a config read from an older file up to [`CONFIG_VERSION`].
+    /// Fields added since were already filled with defaults when parsing;
+    /// renames and other changes go here, one step per version. Returns
+    /// whether anything changed.
+    pub fn migrate(&mut self) -> bool {
+        if self.version >= CONFIG_VERSION {
+            return false;
+        }
+        // 0 -> 1: versioning introduced, nothing renamed
+        self.version = CONFIG_VERSION;
+        true
+    }
+
+    /// Write a migrated config back so the file shows every current field.
+    /// TOML files are left alone rather than rewritten as JSON.
+    fn save_migrated(&self, path: &Path) {
+        let is_json = fs::read_to_string(path)
+            .is_ok_and(|raw| serde_json::from_str::<serde_json::Value>(&raw).is_ok());
+        if !is_json {
+            info!(
+                "{} predates config version {}; add `version = {}` to stop this message",
+                path.display(),
+                CONFIG_VERSION,
+                CONFIG_VERSION
+            );
+            return;
+        }
+        match self.save_to(path) {
+            Ok(()) => info!(
+                "Migrated {} to config version {}",
+                path.display(),
+                CONFIG_VERSION
+            ),
+            Err(e) => warn!("Failed to save migrated config {}: {}", path.display(), e),
+        }
+    }
+
+    /// The working hours from `active_start`, `active_end` and `active_days`,
+    /// or `None` when none is set and RMM is active around the clock. A
+    /// missing start or end means midnight.
+    pub fn working_hours(&self) -> Option<TimeWindow> {
+        if self.active_start.is_none() && self.active_end.is_none() && self.active_days.is_empty() {
+            return None;
+        }
+        Some(TimeWindow {
+            start: self.active_start.unwrap_or(NaiveTime::MIN),
+            end: self.active_end.unwrap_or(NaiveTime::MIN),
+            days: self.active_days.clone(),
+        })
+    }
+
+    /// Reject values that would break the loops: zero intervals or threshold
+    /// (a spinning heartbeat, or moving while the user works), a zero
+    /// `movement_delta`, `min_movement_delta` above `max_movement_delta` and
+    /// `max_errors` below 1.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: &str| Err(crate::error::RmmError::Config(message.to_string()));
+        if self.heartbeat_interval == 0 {
+            return invalid("heartbeat_interval must be at least 1 second");
+        }
+        if self.worker_interval == 0 {
+            return invalid("worker_interval must be at least 1 second");
+        }
+        if self.inactivity_threshold == 0 {
+            return invalid("inactivity_threshold must be at least 1 second");
+        }
+        if self.movement_delta == 0 {
+            return invalid("movement_delta must not be 0");
+        }
+        if self.min_movement_delta > self.max_movement_delta {
+            return invalid(&format!(
+                "min_movement_delta ({}) must not exceed max_movement_delta ({})",
+                self.min_movement_delta, self.max_movement_delta
+            ));
+        }
+        if self.max_errors == 0 {
+            return invalid("max_errors must be at least 1");
+        }
+        if self.verify_poll_ms == 0 {
+            return invalid("verify_poll_ms must be at least 1 millisecond");
+        }
+        Ok(())
+    }
+
+    /// Override fields from `RMM_<FIELD>` variables (e.g. `RMM_INACTIVITY_THRESHOLD=300`).
+    ///
+    /// Values are taken as JSON (numbers, booleans, objects), falling back to a
+    /// plain string; an empty value clears an optional field. Variables that
+    /// don't name a config field are ignored.
+    pub fn apply_env_overrides<I>(&mut self, vars: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let serde_json::Value::Object(mut fields) = serde_json::to_value(&*self)? else {
+            return Ok(());
+        };
+        let mut applied = false;
+        for (name, raw) in vars {
+            let Some(field) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let field = field.to_ascii_lowercase();
+            let Some(current) = fields.get_mut(&field) else {
+                continue;
+            };
+            *current = if raw.is_empty() {
+                serde_json::Value::Null
+            } else if current.is_string() {
+                serde_json::Value::String(raw)
+            } else {
+                serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw))
+            };
+            applied = true;
+        }
+        if applied {
+            *self = serde_json::from_value(serde_json::Value::Object(fields)).map_err(|e| {
+                crate::error::RmmError::Config(format!("Invalid {}* override: {}", ENV_PREFIX, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// The configuration as `RMM_<FIELD>=value` lines, the inverse of
+    /// [`Config::apply_env_overrides`]. Values are quoted where a POSIX shell
+    /// needs it, so the lines can be sourced.
+    pub fn to_env_lines(&self) -> Vec<String> {
+        let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(self) else {
+            return Vec::new();
+        };
+        let mut lines: Vec<String> = fields
+            .into_iter()
+            .map(|(field, value)| {
+                let value = match value {
+                    serde_json::Value::Null => String::new(),
+                    serde_json::Value::String(text) => text,
+                    other => other.to_string(),
+                };
+                format!(
+                    "{}{}={}",
+                    ENV_PREFIX,
+                    field.to_ascii_uppercase(),
+                    shell_quote(&value)
+                )
+            })
+            .collect();
+        lines.sort();
+        lines
+    }
+
+    /// Read the configuration at `path`, using defaults when the file doesn't exist.
+    pub fn resolve(path: &Path) -> Result<Self> {
         if path.exists() {
-            let content = fs::read_to_string(&path)?;
-            Ok(serde_json::from_str(&content)?)
+            Self::load_from(path)
         } else {
-            Ok(Self::default())
+            Ok(Self::platform_defaults())
         }
     }
 
-    #[allow(dead_code)]
+    /// Read the configuration file at `path` as JSON or TOML, whatever its extension.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+            .map_err(|e| crate::error::RmmError::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Parse configuration text, trying JSON first and then TOML.
+    pub fn parse(content: &str) -> Result<Self> {
+        let json_err = match serde_json::from_str(content) {
+            Ok(config) => return Ok(config),
+            Err(e) => e,
+        };
+        let toml_err = match toml::from_str(content) {
+            Ok(config) => return Ok(config),
+            Err(e) => e,
+        };
+        Err(crate::error::RmmError::Config(format!(
+            "not valid JSON ({}) or TOML ({})",
+            json_err,
+            toml_err.message()
+        )))
+    }
+
+    /// Top-level fields whose values differ from `self` in `other`, by name.
+    pub fn diff(&self, other: &Config) -> Vec<ConfigChange> {
+        let (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) =
+            (serde_json::to_value(self), serde_json::to_value(other))
+        else {
+            return Vec::new();
+        };
+        let mut changes: Vec<ConfigChange> = new
+            .into_iter()
+            // The layout version is bookkeeping, not a setting
+            .filter(|(field, value)| field != "version" && old.get(field) != Some(value))
+            .map(|(field, value)| ConfigChange {
+                old: old.get(&field).cloned().unwrap_or(serde_json::Value::Null),
+                field,
+                new: value,
+            })
+            .collect();
+        changes.sort_by(|a, b| a.field.cmp(&b.field));
+        changes
+    }
+
+    /// Re-read the file at `path` and swap it into `shared`, returning what
+    /// changed. `RMM_*` overrides are applied again, as at startup. A missing
+    /// file is an error, not a reset to defaults; on error the live
+    /// configuration is left untouched.
+    pub fn reload(shared: &SharedConfig, path: &Path) -> Result<Vec<ConfigChange>> {
+        let mut fresh = Self::load_from(path)?;
+        fresh.migrate();
+        fresh.apply_env_overrides(std::env::vars())?;
+        fresh.validate()?;
+        let mut live = shared
+            .write()
+            .map_err(|e| crate::error::RmmError::Config(format!("Config lock poisoned: {}", e)))?;
+        let changes = live.diff(&fresh);
+        *live = fresh;
+        Ok(changes)
+    }
+
     pub fn save(&self) -> Result<()> {
-        let path = Self::config_path()?;
+        self.save_to(&Self::config_path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(&path, content)?;
-        Ok(())
+        write_json_atomic(path, self)
     }
 
-    fn config_path() -> Result<PathBuf> {
+    /// Set the top-level `field` of the config file at `path` to `value`,
+    /// leaving the rest of the file as written. Unlike [`Config::save_to`],
+    /// nothing that only lives in memory (`RMM_*` overrides, platform
+    /// defaults) ends up in the file. A TOML file stays TOML; a missing file
+    /// is created with just this field.
+    pub fn save_field<T: Serialize>(path: &Path, field: &str, value: &T) -> Result<()> {
+        let invalid = |message: String| {
+            crate::error::RmmError::Config(format!("{}: {}", path.display(), message))
+        };
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => "{}".to_string(),
+            Err(e) => return Err(e.into()),
+        };
+        // The old name would be read alongside the new one as a duplicate
+        let aliases = FIELD_ALIASES
+            .iter()
+            .filter(|(name, _)| *name == field)
+            .map(|(_, alias)| *alias);
+
+        if let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_str(&content) {
+            aliases.for_each(|alias| {
+                fields.remove(alias);
+            });
+            fields.insert(field.to_string(), serde_json::to_value(value)?);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            return write_json_atomic(path, &fields);
+        }
+        let mut table: toml::Table =
+            toml::from_str(&content).map_err(|e| invalid(e.message().to_string()))?;
+        aliases.for_each(|alias| {
+            table.remove(alias);
+        });
+        let value = toml::Value::try_from(value).map_err(|e| invalid(e.to_string()))?;
+        table.insert(field.to_string(), value);
+        write_atomic(
+            path,
+            &toml::to_string(&table).map_err(|e| invalid(e.to_string()))?,
+        )
+    }
+
+    /// Where the configuration file lives: `RMM_CONFIG` if set (`--config`
+    /// sets it too), otherwise the per-user config directory.
+    pub fn config_path() -> Result<PathBuf> {
+        Self::config_path_from(std::env::var_os(CONFIG_PATH_ENV))
+    }
+
+    /// [`Config::config_path`] with the override given explicitly; an empty
+    /// override counts as unset.
+    pub fn config_path_from(path_override: Option<OsString>) -> Result<PathBuf> {
+        if let Some(path) = path_override.filter(|path| !path.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
         ProjectDirs::from("com", "rmm", "rmm")
             .map(|dirs| dirs.config_dir().join("config.json"))
             .ok_or_else(|| crate::error::RmmError::Config("Cannot find config directory".into()))
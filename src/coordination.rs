@@ -0,0 +1,69 @@
+// Coordination with other automation through an advisory lock file.
+
+use std::fs::{File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Whether another process currently holds an advisory lock on `path`.
+///
+/// Probes by trying to take the lock ourselves without blocking (and releasing
+/// it straight away). A missing or unreadable file counts as not locked.
+pub fn is_locked(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    match file.try_lock_shared() {
+        Ok(()) => {
+            let _ = file.unlock();
+            false
+        }
+        Err(TryLockError::WouldBlock) => true,
+        Err(TryLockError::Error(e)) => {
+            warn!("Failed to probe lock file {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// Rate-limited `is_locked` check, re-probing at most once per interval.
+#[derive(Debug, Default)]
+pub struct LockCheck {
+    last: Option<(PathBuf, Instant, bool)>,
+}
+
+impl LockCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether moves should pause because `path` is locked, re-checking the
+    /// lock when `every` has passed since the last probe (or the path changed).
+    pub fn is_paused(&mut self, path: Option<&Path>, every: Duration, now: Instant) -> bool {
+        let Some(path) = path else {
+            self.last = None;
+            return false;
+        };
+        if let Some((last_path, at, locked)) = &self.last {
+            if last_path == path && now.saturating_duration_since(*at) < every {
+                return *locked;
+            }
+        }
+
+        let locked = is_locked(path);
+        let was_locked = self.last.as_ref().is_some_and(|(_, _, l)| *l);
+        if locked != was_locked {
+            info!(
+                "Lock file {} {}",
+                path.display(),
+                if locked {
+                    "held, pausing"
+                } else {
+                    "released, resuming"
+                }
+            );
+        }
+        self.last = Some((path.to_path_buf(), now, locked));
+        locked
+    }
+}
@@ -0,0 +1,94 @@
+// What the desktop session is doing, as far as it decides whether to move.
+//
+// `check_and_move` asks the process-wide `Desktop` from `shared()`. It is the
+// OS (see `crate::platform`) unless a test installs a stub.
+
+use crate::platform;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Desktop session queries. Each is best effort and returns `None` when the
+/// answer isn't known.
+pub trait Desktop: Send + Sync {
+    /// Whether the session is locked
+    fn session_locked(&self) -> Option<bool>;
+    /// Whether the focused app is fullscreen (presentation, video, game)
+    fn fullscreen_app_active(&self) -> Option<bool>;
+    /// Name of the process owning the focused window
+    fn focused_process_name(&self) -> Option<String>;
+}
+
+/// The real desktop, via [`crate::platform`]
+pub struct SystemDesktop;
+
+impl Desktop for SystemDesktop {
+    fn session_locked(&self) -> Option<bool> {
+        platform::is_session_locked()
+    }
+
+    fn fullscreen_app_active(&self) -> Option<bool> {
+        platform::is_fullscreen_app_active()
+    }
+
+    fn focused_process_name(&self) -> Option<String> {
+        platform::focused_process_name()
+    }
+}
+
+/// The installed [`Desktop`], replaceable at runtime
+pub struct SharedDesktop {
+    inner: RwLock<Arc<dyn Desktop>>,
+}
+
+impl SharedDesktop {
+    /// Answer queries with `desktop` from now on
+    pub fn install(&self, desktop: Arc<dyn Desktop>) {
+        *self.inner.write().unwrap_or_else(|e| e.into_inner()) = desktop;
+    }
+
+    pub fn get(&self) -> Arc<dyn Desktop> {
+        Arc::clone(&self.inner.read().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Process-wide desktop, the OS until [`SharedDesktop::install`] is called
+pub fn shared() -> &'static SharedDesktop {
+    static DESKTOP: OnceLock<SharedDesktop> = OnceLock::new();
+    DESKTOP.get_or_init(|| SharedDesktop {
+        inner: RwLock::new(Arc::new(SystemDesktop)),
+    })
+}
+
+/// Whether to hold a move back because the session is locked. An unknown
+/// lock state never blocks moves. `locked` is only queried when
+/// `move_when_locked` is off.
+pub fn should_skip_for_lock(move_when_locked: bool, locked: impl FnOnce() -> Option<bool>) -> bool {
+    !move_when_locked && locked() == Some(true)
+}
+
+/// Whether to hold a move back because a fullscreen app has focus. Unknown
+/// (e.g. native Wayland apps) never blocks moves. `fullscreen` is only
+/// queried when `pause_on_fullscreen` is set.
+pub fn should_pause_for_fullscreen(
+    pause_on_fullscreen: bool,
+    fullscreen: impl FnOnce() -> Option<bool>,
+) -> bool {
+    pause_on_fullscreen && fullscreen() == Some(true)
+}
+
+/// Whether `process` matches a `blocklist` entry. Matching ignores case and a
+/// trailing ".exe", so "zoom" also covers "Zoom.exe". An unknown process
+/// never matches.
+pub fn is_blocklisted(blocklist: &[String], process: Option<&str>) -> bool {
+    let Some(process) = process else {
+        return false;
+    };
+    let stem = |name: &str| {
+        let name = name.trim().to_lowercase();
+        match name.strip_suffix(".exe") {
+            Some(stem) => stem.to_string(),
+            None => name,
+        }
+    };
+    let process = stem(process);
+    blocklist.iter().any(|entry| stem(entry) == process)
+}
@@ -13,6 +13,8 @@ pub enum RmmError {
     Config(String),
     #[error("Platform-specific error: {0}")]
     Platform(String),
+    #[error("Another RMM instance is already running ({0})")]
+    AlreadyRunning(String),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Serialization error: {0}")]
@@ -0,0 +1,84 @@
+// Bounded in-memory log of recent significant events, served at `GET /events`.
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// How many events are kept before the oldest are dropped
+pub const EVENT_LOG_CAPACITY: usize = 200;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Move,
+    Pause,
+    Resume,
+    Error,
+    Reload,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Event {
+    pub at: DateTime<Local>,
+    pub kind: EventKind,
+    pub detail: String,
+}
+
+/// Ring buffer of the most recent events, oldest first
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    events: VecDeque<Event>,
+    capacity: usize,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(EVENT_LOG_CAPACITY)
+    }
+}
+
+impl EventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append an event, evicting the oldest once full.
+    pub fn push(&mut self, kind: EventKind, detail: impl Into<String>, at: DateTime<Local>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(Event {
+            at,
+            kind,
+            detail: detail.into(),
+        });
+    }
+
+    /// [`EventLog::push`] stamped with the current time
+    pub fn record(&mut self, kind: EventKind, detail: impl Into<String>) {
+        self.push(kind, detail, Local::now());
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter()
+    }
+
+    /// `{"events": [{"at": ..., "kind": ..., "detail": ...}, ...]}`, oldest first
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({ "events": self.events })
+    }
+}
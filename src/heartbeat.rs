@@ -0,0 +1,377 @@
+use crate::activity;
+use crate::api::{self, ApiContext};
+use crate::config::{ActivityMode, Config, SharedConfig};
+use crate::coordination::LockCheck;
+use crate::error::{Result, RmmError};
+use crate::events::EventKind;
+use crate::http;
+use crate::logging;
+use crate::mouse::{self, DisplayGate, LazyController, MouseBackend, MouseController, MoveOutcome};
+use crate::platform;
+use crate::power::{self, WakeDetector};
+use crate::rng;
+use crate::shutdown::{self, ShutdownSignal};
+use crate::sources::{self, OsIdleSource, SharedSources, SourceRegistry};
+use crate::state::{Cause, SharedState};
+use crate::system;
+use crate::worker::Worker;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::mpsc as std_mpsc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, error, info, warn};
+
+/// What the caller should do after a heartbeat tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickOutcome {
+    Continue,
+    Quit,
+}
+
+fn read_config(config: &SharedConfig) -> Config {
+    config.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// When the follow-up moves of a burst happen, relative to the first move:
+/// `moves` evenly spaced across `interval`, excluding the first at zero.
+pub fn sub_tick_offsets(interval: Duration, moves: u32) -> Vec<Duration> {
+    if moves <= 1 {
+        return Vec::new();
+    }
+    (1..moves).map(|i| interval * i / moves).collect()
+}
+
+/// Per-heartbeat work: wake detection, display gating and the move check.
+/// Shared by the thread-based loop in [`crate::app`] and [`run_async`]. Settings are
+/// re-read from the shared config on every tick, so reloads take effect.
+/// Housekeeping runs separately on `worker_interval` (see [`crate::worker`]).
+pub struct Heartbeat<B = MouseController> {
+    config: SharedConfig,
+    state: SharedState,
+    dormant: bool,
+    wake_detector: WakeDetector,
+    lock_check: LockCheck,
+    controller: LazyController<B>,
+    sources: Option<SharedSources>,
+    shutdown: &'static ShutdownSignal,
+}
+
+impl Heartbeat {
+    pub fn new(config: SharedConfig, state: SharedState) -> Self {
+        Self::with_controller(config, state, LazyController::default())
+    }
+}
+
+impl<B: MouseBackend> Heartbeat<B> {
+    /// Move the mouse through `controller` instead of the real one
+    pub fn with_controller(
+        config: SharedConfig,
+        state: SharedState,
+        controller: LazyController<B>,
+    ) -> Self {
+        let interval = read_config(&config).heartbeat_interval;
+        let wake_detector = WakeDetector::new(Duration::from_secs(interval));
+        Self {
+            config,
+            state,
+            dormant: false,
+            wake_detector,
+            lock_check: LockCheck::new(),
+            controller,
+            sources: None,
+            shutdown: shutdown::signal(),
+        }
+    }
+
+    /// Poll these activity sources at the start of every tick.
+    pub fn with_sources(mut self, sources: SharedSources) -> Self {
+        self.sources = Some(sources);
+        self
+    }
+
+    /// Cut a burst short when `signal` is requested instead of the
+    /// process-wide [`shutdown::signal`]
+    pub fn with_shutdown(mut self, signal: &'static ShutdownSignal) -> Self {
+        self.shutdown = signal;
+        self
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(read_config(&self.config).heartbeat_interval)
+    }
+
+    /// Past `lock_after_secs` of idle time, lock the screen and pause.
+    fn lock_if_idle(&self, config: &Config) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let idle = state.idle_for(
+            Instant::now(),
+            &config.effective_source_timeouts(),
+            config.inactivity_threshold,
+        );
+        if power::should_lock_screen(config.lock_after_secs, idle, state.is_running) {
+            info!("Idle for {}s, locking the screen", idle.as_secs());
+            state.set_running(false, Cause::Idle);
+            // The lock command can block; don't hold the state while it runs
+            drop(state);
+            if !power::lock_screen() {
+                warn!("Failed to lock the screen");
+            }
+        }
+    }
+
+    /// Spread `moves_per_interval - 1` tiny moves across the rest of the
+    /// interval, alternating direction so the cursor ends where it started.
+    fn burst(&mut self, config: &Config) {
+        let started = Instant::now();
+        let offsets = sub_tick_offsets(
+            Duration::from_secs(config.heartbeat_interval),
+            config.moves_per_interval,
+        );
+        for (i, offset) in offsets.into_iter().enumerate() {
+            if self
+                .shutdown
+                .sleep(offset.saturating_sub(started.elapsed()))
+            {
+                break;
+            }
+            let sign = if i % 2 == 0 { 1 } else { -1 };
+            match mouse::nudge(&self.state, config, &mut self.controller, sign) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) => {
+                    warn!("Burst move failed: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn tick(&mut self) -> TickOutcome {
+        // Snapshot so a reload mid-tick can't mix old and new settings
+        let config = read_config(&self.config);
+
+        if let Ok(mut state) = self.state.lock() {
+            state.heartbeats += 1;
+        }
+
+        if let Some(sources) = &self.sources {
+            sources::poll_shared(sources, &self.state);
+        }
+
+        self.wake_detector
+            .set_expected_interval(Duration::from_secs(config.heartbeat_interval));
+        if self.wake_detector.tick(SystemTime::now()) {
+            let grace = Duration::from_secs(config.post_wake_grace_secs);
+            info!("Resumed from sleep, holding moves for {}s", grace.as_secs());
+            if let Ok(mut state) = self.state.lock() {
+                state.wake_grace_until = Some(Instant::now() + grace);
+            }
+        }
+
+        match mouse::display_gate(config.no_display_action, platform::display_count()) {
+            DisplayGate::Quit => {
+                info!("No displays connected, exiting");
+                return TickOutcome::Quit;
+            }
+            DisplayGate::Dormant => {
+                if !self.dormant {
+                    info!("No displays connected, going dormant");
+                    self.dormant = true;
+                }
+                return TickOutcome::Continue;
+            }
+            DisplayGate::Active => {
+                if self.dormant {
+                    info!("Display detected, resuming");
+                    self.dormant = false;
+                }
+            }
+        }
+
+        if let Some(max) = config.max_load_average {
+            let load = system::load_average();
+            if system::should_skip_for_load(load, Some(max)) {
+                debug!("Load average {:?} above {}, skipping heartbeat", load, max);
+                return TickOutcome::Continue;
+            }
+        }
+
+        if config.lock_after_secs.is_some() {
+            self.lock_if_idle(&config);
+        }
+
+        if self.lock_check.is_paused(
+            config.pause_lock_file.as_deref(),
+            Duration::from_secs(config.worker_interval),
+            Instant::now(),
+        ) {
+            debug!("Coordination lock held, skipping heartbeat");
+            return TickOutcome::Continue;
+        }
+
+        match mouse::check_and_move(Arc::clone(&self.state), &config, &mut self.controller) {
+            Ok(MoveOutcome::Moved) if config.moves_per_interval > 1 => self.burst(&config),
+            Ok(_) => {}
+            Err(e) => {
+                error!("Error in heartbeat: {:?}", e);
+                if let Ok(mut state) = self.state.lock() {
+                    state.events.record(EventKind::Error, e.to_string());
+                }
+            }
+        }
+        TickOutcome::Continue
+    }
+}
+
+/// Run RMM on an existing tokio runtime until `shutdown` resolves.
+///
+/// The heartbeat is driven by `tokio::time::interval`. Ticks run on a
+/// dedicated thread that owns the heartbeat, since the mouse controller can't
+/// move between threads on macOS. The `rdev` activity listener keeps its own
+/// thread too, since `listen` blocks forever. Worker ticks, which save files
+/// and run commands, go to the blocking pool.
+///
+/// With `http_api.enabled`, the API is served on the runtime until `shutdown`
+/// resolves. There is no log file to serve, so `GET /logs` fails.
+pub async fn run_async<F>(config: Config, state: SharedState, shutdown: F) -> Result<()>
+where
+    F: Future<Output = ()>,
+{
+    let sources = activity_sources(config.activity_mode, &state);
+    run_async_with_sources(config, state, sources, shutdown).await
+}
+
+/// [`run_async`] polling `sources` instead of the ones for the configured
+/// `activity_mode`, so no `rdev` listener is started for it.
+pub async fn run_async_with_sources<F>(
+    config: Config,
+    state: SharedState,
+    sources: SharedSources,
+    shutdown: F,
+) -> Result<()>
+where
+    F: Future<Output = ()>,
+{
+    rng::shared().reseed(config.random_seed);
+    if let Ok(mut state) = state.lock() {
+        state.start(&config);
+    }
+
+    let http_api = config.http_api.clone();
+    let config = Arc::new(RwLock::new(config));
+    let worker =
+        Worker::new(Arc::clone(&config), Arc::clone(&state)).with_sources(Arc::clone(&sources));
+    let worker_every = worker.interval();
+    let worker = Arc::new(Mutex::new(worker));
+    let heartbeat_interval = Duration::from_secs(read_config(&config).heartbeat_interval);
+    let quiet = read_config(&config).quiet;
+    let ticks = spawn_tick_thread(Arc::clone(&config), Arc::clone(&state), sources)?;
+
+    let api = if http_api.enabled {
+        let ctx = ApiContext {
+            state: Arc::clone(&state),
+            config: Arc::clone(&config),
+            config_path: None,
+            log_path: PathBuf::new(),
+            mouse_available: MouseController::is_available,
+            move_now: api::move_with_mouse,
+        };
+        match http::start_async(&http_api, ctx).await {
+            Ok((_, task)) => Some(task),
+            Err(e) => {
+                warn!("Failed to start the HTTP API: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut interval = tokio::time::interval(heartbeat_interval);
+    let mut worker_interval = tokio::time::interval(worker_every);
+    // The first tick fires immediately; the thread loop sleeps first, so match it
+    interval.tick().await;
+    worker_interval.tick().await;
+    logging::startup_info(
+        quiet,
+        format_args!(
+            "Heartbeat started ({}s interval)",
+            heartbeat_interval.as_secs()
+        ),
+    );
+
+    tokio::pin!(shutdown);
+    // Errors end the loop rather than return, so the API is still stopped
+    let result = loop {
+        tokio::select! {
+            _ = &mut shutdown => {
+                info!("Shutdown requested, stopping heartbeat");
+                break Ok(());
+            }
+            _ = worker_interval.tick() => {
+                let worker = Arc::clone(&worker);
+                let ticked = tokio::task::spawn_blocking(move || {
+                    worker.lock().unwrap_or_else(|e| e.into_inner()).tick()
+                });
+                if let Err(e) = ticked.await {
+                    error!("Worker task failed: {}", e);
+                }
+            }
+            _ = interval.tick() => {
+                let (reply, outcome) = tokio::sync::oneshot::channel();
+                if ticks.send(reply).is_err() {
+                    break Err(RmmError::Platform("Heartbeat thread stopped".into()));
+                }
+                match outcome.await {
+                    Ok(TickOutcome::Quit) => break Ok(()),
+                    Ok(TickOutcome::Continue) => {}
+                    Err(e) => {
+                        break Err(RmmError::Platform(format!("Heartbeat task failed: {}", e)));
+                    }
+                }
+            }
+        }
+    };
+
+    if let Some(api) = api {
+        api.abort();
+    }
+    if let Ok(mut state) = state.lock() {
+        state.set_running(false, Cause::Shutdown);
+    }
+    result
+}
+
+/// Run a heartbeat on its own thread, ticking once per request received on
+/// the returned channel and replying with the outcome. The thread exits when
+/// the sender is dropped.
+fn spawn_tick_thread(
+    config: SharedConfig,
+    state: SharedState,
+    sources: SharedSources,
+) -> Result<std_mpsc::Sender<tokio::sync::oneshot::Sender<TickOutcome>>> {
+    let (tx, rx) = std_mpsc::channel::<tokio::sync::oneshot::Sender<TickOutcome>>();
+    std::thread::Builder::new()
+        .name("heartbeat".into())
+        .spawn(move || {
+            let mut heartbeat = Heartbeat::new(config, state).with_sources(sources);
+            while let Ok(reply) = rx.recv() {
+                let _ = reply.send(heartbeat.tick());
+            }
+        })?;
+    Ok(tx)
+}
+
+/// The activity sources for `mode`, shared by the heartbeat and the worker.
+/// Starts the `rdev` listener unless `mode` is [`ActivityMode::Poll`].
+pub fn activity_sources(mode: ActivityMode, state: &SharedState) -> SharedSources {
+    let mut sources = SourceRegistry::new();
+    if mode == ActivityMode::Listener {
+        sources.register(Box::new(activity::start_monitoring(Arc::clone(state))));
+    }
+    sources.register(Box::new(OsIdleSource::new()));
+    Arc::new(Mutex::new(sources))
+}
@@ -0,0 +1,245 @@
+// Minimal HTTP/1.1 transport for the local control/status API.
+//
+// One request per connection, handled in turn on a single thread (or, on a
+// tokio runtime, each on the blocking pool): enough for a local client polling
+// `/status` or sending `POST /pause`. Routing and the handlers themselves live
+// in `crate::api`.
+//
+// Every request must carry the configured token as `Authorization: Bearer`,
+// and a `Host` naming the local machine (or the bind address, or one of the
+// configured `allowed_hosts`), so a web page can't reach the API through the
+// browser (directly or by DNS rebinding).
+
+use crate::api::{self, ApiContext, ApiRequest, ApiResponse};
+use crate::config::HttpApiConfig;
+use crate::error::{Result, RmmError};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// A client that stops sending mid-request is dropped after this long
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Request bodies are ignored; larger ones are refused
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// Hosts a request may always name, with or without a port
+const LOCAL_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+/// Listen on the configured address and serve the API in a background
+/// thread. Returns the bound address (useful with port 0). Fails without a
+/// `token`.
+pub fn start(config: &HttpApiConfig, ctx: ApiContext) -> Result<SocketAddr> {
+    let access = Access::new(config)?;
+    let listener = TcpListener::bind((config.bind, config.port))?;
+    let addr = listener.local_addr()?;
+    info!("HTTP API listening on http://{}", addr);
+    std::thread::spawn(move || serve(listener, ctx, &access));
+    Ok(addr)
+}
+
+/// [`start`] on the current tokio runtime: the listener is a task, and each
+/// connection is handled on the blocking pool. Returns the bound address and
+/// the task, which serves until aborted.
+pub async fn start_async(
+    config: &HttpApiConfig,
+    ctx: ApiContext,
+) -> Result<(SocketAddr, JoinHandle<()>)> {
+    let access = Access::new(config)?;
+    let listener = tokio::net::TcpListener::bind((config.bind, config.port)).await?;
+    let addr = listener.local_addr()?;
+    info!("HTTP API listening on http://{}", addr);
+    Ok((addr, tokio::spawn(serve_async(listener, ctx, access))))
+}
+
+/// What a request must present to be answered
+struct Access {
+    token: String,
+    /// Accepted `Host` names besides [`LOCAL_HOSTS`]
+    hosts: Vec<String>,
+}
+
+impl Access {
+    /// Fails without a `token`
+    fn new(config: &HttpApiConfig) -> Result<Self> {
+        let Some(token) = config.token.clone().filter(|token| !token.is_empty()) else {
+            return Err(RmmError::Config(
+                "http_api.token must be set to serve the HTTP API".into(),
+            ));
+        };
+        let mut hosts = config.allowed_hosts.clone();
+        // A specific address is what clients connecting to it will name
+        match config.bind {
+            IpAddr::V4(ip) if !ip.is_unspecified() => hosts.push(ip.to_string()),
+            IpAddr::V6(ip) if !ip.is_unspecified() => hosts.push(format!("[{}]", ip)),
+            _ => {}
+        }
+        Ok(Self { token, hosts })
+    }
+}
+
+/// Answer connections on `listener` until it fails
+fn serve(listener: TcpListener, ctx: ApiContext, access: &Access) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_connection(stream, &ctx, access) {
+                    debug!("HTTP connection failed: {}", e);
+                }
+            }
+            Err(e) => debug!("HTTP accept failed: {}", e),
+        }
+    }
+}
+
+async fn serve_async(listener: tokio::net::TcpListener, ctx: ApiContext, access: Access) {
+    let access = Arc::new(access);
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let (ctx, access) = (ctx.clone(), Arc::clone(&access));
+                tokio::task::spawn_blocking(move || {
+                    let result = stream.into_std().and_then(|stream| {
+                        stream.set_nonblocking(false)?;
+                        handle_connection(stream, &ctx, &access)
+                    });
+                    if let Err(e) = result {
+                        debug!("HTTP connection failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => debug!("HTTP accept failed: {}", e),
+        }
+    }
+}
+
+/// A parsed request plus the headers the transport checks itself
+struct HttpRequest {
+    request: ApiRequest,
+    host: Option<String>,
+    authorization: Option<String>,
+}
+
+fn handle_connection(stream: TcpStream, ctx: &ApiContext, access: &Access) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_request(&mut reader)? {
+        Some(http) if !is_allowed_host(http.host.as_deref(), &access.hosts) => {
+            debug!("HTTP request for host {:?} refused", http.host);
+            ApiResponse::text(403, "Forbidden\n")
+        }
+        Some(http) if !is_authorized(http.authorization.as_deref(), &access.token) => {
+            debug!("HTTP request without a valid token refused");
+            ApiResponse::text(401, "Unauthorized\n")
+        }
+        Some(HttpRequest { request, .. }) => {
+            debug!("HTTP {} {}", request.method, request.path);
+            api::handle(ctx, &request)
+        }
+        None => ApiResponse::text(400, "Bad request\n"),
+    };
+    write_response(stream, &response)
+}
+
+/// Whether `host` (a `Host` header value) names this machine or one of `extra`
+fn is_allowed_host(host: Option<&str>, extra: &[String]) -> bool {
+    let Some(host) = host.map(str::trim) else {
+        return false;
+    };
+    // "localhost:8765" or "[::1]:8765"
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    };
+    LOCAL_HOSTS
+        .iter()
+        .copied()
+        .chain(extra.iter().map(String::as_str))
+        .any(|allowed| name.eq_ignore_ascii_case(allowed))
+}
+
+/// Whether `authorization` (an `Authorization` header value) carries `token`
+fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    let Some((scheme, given)) = authorization.and_then(|value| value.trim().split_once(' ')) else {
+        return false;
+    };
+    // Compare every byte, so the time taken doesn't tell how much matched
+    let given = given.trim().as_bytes();
+    scheme.eq_ignore_ascii_case("bearer")
+        && given.len() == token.len()
+        && given
+            .iter()
+            .zip(token.as_bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Parse the request line and the headers checked by the transport, and
+/// skip the body. `None` for a malformed or oversized request.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Option<HttpRequest>> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    // "GET /status HTTP/1.1"
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let mut request = HttpRequest {
+        request: ApiRequest::new(method, target),
+        host: None,
+        authorization: None,
+    };
+
+    let mut content_length = 0u64;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let (name, value) = (name.trim(), value.trim());
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().unwrap_or(u64::MAX);
+            } else if name.eq_ignore_ascii_case("host") {
+                request.host = Some(value.to_string());
+            } else if name.eq_ignore_ascii_case("authorization") {
+                request.authorization = Some(value.to_string());
+            }
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Ok(None);
+    }
+    io::copy(&mut reader.take(content_length), &mut io::sink())?;
+    Ok(Some(request))
+}
+
+fn write_response(mut stream: TcpStream, response: &ApiResponse) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
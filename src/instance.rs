@@ -0,0 +1,93 @@
+// Single-instance guard, so two RMMs don't fight over the cursor.
+//
+// The guard is an exclusively locked file in the data directory: `flock` on
+// Unix, a file opened without write sharing on Windows. The OS drops the lock
+// when the process exits, so a crash never leaves a stale lock behind.
+
+use crate::error::{Result, RmmError};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Lock file name, in the data directory next to the log
+pub const LOCK_FILE: &str = "rmm.lock";
+
+/// Held while this process is the running instance; released on drop
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl InstanceLock {
+    /// Take the lock at `path`, recording our pid in it. Fails with
+    /// [`RmmError::AlreadyRunning`] while another instance (or another
+    /// `InstanceLock` in this process) holds it.
+    pub fn acquire(path: &Path) -> Result<Self> {
+        let mut file = match lock_file(path) {
+            Ok(file) => file,
+            Err(e) if is_held_elsewhere(&e) => {
+                let holder = fs::read_to_string(path).unwrap_or_default();
+                let holder = match holder.trim() {
+                    "" => "unknown pid".to_string(),
+                    pid => format!("pid {}", pid),
+                };
+                return Err(RmmError::AlreadyRunning(holder));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        file.set_len(0)?;
+        writeln!(file, "{}", std::process::id())?;
+        Ok(Self {
+            _file: file,
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(unix)]
+fn lock_file(path: &Path) -> io::Result<File> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    // Non-blocking: a second instance should give up, not wait
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(file)
+}
+
+#[cfg(unix)]
+fn is_held_elsewhere(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::WouldBlock
+}
+
+#[cfg(windows)]
+fn lock_file(path: &Path) -> io::Result<File> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // FILE_SHARE_READ: others may read the pid but not open it for writing
+    const FILE_SHARE_READ: u32 = 0x1;
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .share_mode(FILE_SHARE_READ)
+        .open(path)
+}
+
+#[cfg(windows)]
+fn is_held_elsewhere(e: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION
+    e.raw_os_error() == Some(32)
+}
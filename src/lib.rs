@@ -1,10 +1,43 @@
 // Library exports for testing and external use
 
+pub mod activity;
+pub mod api;
+pub mod app;
+pub mod audit;
+pub mod autostart;
+pub mod cli;
+pub mod clock;
+pub mod command;
 pub mod config;
+pub mod coordination;
+pub mod desktop;
 pub mod error;
+pub mod events;
+pub mod heartbeat;
+pub mod http;
+pub mod instance;
+pub mod logging;
+pub mod metrics;
+pub mod mouse;
+pub mod movement;
+pub mod oneshot;
+pub mod permissions;
+pub mod platform;
+pub mod power;
+pub mod ratelimit;
+pub mod rng;
+pub mod schedule;
+pub mod shutdown;
+pub mod sources;
 pub mod state;
+pub mod status;
+pub mod supervise;
+pub mod system;
+pub mod watch;
+pub mod worker;
 
 // Re-export commonly used types
-pub use config::Config;
+pub use app::run;
+pub use config::{Config, SharedConfig};
 pub use error::{Result, RmmError};
 pub use state::{AppState, SharedState};
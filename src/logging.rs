@@ -0,0 +1,97 @@
+use crate::error::Result;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn, Level};
+
+/// Size of the chunks read backwards from the end of the file
+const TAIL_CHUNK: u64 = 8 * 1024;
+
+/// How often `start_size_limit` checks the log size
+const SIZE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Return the last `lines` lines of the file at `path`.
+///
+/// Reads backwards from the end in fixed-size chunks, so only roughly the
+/// requested tail is read regardless of the file size.
+pub fn tail_lines(path: &Path, lines: usize) -> Result<Vec<String>> {
+    if lines == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut pos = len;
+    let mut buf: Vec<u8> = Vec::new();
+
+    // Need one more newline than lines wanted (the file usually ends with one)
+    while pos > 0 && buf.iter().filter(|&&b| b == b'\n').count() <= lines {
+        let read = TAIL_CHUNK.min(pos);
+        pos -= read;
+        file.seek(SeekFrom::Start(pos))?;
+        let mut chunk = vec![0; read as usize];
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let all: Vec<&str> = text.lines().collect();
+    // When we stopped mid-file the first line is likely partial; it is beyond the tail anyway
+    let start = all.len().saturating_sub(lines);
+    Ok(all[start..].iter().map(|l| l.to_string()).collect())
+}
+
+/// Shrink the file at `path` to at most `max_bytes`, keeping the end.
+///
+/// The kept tail starts at a line boundary, so the result may be slightly
+/// smaller than `max_bytes`. Returns whether the file was truncated.
+pub fn truncate_to_tail(path: &Path, max_bytes: u64) -> Result<bool> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let len = file.metadata()?.len();
+    if len <= max_bytes {
+        return Ok(false);
+    }
+
+    file.seek(SeekFrom::Start(len - max_bytes))?;
+    let mut tail = Vec::with_capacity(max_bytes as usize);
+    file.read_to_end(&mut tail)?;
+    // Drop the partial first line
+    let start = tail
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(tail.len(), |i| i + 1);
+
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&tail[start..])?;
+    Ok(true)
+}
+
+/// Truncate the log now and then periodically in a background thread.
+pub fn start_size_limit(path: PathBuf, max_bytes: u64) {
+    let check = move || match truncate_to_tail(&path, max_bytes) {
+        Ok(true) => info!("Log file exceeded {} bytes, truncated", max_bytes),
+        Ok(false) => {}
+        Err(e) => warn!("Failed to truncate log file: {}", e),
+    };
+    check();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SIZE_CHECK_INTERVAL);
+        check();
+    });
+}
+
+/// Whether a startup message at `level` is logged. `quiet` drops the routine
+/// INFO chatter but keeps warnings and errors.
+pub fn should_log_startup(quiet: bool, level: Level) -> bool {
+    !quiet || level <= Level::WARN
+}
+
+/// Log a routine startup line at INFO, unless `quiet`.
+pub fn startup_info(quiet: bool, message: std::fmt::Arguments) {
+    if should_log_startup(quiet, Level::INFO) {
+        info!("{}", message);
+    }
+}
@@ -1,19 +1,50 @@
-mod activity;
-mod config;
-mod error;
-mod mouse;
-mod state;
+#[cfg(feature = "tray")]
 mod tray;
 
-use error::Result;
+use rmm::app::Runner;
+use rmm::cli::Cli;
+#[cfg(feature = "tray")]
+use rmm::command::CommandProcessor;
+use rmm::instance::{self, InstanceLock};
+use rmm::{
+    audit, autostart, config, logging, metrics, oneshot, platform, rng, schedule, shutdown, state,
+    supervise, Result, RmmError,
+};
 use std::fs::{self, OpenOptions};
+use std::io::IsTerminal;
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
-use tracing::info;
+#[cfg(feature = "tray")]
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
 use tracing_subscriber::fmt::writer::MakeWriterExt;
 
 fn main() -> Result<()> {
+    #[cfg(feature = "tray")]
+    let started_at = Instant::now();
+    let cli = Cli::from_env()?;
+    if let Some(path) = &cli.config {
+        // Still single-threaded; every later config_path() call sees it
+        std::env::set_var(config::CONFIG_PATH_ENV, path);
+    }
+
+    // Output for scripts: handled before any log line can reach stdout
+    if cli.export_env {
+        for line in config::Config::load()?.to_env_lines() {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    if cli.show_schedule {
+        let config = config::Config::load()?;
+        let now = chrono::Local::now().naive_local();
+        print!(
+            "{}",
+            schedule::format_windows(&schedule::upcoming_windows(&config, now))
+        );
+        return Ok(());
+    }
+
     // Create log directory and file
     let log_dir = directories::ProjectDirs::from("com", "rmm", "rmm")
         .map(|dirs| dirs.data_local_dir().to_path_buf())
@@ -35,9 +66,14 @@ fn main() -> Result<()> {
     // Clone for the startup message
     let log_path_display = log_path.clone();
 
+    // The status line replaces log output on stdout, but only on a terminal
+    let status_line = cli.status_line && std::io::stdout().is_terminal();
+
     // Initialize logging to both stdout and file
     let file_writer = log_file.with_max_level(tracing::Level::INFO);
-    let stdout_writer = std::io::stdout.with_max_level(tracing::Level::INFO);
+    let stdout_writer = std::io::stdout
+        .with_max_level(tracing::Level::INFO)
+        .with_filter(move |_| !status_line);
 
     tracing_subscriber::fmt()
         .with_writer(file_writer.and(stdout_writer))
@@ -48,46 +84,102 @@ fn main() -> Result<()> {
         .with_ansi(false) // Disable ANSI colors in log file
         .init();
 
+    supervise::install_panic_hook();
+
     // Log startup with file location
-    info!("Starting RMM 2");
-    info!("Log file: {}", log_path_display.display());
+    logging::startup_info(cli.quiet, format_args!("Starting RMM 2"));
+    logging::startup_info(
+        cli.quiet,
+        format_args!("Log file: {}", log_path_display.display()),
+    );
 
     // Load configuration (returns error on failure)
     let config = config::Config::load()?;
-    // Create shared, thread-safe application state
-    let state = Arc::new(Mutex::new(state::AppState::new()));
+    let quiet = cli.quiet || config.quiet;
+    rng::shared().reseed(config.random_seed);
 
-    // Set running to true
-    {
-        let mut state_guard = state.lock().unwrap();
-        state_guard.is_running = true;
+    audit::init(log_dir.join("audit.log"));
+    if config.audit_mode {
+        info!(
+            "Audit mode: observing only, decisions go to {}",
+            log_dir.join("audit.log").display()
+        );
+    }
+
+    if let Some(max_bytes) = config.log_max_bytes {
+        logging::start_size_limit(log_path.clone(), max_bytes);
+    }
+
+    if cli.once_and_exit {
+        let idle = platform::system_idle_secs();
+        oneshot::run_once(&config, idle, &log_dir.join("once_state.json"))?;
+        shutdown::hooks().run();
+        return Ok(());
     }
 
-    info!("Configuration loaded");
-    info!("State initialized");
-
-    // Start activity monitoring in background (uses shared `state`)
-    activity::start_monitoring(Arc::clone(&state));
-    info!("Activity monitoring started");
-
-    // Heartbeat loop - check every heartbeat_interval seconds
-    let heartbeat_state = Arc::clone(&state);
-    let inactivity_threshold = config.inactivity_threshold;
-    let heartbeat_interval = config.heartbeat_interval;
-    thread::spawn(move || loop {
-        thread::sleep(Duration::from_secs(heartbeat_interval));
-        if let Err(e) = mouse::check_and_move(Arc::clone(&heartbeat_state), inactivity_threshold) {
-            tracing::error!("Error in heartbeat: {:?}", e);
+    // One resident instance at a time; the lock goes with the process
+    match InstanceLock::acquire(&log_dir.join(instance::LOCK_FILE)) {
+        Ok(lock) => shutdown::hooks().register("instance lock", move || drop(lock)),
+        Err(e @ RmmError::AlreadyRunning(_)) => {
+            warn!("{}, exiting", e);
+            return Ok(());
         }
-    });
-    info!("Heartbeat started ({}s interval)", heartbeat_interval);
+        Err(e) => warn!("Could not take the single-instance lock: {}", e),
+    }
+
+    if let Err(e) = autostart::sync(config.auto_start) {
+        warn!("Failed to update auto-start: {}", e);
+    }
+
+    // Create shared, thread-safe application state
+    let metrics_path = log_dir.join("metrics.json");
+    let stats_path = log_dir.join("stats.json");
+    let state = Arc::new(Mutex::new(state::AppState::new()));
+
+    state.lock().unwrap().metrics = metrics::Metrics::load(&metrics_path);
+    state.lock().unwrap().lifetime = metrics::Stats::load(&stats_path);
+
+    logging::startup_info(quiet, format_args!("Configuration loaded"));
+    logging::startup_info(quiet, format_args!("State initialized"));
+
+    let mut runner = Runner::new(config.clone(), Arc::clone(&state))
+        .with_metrics_path(metrics_path)
+        .with_stats_path(stats_path)
+        .with_log_path(log_path)
+        .status_line(status_line)
+        .quiet(quiet);
+    if let Ok(path) = config::Config::config_path() {
+        runner = runner.watch_config(path);
+    }
 
     // Create system tray icon (must be on main thread for macOS)
     // This will block the main thread and keep the tray alive
-    let _tray = tray::create_tray();
-
-    // Keep the main thread alive to maintain the tray icon
-    loop {
-        thread::sleep(Duration::from_secs(1));
+    #[cfg(feature = "tray")]
+    {
+        runner = runner.with_ui(move |shared_config| {
+            let commands = CommandProcessor::new(
+                Arc::clone(&state),
+                Duration::from_millis(config.toggle_debounce_ms),
+            );
+            let commands = match config::Config::config_path() {
+                Ok(path) => commands.with_persistence(Arc::clone(&shared_config), path),
+                Err(_) => commands.with_config(Arc::clone(&shared_config)),
+            };
+            tray::create_tray(
+                config.tray.clone(),
+                started_at,
+                shared_config,
+                state,
+                Arc::new(Mutex::new(commands)),
+            );
+        });
     }
+
+    #[cfg(not(feature = "tray"))]
+    logging::startup_info(
+        quiet,
+        format_args!("Built without tray support, running headless"),
+    );
+
+    runner.run()
 }
@@ -0,0 +1,141 @@
+// Counters persisted across restarts for reporting.
+
+use crate::config::write_json_atomic;
+use crate::error::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Moves per local calendar day, kept in `metrics.json` next to the log file
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Metrics {
+    /// Day `moves_today` counts for
+    pub day: Option<NaiveDate>,
+    pub moves_today: u64,
+}
+
+impl Metrics {
+    /// Load from `path`, falling back to empty counters if missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_json_atomic(path, self)
+    }
+
+    /// Start a new count if `today` differs from the counted day.
+    /// Returns whether the counter was reset.
+    pub fn roll_over(&mut self, today: NaiveDate) -> bool {
+        if self.day == Some(today) {
+            return false;
+        }
+        let reset = self.day.is_some();
+        self.day = Some(today);
+        self.moves_today = 0;
+        reset
+    }
+
+    pub fn record_move(&mut self, today: NaiveDate) {
+        self.roll_over(today);
+        self.moves_today += 1;
+    }
+
+    /// Moves on `today`, or 0 if the counter still belongs to an earlier day
+    pub fn moves_on(&self, today: NaiveDate) -> u64 {
+        if self.day == Some(today) {
+            self.moves_today
+        } else {
+            0
+        }
+    }
+}
+
+/// Lifetime move counts, kept in `stats.json` next to the log file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Stats {
+    pub total_moves: u64,
+    pub failed_moves: u64,
+    /// When RMM first ran with this data directory
+    pub first_run: DateTime<Utc>,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            total_moves: 0,
+            failed_moves: 0,
+            first_run: Utc::now(),
+        }
+    }
+}
+
+impl Stats {
+    /// Load from `path`; a missing or unreadable file starts counting now. A
+    /// corrupt one is set aside as `<path>.bad`, with a warning, rather than
+    /// silently overwritten on the next save.
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!(
+                    "Failed to read {}, counting from now: {}",
+                    path.display(),
+                    e
+                );
+                return Self::default();
+            }
+        };
+        match serde_json::from_str(&content) {
+            Ok(stats) => stats,
+            Err(e) => {
+                let mut bad = path.as_os_str().to_owned();
+                bad.push(".bad");
+                let bad = PathBuf::from(bad);
+                warn!(
+                    "{} is corrupt ({}), keeping it as {} and counting from now",
+                    path.display(),
+                    e,
+                    bad.display()
+                );
+                if let Err(e) = fs::rename(path, &bad) {
+                    warn!("Failed to move aside {}: {}", path.display(), e);
+                }
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_json_atomic(path, self)
+    }
+
+    /// These counts plus `moves` and `failures` from the current run
+    pub fn plus(&self, moves: u64, failures: u64) -> Self {
+        Self {
+            total_moves: self.total_moves + moves,
+            failed_moves: self.failed_moves + failures,
+            first_run: self.first_run,
+        }
+    }
+}
+
+/// The current local calendar day
+pub fn local_today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
@@ -1,8 +1,114 @@
+use crate::activity::{self, EchoStatus};
+use crate::audit;
+use crate::clock;
+use crate::config::{ActivityMode, Config, Easing, KeepAwakeMode, NoDisplayAction, Region};
+use crate::desktop;
 use crate::error::{Result, RmmError};
-use crate::state::SharedState;
-use enigo::{Enigo, Mouse, Settings};
-use std::time::Instant;
-use tracing::{error, info, warn};
+use crate::events::EventKind;
+use crate::metrics;
+use crate::movement::{
+    choose_pattern, clamp_movement_delta, Move, MovementPattern, MovementStrategy,
+};
+use crate::permissions;
+use crate::power;
+use crate::rng;
+use crate::schedule;
+use crate::state::{AppState, Cause, InputKind, SharedState};
+use enigo::{Axis, Direction, Enigo, Key, Keyboard, Mouse, Settings};
+use rand::Rng;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn, Level};
+
+/// Cursor access used by the move logic. [`MouseController`] is the real
+/// (enigo) implementation; tests can substitute a scripted one.
+///
+/// Only `get_position` and `move_mouse` are required; smoothing and
+/// verification are built on them.
+pub trait MouseBackend {
+    fn get_position(&mut self) -> Result<(i32, i32)>;
+
+    fn move_mouse(&mut self, x: i32, y: i32) -> Result<()>;
+
+    /// Size of the main display in pixels, if known.
+    fn get_screen_size(&mut self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// Press and release F15, which counts as activity without typing anything.
+    fn tap_key(&mut self) -> Result<()> {
+        Err(RmmError::MouseControl(
+            "Key taps are not supported by this backend".into(),
+        ))
+    }
+
+    /// Scroll by `dx` notches horizontally and `dy` vertically (positive is
+    /// right and down).
+    fn scroll(&mut self, _dx: i32, _dy: i32) -> Result<()> {
+        Err(RmmError::MouseControl(
+            "Scrolling is not supported by this backend".into(),
+        ))
+    }
+
+    /// Scroll one notch up and back down, leaving the view where it was.
+    fn scroll_nudge(&mut self) -> Result<()> {
+        self.scroll(0, -1)?;
+        self.scroll(0, 1)
+    }
+
+    /// Move to (x, y) through `steps` intermediate positions following `easing`.
+    fn smooth_move_to(&mut self, x: i32, y: i32, steps: u32, easing: Easing) -> Result<()> {
+        let from = self.get_position()?;
+        let path = smooth_steps(from, (x, y), steps, easing);
+        let last = path.len().saturating_sub(1);
+        for (i, (step_x, step_y)) in path.into_iter().enumerate() {
+            self.move_mouse(step_x, step_y)?;
+            if i < last {
+                std::thread::sleep(SMOOTH_STEP_DELAY);
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_position(
+        &mut self,
+        expected_x: i32,
+        expected_y: i32,
+        tolerance: i32,
+    ) -> Result<bool> {
+        let actual = self.get_position()?;
+        Ok(within_tolerance(
+            actual,
+            (expected_x, expected_y),
+            tolerance,
+        ))
+    }
+
+    /// Poll the cursor every `poll` until it is within `tolerance` of
+    /// `expected`, giving up after `timeout`.
+    fn verify_until(
+        &mut self,
+        expected: (i32, i32),
+        tolerance: i32,
+        poll: Duration,
+        timeout: Duration,
+    ) -> Result<bool> {
+        poll_until_within(|| self.get_position(), expected, tolerance, poll, timeout)
+    }
+
+    /// Verify the cursor moved by roughly `(dx, dy)` from `before`, regardless
+    /// of where it ended up in absolute terms.
+    fn verify_delta(
+        &mut self,
+        before: (i32, i32),
+        dx: i32,
+        dy: i32,
+        tolerance: i32,
+    ) -> Result<bool> {
+        let after = self.get_position()?;
+        Ok(delta_matches(before, after, (dx, dy), tolerance))
+    }
+}
 
 pub struct MouseController {
     enigo: Enigo,
@@ -15,87 +121,907 @@ impl MouseController {
         Ok(Self { enigo })
     }
 
-    pub fn get_position(&mut self) -> Result<(i32, i32)> {
+    /// Whether the input backend can be created right now
+    pub fn is_available() -> bool {
+        Self::new().is_ok()
+    }
+}
+
+impl MouseBackend for MouseController {
+    fn get_position(&mut self) -> Result<(i32, i32)> {
         self.enigo
             .location()
             .map_err(|e| RmmError::MouseControl(format!("Failed to get mouse position: {:?}", e)))
     }
 
-    pub fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
+    fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
         self.enigo
             .move_mouse(x, y, enigo::Coordinate::Abs)
             .map_err(|e| RmmError::MouseControl(format!("Failed to move mouse: {:?}", e)))
     }
 
-    pub fn verify_position(&mut self, expected_x: i32, expected_y: i32) -> Result<bool> {
-        let (actual_x, actual_y) = self.get_position()?;
-        let tolerance = 5; // Allow 5 pixel tolerance
-        let x_match = (actual_x - expected_x).abs() <= tolerance;
-        let y_match = (actual_y - expected_y).abs() <= tolerance;
-        Ok(x_match && y_match)
+    fn get_screen_size(&mut self) -> Option<(i32, i32)> {
+        self.enigo
+            .main_display()
+            .ok()
+            .filter(|&(width, height)| width > 0 && height > 0)
+    }
+
+    fn tap_key(&mut self) -> Result<()> {
+        self.enigo
+            .key(Key::F15, Direction::Click)
+            .map_err(|e| RmmError::MouseControl(format!("Failed to tap key: {:?}", e)))
+    }
+
+    fn scroll(&mut self, dx: i32, dy: i32) -> Result<()> {
+        for (length, axis) in [(dx, Axis::Horizontal), (dy, Axis::Vertical)] {
+            if length != 0 {
+                self.enigo
+                    .scroll(length, axis)
+                    .map_err(|e| RmmError::MouseControl(format!("Failed to scroll: {:?}", e)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A [`MouseBackend`] created on first use and reused afterwards, so the
+/// input backend isn't rebuilt on every heartbeat. If creation fails it is
+/// retried on the next use. Not `Send` on macOS: keep it on the heartbeat thread.
+pub struct LazyController<B = MouseController> {
+    inner: Option<B>,
+    factory: fn() -> Result<B>,
+}
+
+impl Default for LazyController<MouseController> {
+    fn default() -> Self {
+        Self::with_factory(MouseController::new)
     }
 }
 
-pub fn check_and_move(state: SharedState, inactivity_threshold: u64) -> Result<()> {
-    let mut controller = MouseController::new()?;
+impl<B> LazyController<B> {
+    /// Create backends with `factory` instead of [`MouseController::new`]
+    pub fn with_factory(factory: fn() -> Result<B>) -> Self {
+        Self {
+            inner: None,
+            factory,
+        }
+    }
 
-    let (should_move, direction) = {
-        let state_guard = state
+    pub fn get(&mut self) -> Result<&mut B> {
+        let controller = match self.inner.take() {
+            Some(controller) => controller,
+            None => {
+                let controller = (self.factory)()?;
+                debug!("Mouse controller initialized");
+                controller
+            }
+        };
+        Ok(self.inner.insert(controller))
+    }
+}
+
+/// Decide whether the cursor should be nudged: monitoring is running and the
+/// user has been inactive for at least `inactivity_threshold` seconds, taking
+/// per-input timeouts (`source_timeouts`, `keyboard_idle_threshold`) into account,
+/// and not within the cooldown after sustained fast mouse movement. With
+/// `typing_presence_window` set, only pointer idle time counts and a recent
+/// keystroke is required instead.
+pub fn should_move(state: &AppState, config: &Config) -> bool {
+    let now = clock::now();
+    let idle_enough = match config.typing_presence_window {
+        Some(window) => typing_while_pointer_idle(state, now, config.inactivity_threshold, window),
+        None => {
+            let idle = state.idle_for(
+                now,
+                &config.effective_source_timeouts(),
+                config.inactivity_threshold,
+            );
+            idle.as_secs() >= config.inactivity_threshold
+        }
+    };
+    state.is_running && idle_enough && !state.velocity.is_present(now)
+}
+
+/// The `typing_presence_window` condition: the pointer (mouse and buttons) has
+/// been idle for `pointer_idle_secs` while a key was pressed within the last
+/// `window_secs`. Without any pointer input, the time since the last synthetic
+/// move (or startup) counts as pointer idle time.
+pub fn typing_while_pointer_idle(
+    state: &AppState,
+    now: Instant,
+    pointer_idle_secs: u64,
+    window_secs: u64,
+) -> bool {
+    let pointer_idle = state
+        .since_last(&[InputKind::Mouse, InputKind::Button], now)
+        .unwrap_or_else(|| now.saturating_duration_since(state.last_moved));
+    let typed_recently = state
+        .since_last(&[InputKind::Keyboard], now)
+        .is_some_and(|since| since.as_secs() <= window_secs);
+    pointer_idle.as_secs() >= pointer_idle_secs && typed_recently
+}
+
+/// Whether a held mouse button (a drag in progress) should block the move
+pub fn blocked_by_drag(state: &AppState, config: &Config) -> bool {
+    config.skip_while_button_held && state.button_held()
+}
+
+/// Allowed distance in pixels (per axis) between expected and actual positions
+const VERIFY_TOLERANCE: i32 = 5;
+
+/// Pixel tolerance used when verifying moves; `strict_verify` requires an exact match.
+pub fn verify_tolerance(config: &Config) -> i32 {
+    if config.strict_verify {
+        0
+    } else {
+        VERIFY_TOLERANCE
+    }
+}
+
+/// [`verify_tolerance`] for one check, varied by up to
+/// `verify_tolerance_jitter` pixels either way. Never negative, and
+/// `strict_verify` stays exact.
+pub fn jittered_tolerance<R: Rng + ?Sized>(config: &Config, rng: &mut R) -> i32 {
+    let base = verify_tolerance(config);
+    let jitter = i32::try_from(config.verify_tolerance_jitter).unwrap_or(i32::MAX);
+    if config.strict_verify || jitter == 0 {
+        return base;
+    }
+    base.saturating_add(rng.gen_range(-jitter..=jitter)).max(0)
+}
+
+/// Whether `actual` is within `tolerance` pixels of `expected` on both axes
+pub fn within_tolerance(actual: (i32, i32), expected: (i32, i32), tolerance: i32) -> bool {
+    (actual.0 - expected.0).abs() <= tolerance && (actual.1 - expected.1).abs() <= tolerance
+}
+
+/// Read positions from `read` every `poll` until one is within `tolerance` of
+/// `expected` (success) or `timeout` has passed since the first read (failure).
+pub fn poll_until_within<F>(
+    mut read: F,
+    expected: (i32, i32),
+    tolerance: i32,
+    poll: Duration,
+    timeout: Duration,
+) -> Result<bool>
+where
+    F: FnMut() -> Result<(i32, i32)>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        if within_tolerance(read()?, expected, tolerance) {
+            return Ok(true);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(false);
+        }
+        std::thread::sleep(poll.min(deadline - now));
+    }
+}
+
+/// Whether the observed movement `after - before` matches the requested delta
+pub fn delta_matches(
+    before: (i32, i32),
+    after: (i32, i32),
+    requested: (i32, i32),
+    tolerance: i32,
+) -> bool {
+    let moved = (after.0 - before.0, after.1 - before.1);
+    within_tolerance(moved, requested, tolerance)
+}
+
+/// Pause between intermediate positions of a smooth move
+const SMOOTH_STEP_DELAY: Duration = Duration::from_millis(10);
+
+/// Extra time after a synthetic action during which the listener may still
+/// deliver its events
+const OWN_INPUT_GRACE: Duration = Duration::from_millis(250);
+
+/// How long input events should be attributed to our own move: the smooth
+/// move's duration plus a grace period for delivery.
+pub fn own_input_window(config: &Config) -> Duration {
+    SMOOTH_STEP_DELAY * config.smooth_move_steps + OWN_INPUT_GRACE
+}
+
+fn ignore_own_input(state: &SharedState, config: &Config) {
+    if let Ok(mut state_guard) = state.lock() {
+        state_guard.ignore_own_input(Instant::now(), own_input_window(config));
+    }
+}
+
+/// Progress along the path (0.0..=1.0) at time fraction `t` for the given easing
+pub fn ease(easing: Easing, t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    match easing {
+        Easing::Linear => t,
+        // Smoothstep: zero velocity at both ends, fastest in the middle
+        Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+    }
+}
+
+/// Intermediate positions from `from` to `to` (excluding `from`, ending exactly at `to`).
+pub fn smooth_steps(
+    from: (i32, i32),
+    to: (i32, i32),
+    steps: u32,
+    easing: Easing,
+) -> Vec<(i32, i32)> {
+    let steps = steps.max(1);
+    let (dx, dy) = ((to.0 - from.0) as f64, (to.1 - from.1) as f64);
+    (1..=steps)
+        .map(|i| {
+            let p = ease(easing, i as f64 / steps as f64);
+            (
+                from.0 + (dx * p).round() as i32,
+                from.1 + (dy * p).round() as i32,
+            )
+        })
+        .collect()
+}
+
+/// One tiny follow-up move of a burst (see `moves_per_interval`), by
+/// `min_movement_delta` pixels in `sign` direction. Goes through the same
+/// gates as a regular move, budget included, and stops the burst (returns
+/// `false`) once any of them says no or the mode isn't `Mouse`.
+pub fn nudge<B: MouseBackend>(
+    state: &SharedState,
+    config: &Config,
+    controller: &mut LazyController<B>,
+    sign: i32,
+) -> Result<bool> {
+    let Some(_guard) = MoveGuard::try_acquire(&MOVE_IN_PROGRESS) else {
+        return Ok(false);
+    };
+    let mode = state
+        .lock()
+        .map_err(|e| RmmError::MouseControl(format!("Failed to lock state: {}", e)))?
+        .keep_awake_mode;
+    // Key taps and scrolls are one action per heartbeat
+    if mode != KeepAwakeMode::Mouse || clear_to_move(state, config, false)?.is_none() {
+        return Ok(false);
+    }
+
+    let controller = controller.get()?;
+    ignore_own_input(state, config);
+    let step = cap_move(
+        Move::diagonal(config.min_movement_delta.max(1)).scale(sign),
+        config.max_move_distance,
+    )
+    .0;
+    let current = controller.get_position()?;
+    let mut target = step.apply(current);
+    if let Some(screen) = controller.get_screen_size() {
+        target = clamp_to_screen(current, target, screen).0;
+    }
+    let (x, y) = target;
+    controller.move_mouse(x, y)?;
+    if let Ok(mut state_guard) = state.lock() {
+        // Our own input as far as the OS idle time is concerned
+        state_guard.last_moved = Instant::now();
+    }
+    debug!("Burst nudge by ({}, {})", step.dx, step.dy);
+    Ok(true)
+}
+
+/// Largest power of two the failure backoff is multiplied by
+pub const FAILURE_BACKOFF_CAP: u32 = 6;
+
+/// Wait before retrying after `error_count` consecutive failed moves:
+/// `base * 2^min(error_count, FAILURE_BACKOFF_CAP)`
+pub fn failure_backoff(base: Duration, error_count: u32) -> Duration {
+    base.saturating_mul(1 << error_count.min(FAILURE_BACKOFF_CAP))
+}
+
+/// Count a failed move. Returns whether failures have reached `max_errors`,
+/// in which case the error is escalated and permissions are re-checked.
+pub fn record_failure(state: &mut AppState, max_errors: u32) -> bool {
+    state.error_count += 1;
+    state.count_failed_move();
+    state.events.record(
+        EventKind::Error,
+        format!("move verification failed ({} in a row)", state.error_count),
+    );
+    warn!(
+        "Mouse movement verification failed (error count: {})",
+        state.error_count
+    );
+
+    if state.error_count < max_errors {
+        return false;
+    }
+    error!(
+        "Mouse movement failed {} times! Please check system permissions.",
+        state.error_count
+    );
+    permissions::self_heal(state, max_errors);
+    true
+}
+
+/// Wait up to `window` for the activity listener to report the tracked move.
+fn wait_for_echo(state: &SharedState, window: Duration, poll: Duration) -> bool {
+    loop {
+        let status = match state.lock() {
+            Ok(state_guard) => state_guard.move_echo.status(Instant::now(), window),
+            Err(_) => return false,
+        };
+        match status {
+            EchoStatus::Observed => return true,
+            EchoStatus::Pending => std::thread::sleep(poll),
+            EchoStatus::Idle | EchoStatus::TimedOut => return false,
+        }
+    }
+}
+
+/// The relative move for one check: `movement_delta` (clamped to its
+/// bounds) shaped by `pattern` and pointed in `direction`.
+pub fn planned_step(config: &Config, pattern: MovementPattern, direction: i32) -> Move {
+    let delta = clamp_movement_delta(
+        config.movement_delta,
+        config.min_movement_delta,
+        config.max_movement_delta,
+    );
+    pattern.step(delta).scale(direction)
+}
+
+/// Limit `step` to `max_move_distance` pixels on each axis. Returns the
+/// capped move and whether it had to be cut.
+pub fn cap_move(step: Move, max_move_distance: u32) -> (Move, bool) {
+    let max = i32::try_from(max_move_distance).unwrap_or(i32::MAX);
+    let capped = step.clamp_to(max);
+    (capped, capped != step)
+}
+
+/// Confine a move to `region`: returns the position to start from (pulled
+/// inside if the cursor is currently outside) and the clamped target.
+pub fn confine_move(
+    region: &Region,
+    current: (i32, i32),
+    target: (i32, i32),
+) -> ((i32, i32), (i32, i32)) {
+    let start = region.clamp(current);
+    let offset = (target.0 - current.0, target.1 - current.1);
+    let target = region.clamp((start.0 + offset.0, start.1 + offset.1));
+    (start, target)
+}
+
+/// Clamp `target` into a `screen` of (width, height) pixels. Returns the
+/// clamped target and whether it had to be changed. A `current` position
+/// outside the screen means the cursor is on another display, which is left
+/// alone.
+pub fn clamp_to_screen(
+    current: (i32, i32),
+    target: (i32, i32),
+    screen: (i32, i32),
+) -> ((i32, i32), bool) {
+    let (width, height) = screen;
+    let on_screen = (0..width).contains(&current.0) && (0..height).contains(&current.1);
+    if !on_screen {
+        return (target, false);
+    }
+    let clamped = (target.0.clamp(0, width - 1), target.1.clamp(0, height - 1));
+    (clamped, clamped != target)
+}
+
+/// Result of a single [`check_and_move`] call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveOutcome {
+    /// The cursor was moved and the move verified
+    Moved,
+    /// A move was attempted but verification failed
+    Failed,
+    /// No move was needed or a gate held it back
+    Skipped,
+    /// Another `check_and_move` was already running
+    Busy,
+}
+
+impl MoveOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Moved => "moved",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+            Self::Busy => "busy",
+        }
+    }
+}
+
+/// Set while a `check_and_move` call is running, across all trigger paths
+static MOVE_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// RAII guard for an "in progress" flag; released on drop.
+pub struct MoveGuard<'a> {
+    flag: &'a AtomicBool,
+}
+
+impl<'a> MoveGuard<'a> {
+    /// Set the flag, or return `None` if it's already held.
+    pub fn try_acquire(flag: &'a AtomicBool) -> Option<Self> {
+        flag.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| Self { flag })
+    }
+}
+
+impl Drop for MoveGuard<'_> {
+    fn drop(&mut self) {
+        self.flag.store(false, Ordering::Release);
+    }
+}
+
+/// Level for the per-move log lines. Failures always log at WARN; verified
+/// moves drop to DEBUG in quiet mode.
+pub fn move_log_level(verified: bool, quiet: bool) -> Level {
+    if !verified {
+        Level::WARN
+    } else if quiet {
+        Level::DEBUG
+    } else {
+        Level::INFO
+    }
+}
+
+/// Whether the `verified_moves`-th verified move should log a summary at INFO.
+/// Only used in quiet mode, where individual moves are not logged at INFO.
+pub fn should_log_move_summary(verified_moves: u64, quiet: bool, every: u64) -> bool {
+    quiet && every > 0 && verified_moves > 0 && verified_moves.is_multiple_of(every)
+}
+
+/// Whether the `consecutive`-th check skipped because the user is active should
+/// log a reassurance line. Fires once every `every` checks (0 = never).
+pub fn should_log_liveness(consecutive: u64, every: u64) -> bool {
+    every > 0 && consecutive > 0 && consecutive.is_multiple_of(every)
+}
+
+/// Log a routine move message at INFO or DEBUG
+fn log_move(level: Level, message: std::fmt::Arguments) {
+    if level == Level::INFO {
+        info!("{}", message);
+    } else {
+        debug!("{}", message);
+    }
+}
+
+/// Outcome of the display check performed before each heartbeat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayGate {
+    Active,
+    Dormant,
+    Quit,
+}
+
+/// Decide how the heartbeat should behave given the number of connected displays.
+/// An unknown count is treated as "displays present".
+pub fn display_gate(action: NoDisplayAction, display_count: Option<u32>) -> DisplayGate {
+    match (action, display_count) {
+        (NoDisplayAction::Dormant, Some(0)) => DisplayGate::Dormant,
+        (NoDisplayAction::Quit, Some(0)) => DisplayGate::Quit,
+        _ => DisplayGate::Active,
+    }
+}
+
+pub fn check_and_move<B: MouseBackend>(
+    state: SharedState,
+    config: &Config,
+    controller: &mut LazyController<B>,
+) -> Result<MoveOutcome> {
+    run_move(state, config, controller, false)
+}
+
+/// Move once now whether or not the user is idle, as for `POST /move`. The
+/// other gates (paused, snoozed, schedule, lock, ...) still apply.
+pub fn move_now<B: MouseBackend>(
+    state: SharedState,
+    config: &Config,
+    controller: &mut LazyController<B>,
+) -> Result<MoveOutcome> {
+    run_move(state, config, controller, true)
+}
+
+/// The gates every move path goes through, in order: schedule, pause, snooze,
+/// backoff, wake grace, drag, the idle check (skipped when `force`), the
+/// desktop checks and finally a token from the move budget. Returns the
+/// direction and keep-awake mode to move with, or `None` to skip.
+fn clear_to_move(
+    state: &SharedState,
+    config: &Config,
+    force: bool,
+) -> Result<Option<(i32, KeepAwakeMode)>> {
+    let now = chrono::Local::now().naive_local();
+    if schedule::in_maintenance(config, now) {
+        debug!("In a maintenance window, skipping move");
+        return Ok(None);
+    }
+    if !schedule::in_working_hours(config, now) {
+        debug!("Outside working hours, skipping move");
+        return Ok(None);
+    }
+
+    let (should_move, direction, mode) = {
+        let mut state_guard = state
             .lock()
             .map_err(|e| RmmError::MouseControl(format!("Failed to lock state: {}", e)))?;
 
         if !state_guard.is_running {
-            return Ok(());
+            return Ok(None);
+        }
+
+        if state_guard.snoozed(Instant::now()) {
+            debug!("Snoozed, skipping move");
+            return Ok(None);
         }
 
-        let inactive_duration = state_guard.last_activity.elapsed().as_secs();
-        let should_move = inactive_duration >= inactivity_threshold;
+        if state_guard
+            .retry_after
+            .is_some_and(|until| Instant::now() < until)
+        {
+            debug!("Backing off after a failed move, skipping");
+            return Ok(None);
+        }
+
+        if power::in_wake_grace(state_guard.wake_grace_until, Instant::now()) {
+            debug!("Within post-wake grace period, skipping move");
+            return Ok(None);
+        }
 
-        (should_move, state_guard.move_direction)
+        if blocked_by_drag(&state_guard, config) {
+            debug!("Mouse button held, skipping move");
+            return Ok(None);
+        }
+
+        let should_move = force || should_move(&state_guard, config);
+        if should_move {
+            state_guard.active_skips = 0;
+        } else {
+            state_guard.active_skips += 1;
+            if should_log_liveness(state_guard.active_skips, config.liveness_log_every) {
+                info!(
+                    "User active, no action needed ({} checks in a row)",
+                    state_guard.active_skips
+                );
+            }
+        }
+        (
+            should_move,
+            state_guard.move_direction,
+            state_guard.keep_awake_mode,
+        )
     };
 
     if !should_move {
-        return Ok(());
+        return Ok(None);
+    }
+
+    if power::should_pause_for_lid(config.pause_when_lid_closed, power::lid_closed) {
+        debug!("Lid is closed, skipping move");
+        return Ok(None);
+    }
+
+    if power::should_skip_for_display_off(config.require_display_on, power::any_display_on) {
+        debug!("All displays are off, skipping move");
+        return Ok(None);
+    }
+
+    let desktop = desktop::shared().get();
+    if desktop::should_skip_for_lock(config.move_when_locked, || desktop.session_locked()) {
+        debug!("Session is locked, skipping move");
+        return Ok(None);
+    }
+
+    if desktop::should_pause_for_fullscreen(config.pause_on_fullscreen, || {
+        desktop.fullscreen_app_active()
+    }) {
+        debug!("Fullscreen app in focus, skipping move");
+        return Ok(None);
+    }
+
+    if !config.blocklist_processes.is_empty() {
+        let process = desktop.focused_process_name();
+        if desktop::is_blocklisted(&config.blocklist_processes, process.as_deref()) {
+            debug!("Blocklisted app in focus ({:?}), skipping move", process);
+            return Ok(None);
+        }
+    }
+
+    if activity::should_pause_for_presence(config.respect_presence_api, activity::user_present) {
+        debug!("User reported present, skipping move");
+        return Ok(None);
+    }
+
+    // Every move path draws from the shared budget
+    {
+        let mut state_guard = state
+            .lock()
+            .map_err(|e| RmmError::MouseControl(format!("Failed to lock state: {}", e)))?;
+        if !state_guard.move_budget.try_acquire(Instant::now()) {
+            debug!("Move budget exhausted, skipping move");
+            return Ok(None);
+        }
+    }
+
+    Ok(Some((direction, mode)))
+}
+
+fn run_move<B: MouseBackend>(
+    state: SharedState,
+    config: &Config,
+    controller: &mut LazyController<B>,
+    force: bool,
+) -> Result<MoveOutcome> {
+    let _guard = match MoveGuard::try_acquire(&MOVE_IN_PROGRESS) {
+        Some(guard) => guard,
+        None => {
+            debug!("Another move is in progress, skipping");
+            return Ok(MoveOutcome::Busy);
+        }
+    };
+
+    let Some((direction, mode)) = clear_to_move(&state, config, force)? else {
+        return Ok(MoveOutcome::Skipped);
+    };
+
+    if config.audit_mode {
+        let idle = state
+            .lock()
+            .map(|state_guard| {
+                state_guard.idle_for(
+                    Instant::now(),
+                    &config.effective_source_timeouts(),
+                    config.inactivity_threshold,
+                )
+            })
+            .unwrap_or_default();
+        let pattern = rng::shared().with(|rng| choose_pattern(&config.pattern_set, rng));
+        let step = match mode {
+            KeepAwakeMode::Mouse => planned_step(config, pattern, direction),
+            KeepAwakeMode::Key | KeepAwakeMode::Scroll => Move::default(),
+        };
+        audit::record(&audit::Decision {
+            idle_secs: idle.as_secs(),
+            mode,
+            dx: step.dx,
+            dy: step.dy,
+        });
+        return Ok(MoveOutcome::Skipped);
+    }
+
+    let controller = controller.get()?;
+    ignore_own_input(&state, config);
+
+    match mode {
+        KeepAwakeMode::Mouse => {}
+        KeepAwakeMode::Key | KeepAwakeMode::Scroll => {
+            if mode == KeepAwakeMode::Key {
+                controller.tap_key()?;
+            } else {
+                controller.scroll_nudge()?;
+            }
+            // Nothing to read back, so the action counts as done once issued
+            log_move(
+                move_log_level(true, config.quiet_move_logs),
+                format_args!("Kept awake via {}", mode.as_str()),
+            );
+            let mut state_guard = state
+                .lock()
+                .map_err(|e| RmmError::MouseControl(format!("Failed to lock state: {}", e)))?;
+            state_guard.count_move();
+            state_guard.metrics.record_move(metrics::local_today());
+            state_guard.events.record(EventKind::Move, mode.as_str());
+            state_guard.last_moved = Instant::now();
+            state_guard.error_count = 0;
+            state_guard.retry_after = None;
+            return Ok(MoveOutcome::Moved);
+        }
     }
 
     // Get current position
-    let (current_x, current_y) = controller.get_position()?;
-    info!("Current mouse position: ({}, {})", current_x, current_y);
+    let (mut current_x, mut current_y) = controller.get_position()?;
+    let routine = move_log_level(true, config.quiet_move_logs);
+    log_move(
+        routine,
+        format_args!("Current mouse position: ({}, {})", current_x, current_y),
+    );
 
     // Calculate new position
-    let delta = 10 * direction;
-    let new_x = current_x + delta;
-    let new_y = current_y + delta;
+    let pattern = rng::shared().with(|rng| choose_pattern(&config.pattern_set, rng));
+    let step = match config.movement_pattern {
+        MovementStrategy::Linear => planned_step(config, pattern, direction),
+        MovementStrategy::Circle => {
+            let radius = planned_step(config, MovementPattern::Horizontal, 1).dx;
+            let target = state
+                .lock()
+                .map_err(|e| RmmError::MouseControl(format!("Failed to lock state: {}", e)))?
+                .orbit
+                .target((current_x, current_y), radius);
+            Move::new(target.0 - current_x, target.1 - current_y)
+        }
+    };
+    let (mut new_x, mut new_y) = step.apply((current_x, current_y));
+
+    if let Some(region) = &config.move_region {
+        let (start, target) = confine_move(region, (current_x, current_y), (new_x, new_y));
+        if start != (current_x, current_y) {
+            info!(
+                "Cursor outside move region, pulling it to ({}, {})",
+                start.0, start.1
+            );
+            controller.move_mouse(start.0, start.1)?;
+            (current_x, current_y) = start;
+        }
+        (new_x, new_y) = target;
+        if (new_x, new_y) == (current_x, current_y) {
+            // Pinned against the region's edge: nothing to move or verify,
+            // so head back inward next time
+            debug!("No room left in the move region this way, skipping move");
+            if let Ok(mut state_guard) = state.lock() {
+                state_guard.move_direction *= -1;
+            }
+            return Ok(MoveOutcome::Skipped);
+        }
+    }
+
+    // Near the right/bottom edge the OS would clamp the cursor and verification
+    // would fail, so stop at the edge and head back inward next time
+    let mut hit_edge = false;
+    if let Some(screen) = controller.get_screen_size() {
+        let (target, clamped) = clamp_to_screen((current_x, current_y), (new_x, new_y), screen);
+        if clamped {
+            debug!(
+                "Target ({}, {}) is off-screen, clamped to ({}, {})",
+                new_x, new_y, target.0, target.1
+            );
+            (new_x, new_y) = target;
+            hit_edge = true;
+        }
+        if (new_x, new_y) == (current_x, current_y) {
+            // Already at the edge: skip like a pinned region move
+            debug!("Cursor already at the screen edge this way, skipping move");
+            if let Ok(mut state_guard) = state.lock() {
+                state_guard.move_direction *= -1;
+            }
+            return Ok(MoveOutcome::Skipped);
+        }
+    }
+
+    // Last line of defence against a movement bug sending the cursor flying
+    let planned = Move::new(new_x - current_x, new_y - current_y);
+    let (capped, cut) = cap_move(planned, config.max_move_distance);
+    if cut {
+        warn!(
+            "Move by ({}, {}) exceeds max_move_distance {}, clamped to ({}, {})",
+            planned.dx, planned.dy, config.max_move_distance, capped.dx, capped.dy
+        );
+        (new_x, new_y) = capped.apply((current_x, current_y));
+    }
 
-    info!("Moving mouse by {} pixels to ({}, {})", delta, new_x, new_y);
+    log_move(
+        routine,
+        format_args!(
+            "Moving mouse by ({}, {}) to ({}, {})",
+            step.dx, step.dy, new_x, new_y
+        ),
+    );
+
+    let tolerance = rng::shared().with(|rng| jittered_tolerance(config, rng));
+    if config.listener_verify_ms.is_some() {
+        if let Ok(mut state_guard) = state.lock() {
+            state_guard
+                .move_echo
+                .expect((new_x, new_y), tolerance, Instant::now());
+        }
+    }
 
     // Move mouse
-    controller.move_mouse(new_x, new_y)?;
+    controller.smooth_move_to(new_x, new_y, config.smooth_move_steps, config.easing)?;
 
     // Verify movement
-    std::thread::sleep(std::time::Duration::from_millis(100));
-    let verified = controller.verify_position(new_x, new_y)?;
+    let verified = if let Some(timeout_ms) = config.verify_timeout_ms {
+        // The target is also `current + delta`, so this covers verify_by_delta too
+        controller.verify_until(
+            (new_x, new_y),
+            tolerance,
+            Duration::from_millis(config.verify_poll_ms),
+            Duration::from_millis(timeout_ms),
+        )?
+    } else if config.verify_by_delta {
+        std::thread::sleep(Duration::from_millis(config.verify_delay_ms));
+        controller.verify_delta(
+            (current_x, current_y),
+            new_x - current_x,
+            new_y - current_y,
+            tolerance,
+        )?
+    } else {
+        std::thread::sleep(Duration::from_millis(config.verify_delay_ms));
+        controller.verify_position(new_x, new_y, tolerance)?
+    };
+
+    let verified = match config.listener_verify_ms {
+        // Nothing to confirm the move without the listener
+        Some(window_ms) if verified && config.activity_mode == ActivityMode::Listener => {
+            let observed = wait_for_echo(
+                &state,
+                Duration::from_millis(window_ms),
+                Duration::from_millis(config.verify_poll_ms.max(1)),
+            );
+            if !observed {
+                warn!(
+                    "Cursor reached the target but the listener saw no move within {}ms",
+                    window_ms
+                );
+            }
+            observed
+        }
+        _ => verified,
+    };
 
     let mut state_guard = state
         .lock()
         .map_err(|e| RmmError::MouseControl(format!("Failed to lock state: {}", e)))?;
+    state_guard.move_echo.clear();
 
     if verified {
-        info!("Mouse movement verified successfully");
+        log_move(
+            routine,
+            format_args!("Mouse movement verified successfully"),
+        );
+        state_guard.count_move();
+        state_guard.metrics.record_move(metrics::local_today());
+        state_guard.events.record(
+            EventKind::Move,
+            format!("by ({}, {}) to ({}, {})", step.dx, step.dy, new_x, new_y),
+        );
+        if should_log_move_summary(
+            state_guard.total_moves(),
+            config.quiet_move_logs,
+            config.move_summary_every,
+        ) {
+            info!("{} mouse moves verified so far", state_guard.total_moves());
+        }
         state_guard.last_moved = Instant::now();
         state_guard.move_direction *= -1; // Alternate direction
+        if config.movement_pattern == MovementStrategy::Circle {
+            state_guard.orbit.advance();
+        }
         state_guard.error_count = 0;
+        state_guard.retry_after = None;
+        state_guard.permission_prompted = false;
+        state_guard.failure_notified = false;
     } else {
-        state_guard.error_count += 1;
-        warn!(
-            "Mouse movement verification failed (error count: {})",
-            state_guard.error_count
-        );
-
-        if state_guard.error_count >= 10 {
-            error!("Mouse movement failed 10 times! Please check system permissions.");
+        // Turn back from an edge, or on any failure when asked to
+        if hit_edge || config.flip_on_failure {
+            state_guard.move_direction *= -1;
+        }
+        let failed = record_failure(&mut state_guard, config.max_errors);
+        if config.failure_backoff_ms > 0 {
+            let wait = failure_backoff(
+                Duration::from_millis(config.failure_backoff_ms),
+                state_guard.error_count,
+            );
+            debug!("Retrying the move in {:?}", wait);
+            state_guard.retry_after = Some(Instant::now() + wait);
+        }
+        if failed {
+            if config.disable_on_max_errors {
+                warn!(
+                    "Pausing after {} failed moves; resume from the tray once fixed",
+                    state_guard.error_count
+                );
+                state_guard.set_running(false, Cause::Error);
+            }
+            permissions::notify_failures(
+                &mut state_guard,
+                config.notifications_enabled,
+                permissions::notify_in_background,
+            );
         }
     }
 
-    Ok(())
+    Ok(if verified {
+        MoveOutcome::Moved
+    } else {
+        MoveOutcome::Failed
+    })
 }
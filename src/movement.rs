@@ -0,0 +1,163 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Limit the size of a movement delta to `min..=max` pixels, keeping its sign.
+///
+/// Applied where the delta is used, so a bad value set at runtime (tray, IPC,
+/// reload) can't send the cursor flying. Swapped or negative bounds are
+/// normalized rather than rejected; [`crate::config::Config::validate`]
+/// refuses swapped ones in a config file.
+pub fn clamp_movement_delta(delta: i32, min: i32, max: i32) -> i32 {
+    let (min, max) = (min.unsigned_abs(), max.unsigned_abs());
+    let (low, high) = (min.min(max), min.max(max));
+    let magnitude = delta.unsigned_abs().clamp(low, high);
+    let magnitude = i32::try_from(magnitude).unwrap_or(i32::MAX);
+    if delta < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// A relative cursor movement in pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Move {
+    pub dx: i32,
+    pub dy: i32,
+}
+
+impl Move {
+    pub fn new(dx: i32, dy: i32) -> Self {
+        Self { dx, dy }
+    }
+
+    /// The same distance on both axes
+    pub fn diagonal(delta: i32) -> Self {
+        Self::new(delta, delta)
+    }
+
+    /// Multiply both axes, e.g. by the current direction (`1` or `-1`)
+    pub fn scale(self, factor: i32) -> Self {
+        Self::new(
+            self.dx.saturating_mul(factor),
+            self.dy.saturating_mul(factor),
+        )
+    }
+
+    pub fn negate(self) -> Self {
+        self.scale(-1)
+    }
+
+    /// Limit each axis to at most `max` pixels in either direction
+    pub fn clamp_to(self, max: i32) -> Self {
+        let max = max.abs();
+        Self::new(self.dx.clamp(-max, max), self.dy.clamp(-max, max))
+    }
+
+    /// Add a random offset of up to `amount` pixels on each axis
+    pub fn jitter<R: Rng + ?Sized>(self, amount: i32, rng: &mut R) -> Self {
+        let amount = amount.abs();
+        if amount == 0 {
+            return self;
+        }
+        Self::new(
+            self.dx.saturating_add(rng.gen_range(-amount..=amount)),
+            self.dy.saturating_add(rng.gen_range(-amount..=amount)),
+        )
+    }
+
+    /// The position reached by applying this move at `from`
+    pub fn apply(self, from: (i32, i32)) -> (i32, i32) {
+        (
+            from.0.saturating_add(self.dx),
+            from.1.saturating_add(self.dy),
+        )
+    }
+}
+
+/// Shape of a single triggered move
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementPattern {
+    /// Down and to the right (or back), the classic jiggle
+    #[default]
+    Diagonal,
+    Horizontal,
+    Vertical,
+    /// Diagonal with the vertical axis mirrored
+    AntiDiagonal,
+}
+
+impl MovementPattern {
+    /// The move for this pattern with `delta` pixels per axis
+    pub fn step(self, delta: i32) -> Move {
+        match self {
+            MovementPattern::Diagonal => Move::diagonal(delta),
+            MovementPattern::Horizontal => Move::new(delta, 0),
+            MovementPattern::Vertical => Move::new(0, delta),
+            MovementPattern::AntiDiagonal => Move::new(delta, -delta),
+        }
+    }
+}
+
+/// Pick one pattern from `set` at random; an empty set means [`MovementPattern::Diagonal`].
+pub fn choose_pattern<R: Rng + ?Sized>(set: &[MovementPattern], rng: &mut R) -> MovementPattern {
+    set.choose(rng).copied().unwrap_or_default()
+}
+
+/// How successive moves are laid out
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementStrategy {
+    /// Back and forth along a pattern from `pattern_set`
+    #[default]
+    Linear,
+    /// Around a small circle centered where the cursor was
+    Circle,
+}
+
+/// Angle advanced per circular move: an eighth of a turn
+pub const ORBIT_STEP: f64 = std::f64::consts::FRAC_PI_4;
+
+/// Rounding slack (pixels) before the cursor counts as having left the circle
+const ORBIT_SLACK: f64 = 2.0;
+
+/// Progress around the circle for [`MovementStrategy::Circle`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Orbit {
+    /// Set on the first move, and again whenever the cursor is found off the circle
+    pub center: Option<(i32, i32)>,
+    /// Radians
+    pub angle: f64,
+}
+
+/// The point at `angle` radians on a circle of `radius` around `center`
+pub fn orbit_point(center: (i32, i32), radius: i32, angle: f64) -> (i32, i32) {
+    let radius = f64::from(radius);
+    (
+        center.0 + (radius * angle.cos()).round() as i32,
+        center.1 + (radius * angle.sin()).round() as i32,
+    )
+}
+
+impl Orbit {
+    /// The next point to move to from `current`. Starts a new circle around
+    /// `current` if there is none yet or the cursor was moved away from it.
+    pub fn target(&mut self, current: (i32, i32), radius: i32) -> (i32, i32) {
+        let off_circle = self.center.is_none_or(|center| {
+            let distance = f64::from(current.0 - center.0).hypot(f64::from(current.1 - center.1));
+            distance > f64::from(radius.abs()) + ORBIT_SLACK
+        });
+        if off_circle {
+            self.center = Some(current);
+            self.angle = 0.0;
+        }
+        orbit_point(self.center.unwrap_or(current), radius, self.angle)
+    }
+
+    /// Move on to the next point after a completed move
+    pub fn advance(&mut self) {
+        self.angle = (self.angle + ORBIT_STEP) % std::f64::consts::TAU;
+    }
+}
@@ -0,0 +1,83 @@
+// One-shot mode for cron/launchd: a single check-and-move per invocation.
+//
+// There is no resident activity listener, so idleness comes from the OS and
+// the alternating move direction is persisted between runs.
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::mouse::{self, LazyController};
+use crate::state::{AppState, Cause};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// State carried from one invocation to the next
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct OneShotState {
+    pub move_direction: i32,
+}
+
+impl Default for OneShotState {
+    fn default() -> Self {
+        Self { move_direction: 1 }
+    }
+}
+
+impl OneShotState {
+    /// Load from `path`, falling back to defaults if missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Decide whether a one-shot run should move, given the OS idle time.
+/// Unknown idle time never moves, since we can't tell the user is away.
+pub fn should_move_once(system_idle_secs: Option<u64>, inactivity_threshold: u64) -> bool {
+    matches!(system_idle_secs, Some(idle) if idle >= inactivity_threshold)
+}
+
+/// Run one check-and-move using `system_idle_secs` as the idle source.
+/// Returns whether a move was attempted.
+pub fn run_once(config: &Config, system_idle_secs: Option<u64>, state_path: &Path) -> Result<bool> {
+    if !should_move_once(system_idle_secs, config.inactivity_threshold) {
+        info!(
+            "One-shot: user not idle long enough (idle: {:?}s), nothing to do",
+            system_idle_secs
+        );
+        return Ok(false);
+    }
+
+    let persisted = OneShotState::load(state_path);
+    let mut app_state = AppState::new();
+    app_state.set_running(true, Cause::Startup);
+    app_state.move_direction = persisted.move_direction;
+    let idle = Duration::from_secs(system_idle_secs.unwrap_or_default());
+    app_state.last_activity = Instant::now()
+        .checked_sub(idle)
+        .unwrap_or_else(Instant::now);
+
+    let state = Arc::new(Mutex::new(app_state));
+    mouse::check_and_move(Arc::clone(&state), config, &mut LazyController::default())?;
+
+    let move_direction = state
+        .lock()
+        .map(|s| s.move_direction)
+        .unwrap_or(persisted.move_direction);
+    OneShotState { move_direction }.save(state_path)?;
+    Ok(true)
+}
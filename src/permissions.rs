@@ -0,0 +1,81 @@
+use crate::platform;
+use crate::state::AppState;
+use tracing::{info, warn};
+
+/// What to do about OS permissions after a failed move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionAction {
+    Nothing,
+    /// Permission is missing: ask the user to grant it again
+    Prompt,
+}
+
+/// Decide whether to re-request permissions once failures reach the error
+/// threshold. Only prompts when the OS confirms the permission is missing,
+/// and only once until a move succeeds again.
+pub fn recheck_decision(
+    error_count: u32,
+    max_errors: u32,
+    trusted: Option<bool>,
+    already_prompted: bool,
+) -> PermissionAction {
+    if error_count < max_errors || already_prompted {
+        return PermissionAction::Nothing;
+    }
+    match trusted {
+        Some(false) => PermissionAction::Prompt,
+        _ => PermissionAction::Nothing,
+    }
+}
+
+pub const FAILURE_TITLE: &str = "RMM can't move the mouse";
+pub const FAILURE_BODY: &str = "Mouse movement keeps failing. Grant RMM accessibility/input \
+permissions in your system settings, then resume it from the tray.";
+
+/// Tell the user that moves keep failing, once until a move succeeds again.
+/// `notify` shows the notification. Returns whether it was shown.
+pub fn notify_failures(state: &mut AppState, enabled: bool, notify: fn(&str, &str)) -> bool {
+    if !enabled || state.failure_notified {
+        return false;
+    }
+    state.failure_notified = true;
+    notify(FAILURE_TITLE, FAILURE_BODY);
+    true
+}
+
+/// Show a desktop notification from its own thread, so a slow notification
+/// service doesn't hold up the caller.
+pub fn notify_in_background(title: &str, body: &str) {
+    let (title, body) = (title.to_string(), body.to_string());
+    std::thread::spawn(move || {
+        if !platform::notify(&title, &body) {
+            warn!("Could not show a desktop notification: {}", body);
+        }
+    });
+}
+
+/// Re-check accessibility permission after repeated failures and prompt the
+/// user to re-grant it if it was revoked (macOS); a no-op elsewhere.
+pub fn self_heal(state: &mut AppState, max_errors: u32) {
+    if state.error_count < max_errors || state.permission_prompted {
+        return;
+    }
+    let trusted = platform::accessibility_trusted();
+    match recheck_decision(
+        state.error_count,
+        max_errors,
+        trusted,
+        state.permission_prompted,
+    ) {
+        PermissionAction::Prompt => {
+            warn!("Accessibility permission was revoked, asking the user to grant it again");
+            platform::request_accessibility();
+            state.permission_prompted = true;
+        }
+        PermissionAction::Nothing => {
+            if trusted == Some(true) {
+                info!("Accessibility permission is granted; failures have another cause");
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+use dbus::blocking::Connection;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+const DRM_DIR: &str = "/sys/class/drm";
+const ACPI_LID_DIR: &str = "/proc/acpi/button/lid";
+const INPUT_DIR: &str = "/dev/input";
+
+pub fn display_count() -> Option<u32> {
+    // Each connector (e.g. card0-HDMI-A-1) exposes a `status` file
+    let entries = fs::read_dir(DRM_DIR).ok()?;
+    let mut connectors = 0;
+    let mut connected = 0;
+    for entry in entries.flatten() {
+        let status = entry.path().join("status");
+        if let Ok(value) = fs::read_to_string(status) {
+            connectors += 1;
+            if value.trim() == "connected" {
+                connected += 1;
+            }
+        }
+    }
+    // No connectors at all usually means a VM or container without DRM
+    if connectors == 0 {
+        None
+    } else {
+        Some(connected)
+    }
+}
+
+pub fn any_display_on() -> Option<bool> {
+    // Connected connectors report their DPMS state ("On"/"Off") in `dpms`
+    let entries = fs::read_dir(DRM_DIR).ok()?;
+    let mut known = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let connected = fs::read_to_string(path.join("status"))
+            .is_ok_and(|status| status.trim() == "connected");
+        if !connected {
+            continue;
+        }
+        if let Ok(dpms) = fs::read_to_string(path.join("dpms")) {
+            if dpms.trim() == "On" {
+                return Some(true);
+            }
+            known = true;
+        }
+    }
+    known.then_some(false)
+}
+
+pub fn system_idle_secs() -> Option<u64> {
+    // No portable X11/Wayland API without extra libraries, so ask the desktop
+    // over the session bus: GNOME's Mutter first, then the freedesktop
+    // screensaver (KDE), then X11's screensaver extension via xprintidle
+    let Ok(conn) = Connection::new_session() else {
+        return xprintidle_secs();
+    };
+    let timeout = Duration::from_millis(500);
+
+    let mutter = conn.with_proxy(
+        "org.gnome.Mutter.IdleMonitor",
+        "/org/gnome/Mutter/IdleMonitor/Core",
+        timeout,
+    );
+    if let Ok((idle_ms,)) =
+        mutter.method_call::<(u64,), _, _, _>("org.gnome.Mutter.IdleMonitor", "GetIdletime", ())
+    {
+        return Some(idle_ms / 1000);
+    }
+
+    let screensaver = conn.with_proxy(
+        "org.freedesktop.ScreenSaver",
+        "/org/freedesktop/ScreenSaver",
+        timeout,
+    );
+    screensaver
+        .method_call::<(u32,), _, _, _>("org.freedesktop.ScreenSaver", "GetSessionIdleTime", ())
+        .ok()
+        .map(|(idle_secs,)| idle_secs as u64)
+        .or_else(xprintidle_secs)
+}
+
+fn xprintidle_secs() -> Option<u64> {
+    // Prints the XScreenSaver idle time in milliseconds
+    std::env::var_os("DISPLAY")?;
+    let output = Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let idle_ms: u64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(idle_ms / 1000)
+}
+
+pub fn lid_closed() -> Option<bool> {
+    // e.g. /proc/acpi/button/lid/LID0/state contains "state:      closed"
+    let entries = fs::read_dir(ACPI_LID_DIR).ok()?;
+    for entry in entries.flatten() {
+        if let Ok(value) = fs::read_to_string(entry.path().join("state")) {
+            if value.contains("closed") {
+                return Some(true);
+            }
+            if value.contains("open") {
+                return Some(false);
+            }
+        }
+    }
+    None
+}
+
+pub fn input_device_count() -> Option<u32> {
+    // One evdev node (event0, event1, ...) per attached input device
+    let entries = fs::read_dir(INPUT_DIR).ok()?;
+    let count = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+        .count();
+    u32::try_from(count).ok()
+}
+
+pub fn user_present() -> Option<bool> {
+    // No public presence/attention sensor API to query
+    None
+}
+
+pub fn lock_screen() -> bool {
+    // Ask logind to lock the caller's session; the desktop's locker reacts to it
+    let Ok(conn) = Connection::new_system() else {
+        return false;
+    };
+    let session = conn.with_proxy(
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1/session/auto",
+        Duration::from_millis(500),
+    );
+    session
+        .method_call::<(), _, _, _>("org.freedesktop.login1.Session", "Lock", ())
+        .is_ok()
+}
+
+pub fn login_item_dir() -> Option<PathBuf> {
+    // XDG autostart entries are picked up by the desktop session at login
+    directories::BaseDirs::new().map(|dirs| dirs.config_dir().join("autostart"))
+}
+
+pub fn set_run_key(_enable: bool, _exe: &Path) -> bool {
+    false
+}
+
+pub fn accessibility_trusted() -> Option<bool> {
+    None
+}
+
+pub fn request_accessibility() {}
+
+pub fn is_session_locked() -> Option<bool> {
+    // logind's LockedHint, set by the desktop's screen locker
+    let conn = Connection::new_system().ok()?;
+    let session = conn.with_proxy(
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1/session/auto",
+        Duration::from_millis(500),
+    );
+    session
+        .get("org.freedesktop.login1.Session", "LockedHint")
+        .ok()
+}
+
+pub fn is_fullscreen_app_active() -> Option<bool> {
+    let window = active_window()?;
+    let state = xprop(&["-id", &window, "_NET_WM_STATE"])?;
+    Some(state.contains("_NET_WM_STATE_FULLSCREEN"))
+}
+
+pub fn focused_process_name() -> Option<String> {
+    // "_NET_WM_PID(CARDINAL) = 1234", then the process's command name
+    let window = active_window()?;
+    let pid = xprop(&["-id", &window, "_NET_WM_PID"])?;
+    let pid: u32 = pid.split_whitespace().last()?.parse().ok()?;
+    let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(comm.trim().to_string())
+}
+
+/// The focused X11 window's id, from the EWMH hints via xprop. Wayland has
+/// no protocol for asking about other clients' windows, so under Wayland
+/// only XWayland apps are seen.
+fn active_window() -> Option<String> {
+    std::env::var_os("DISPLAY")?;
+    // "_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007"
+    let active = xprop(&["-root", "_NET_ACTIVE_WINDOW"])?;
+    let window = active.split_whitespace().last()?;
+    let id = u64::from_str_radix(window.strip_prefix("0x")?, 16).ok()?;
+    // 0 when nothing has focus
+    (id != 0).then(|| window.to_string())
+}
+
+fn xprop(args: &[&str]) -> Option<String> {
+    let output = Command::new("xprop").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub fn notify(title: &str, body: &str) -> bool {
+    // The freedesktop notification service, provided by every major desktop
+    let Ok(conn) = Connection::new_session() else {
+        return false;
+    };
+    let notifications = conn.with_proxy(
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        Duration::from_millis(500),
+    );
+    let hints = dbus::arg::PropMap::new();
+    let actions: Vec<&str> = Vec::new();
+    notifications
+        .method_call::<(u32,), _, _, _>(
+            "org.freedesktop.Notifications",
+            "Notify",
+            ("RMM", 0u32, "", title, body, actions, hints, -1i32),
+        )
+        .is_ok()
+}
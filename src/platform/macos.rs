@@ -0,0 +1,233 @@
+use core_foundation::array::{CFArray, CFArrayRef};
+use core_foundation::base::{CFType, TCFType};
+use core_foundation::boolean::CFBoolean;
+use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+use core_foundation::number::CFNumber;
+use core_foundation::string::{CFString, CFStringRef};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGGetActiveDisplayList(max_displays: u32, displays: *mut u32, count: *mut u32) -> i32;
+    fn CGGetOnlineDisplayList(max_displays: u32, displays: *mut u32, count: *mut u32) -> i32;
+    fn CGDisplayIsAsleep(display: u32) -> u32;
+    fn CGSessionCopyCurrentDictionary() -> CFDictionaryRef;
+    fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+    fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> CFArrayRef;
+    fn CGDisplayBounds(display: u32) -> CGRect;
+}
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrusted() -> bool;
+    fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> bool;
+    static kAXTrustedCheckOptionPrompt: CFStringRef;
+}
+
+// kCGEventSourceStateCombinedSessionState
+const COMBINED_SESSION_STATE: i32 = 0;
+// kCGAnyInputEventType
+const ANY_INPUT_EVENT: u32 = !0;
+// kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements
+const ON_SCREEN_WINDOWS: u32 = (1 << 0) | (1 << 4);
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CGRect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+pub fn display_count() -> Option<u32> {
+    let mut count: u32 = 0;
+    // Passing a null list with max 0 only queries the count
+    let err = unsafe { CGGetActiveDisplayList(0, std::ptr::null_mut(), &mut count) };
+    if err == 0 {
+        Some(count)
+    } else {
+        None
+    }
+}
+
+pub fn any_display_on() -> Option<bool> {
+    // Online displays include sleeping ones, unlike the active list
+    const MAX_DISPLAYS: usize = 16;
+    let mut displays = [0u32; MAX_DISPLAYS];
+    let mut count: u32 = 0;
+    let err =
+        unsafe { CGGetOnlineDisplayList(MAX_DISPLAYS as u32, displays.as_mut_ptr(), &mut count) };
+    if err != 0 || count == 0 {
+        return None;
+    }
+    let online = &displays[..(count as usize).min(MAX_DISPLAYS)];
+    Some(
+        online
+            .iter()
+            .any(|&display| unsafe { CGDisplayIsAsleep(display) } == 0),
+    )
+}
+
+pub fn system_idle_secs() -> Option<u64> {
+    let secs =
+        unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT) };
+    if secs.is_finite() && secs >= 0.0 {
+        Some(secs as u64)
+    } else {
+        None
+    }
+}
+
+pub fn lid_closed() -> Option<bool> {
+    // The clamshell state is only published on machines that have a lid
+    let output = Command::new("ioreg")
+        .args(["-r", "-k", "AppleClamshellState", "-d", "4"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text
+        .lines()
+        .find(|l| l.contains("\"AppleClamshellState\""))?;
+    Some(line.contains("Yes"))
+}
+
+pub fn input_device_count() -> Option<u32> {
+    // Each matching HID service is printed as a "+-o" node
+    let output = Command::new("ioreg")
+        .args(["-r", "-c", "IOHIDDevice", "-d", "1"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let count = text
+        .lines()
+        .filter(|l| l.trim_start().starts_with("+-o"))
+        .count();
+    u32::try_from(count).ok()
+}
+
+pub fn user_present() -> Option<bool> {
+    // No public presence/attention sensor API to query
+    None
+}
+
+pub fn lock_screen() -> bool {
+    // Sleeping the display locks the session when a password is required after sleep
+    Command::new("pmset")
+        .arg("displaysleepnow")
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+pub fn login_item_dir() -> Option<PathBuf> {
+    // Per-user launch agents are loaded by launchd at login
+    directories::BaseDirs::new().map(|dirs| dirs.home_dir().join("Library/LaunchAgents"))
+}
+
+pub fn set_run_key(_enable: bool, _exe: &Path) -> bool {
+    false
+}
+
+pub fn accessibility_trusted() -> Option<bool> {
+    Some(unsafe { AXIsProcessTrusted() })
+}
+
+pub fn is_session_locked() -> Option<bool> {
+    // The key is only present while the screen is locked
+    let dict = unsafe { CGSessionCopyCurrentDictionary() };
+    if dict.is_null() {
+        return None;
+    }
+    let session: CFDictionary<CFString, CFType> =
+        unsafe { CFDictionary::wrap_under_create_rule(dict) };
+    let locked = session
+        .find(CFString::from_static_string("CGSSessionScreenIsLocked"))
+        .and_then(|value| value.downcast::<CFBoolean>())
+        .is_some_and(bool::from);
+    Some(locked)
+}
+
+pub fn is_fullscreen_app_active() -> Option<bool> {
+    // Fullscreen means the front window covers a whole display
+    let front = front_window()?;
+    let bounds = front.find(CFString::from_static_string("kCGWindowBounds"))?;
+    let bounds: WindowInfo =
+        unsafe { CFDictionary::wrap_under_get_rule(bounds.as_CFTypeRef() as CFDictionaryRef) };
+    let window = CGRect {
+        x: number(&bounds, "X")?,
+        y: number(&bounds, "Y")?,
+        width: number(&bounds, "Width")?,
+        height: number(&bounds, "Height")?,
+    };
+
+    const MAX_DISPLAYS: usize = 16;
+    let mut displays = [0u32; MAX_DISPLAYS];
+    let mut count: u32 = 0;
+    let err =
+        unsafe { CGGetActiveDisplayList(MAX_DISPLAYS as u32, displays.as_mut_ptr(), &mut count) };
+    if err != 0 {
+        return None;
+    }
+    let active = &displays[..(count as usize).min(MAX_DISPLAYS)];
+    Some(active.iter().any(|&display| {
+        let screen = unsafe { CGDisplayBounds(display) };
+        window.x <= screen.x
+            && window.y <= screen.y
+            && window.x + window.width >= screen.x + screen.width
+            && window.y + window.height >= screen.y + screen.height
+    }))
+}
+
+pub fn focused_process_name() -> Option<String> {
+    // The owning app's name, e.g. "zoom.us"
+    let front = front_window()?;
+    let owner = front.find(CFString::from_static_string("kCGWindowOwnerName"))?;
+    Some(owner.downcast::<CFString>()?.to_string())
+}
+
+type WindowInfo = CFDictionary<CFString, CFType>;
+
+/// The focused app's frontmost window. The window list runs front to back,
+/// and the first window on the normal layer (0) belongs to the focused app.
+fn front_window() -> Option<WindowInfo> {
+    let list = unsafe { CGWindowListCopyWindowInfo(ON_SCREEN_WINDOWS, 0) };
+    if list.is_null() {
+        return None;
+    }
+    let windows: CFArray = unsafe { CFArray::wrap_under_create_rule(list) };
+    windows.iter().find_map(|window| {
+        let window: WindowInfo =
+            unsafe { CFDictionary::wrap_under_get_rule(*window as CFDictionaryRef) };
+        (number(&window, "kCGWindowLayer") == Some(0.0)).then_some(window)
+    })
+}
+
+fn number(dict: &WindowInfo, key: &'static str) -> Option<f64> {
+    dict.find(CFString::from_static_string(key))
+        .and_then(|value| value.downcast::<CFNumber>())
+        .and_then(|value| value.to_f64())
+}
+
+pub fn notify(title: &str, body: &str) -> bool {
+    // AppleScript string literals only need quotes and backslashes escaped
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!(
+        "display notification {} with title {}",
+        quote(body),
+        quote(title)
+    );
+    Command::new("osascript")
+        .args(["-e", &script])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+pub fn request_accessibility() {
+    let key = unsafe { CFString::wrap_under_get_rule(kAXTrustedCheckOptionPrompt) };
+    let options = CFDictionary::from_CFType_pairs(&[(key, CFBoolean::true_value())]);
+    unsafe { AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef()) };
+}
@@ -0,0 +1,106 @@
+// Platform-specific system queries. Each function is best effort and returns
+// `None` when the information isn't available on the current platform.
+
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+use linux as imp;
+#[cfg(target_os = "macos")]
+use macos as imp;
+#[cfg(target_os = "windows")]
+use windows as imp;
+
+/// Number of displays currently connected, if it can be determined.
+pub fn display_count() -> Option<u32> {
+    imp::display_count()
+}
+
+/// Whether at least one connected display is powered on (not blanked by
+/// power management), if it can be determined.
+pub fn any_display_on() -> Option<bool> {
+    imp::any_display_on()
+}
+
+/// Seconds since the last keyboard or mouse input anywhere in the session,
+/// as reported by the OS, if it can be determined.
+pub fn system_idle_secs() -> Option<u64> {
+    imp::system_idle_secs()
+}
+
+/// Whether the laptop lid is closed, if it can be determined.
+pub fn lid_closed() -> Option<bool> {
+    imp::lid_closed()
+}
+
+/// Number of attached input devices (keyboards, mice, ...), if it can be
+/// determined. Only changes in the count are meaningful.
+pub fn input_device_count() -> Option<u32> {
+    imp::input_device_count()
+}
+
+/// Whether the OS reports that the user is physically present (e.g. via an
+/// attention or presence sensor), if it can be determined.
+pub fn user_present() -> Option<bool> {
+    imp::user_present()
+}
+
+/// Lock the screen (the user has to log back in). Returns whether the
+/// request was accepted.
+pub fn lock_screen() -> bool {
+    imp::lock_screen()
+}
+
+/// Directory holding file-based login items (XDG autostart, LaunchAgents),
+/// or `None` where login items aren't files (Windows).
+pub fn login_item_dir() -> Option<PathBuf> {
+    imp::login_item_dir()
+}
+
+/// Add (or remove) `exe` under the Windows `Run` registry key. Returns
+/// whether the change was made; always `false` elsewhere.
+pub fn set_run_key(enable: bool, exe: &Path) -> bool {
+    imp::set_run_key(enable, exe)
+}
+
+/// Whether the process is trusted for accessibility (macOS). `None` where
+/// the concept doesn't apply.
+pub fn accessibility_trusted() -> Option<bool> {
+    imp::accessibility_trusted()
+}
+
+/// Ask the user to grant accessibility permission (shows the macOS system prompt).
+pub fn request_accessibility() {
+    imp::request_accessibility()
+}
+
+/// Whether the user's session is locked (lock screen showing), if it can be
+/// determined.
+pub fn is_session_locked() -> Option<bool> {
+    imp::is_session_locked()
+}
+
+/// Whether the focused application is fullscreen (a slideshow, video or
+/// game), if it can be determined. On Linux only X11 windows are visible, so
+/// native Wayland apps are never reported.
+pub fn is_fullscreen_app_active() -> Option<bool> {
+    imp::is_fullscreen_app_active()
+}
+
+/// Name of the process owning the focused window ("zoom", "zoom.us",
+/// "Zoom.exe"), if it can be determined. Same X11-only caveat on Linux as
+/// [`is_fullscreen_app_active`].
+pub fn focused_process_name() -> Option<String> {
+    imp::focused_process_name()
+}
+
+/// Show a desktop notification. Returns whether it was handed to the OS.
+pub fn notify(title: &str, body: &str) -> bool {
+    imp::notify(title, body)
+}
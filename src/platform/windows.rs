@@ -0,0 +1,185 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{CloseHandle, RECT};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONULL,
+};
+use windows::Win32::System::Shutdown::LockWorkStation;
+use windows::Win32::System::StationsAndDesktops::{
+    CloseDesktop, OpenInputDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_SWITCHDESKTOP,
+};
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+use windows::Win32::UI::Input::{GetRawInputDeviceList, RAWINPUTDEVICELIST};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetDesktopWindow, GetForegroundWindow, GetShellWindow, GetSystemMetrics, GetWindowLongW,
+    GetWindowRect, GetWindowThreadProcessId, GWL_STYLE, SM_CMONITORS, WS_CAPTION,
+};
+
+pub fn display_count() -> Option<u32> {
+    let count = unsafe { GetSystemMetrics(SM_CMONITORS) };
+    u32::try_from(count).ok()
+}
+
+pub fn any_display_on() -> Option<bool> {
+    // Monitor power state is only delivered via power setting notifications to a window
+    None
+}
+
+pub fn system_idle_secs() -> Option<u64> {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return None;
+    }
+    // Both values are milliseconds since boot and wrap together every ~49 days
+    let now = unsafe { GetTickCount() };
+    Some((now.wrapping_sub(info.dwTime) / 1000) as u64)
+}
+
+pub fn lid_closed() -> Option<bool> {
+    // Lid state is only delivered via power setting notifications to a window
+    None
+}
+
+pub fn input_device_count() -> Option<u32> {
+    // With no buffer, the call only reports how many devices there are
+    let mut count = 0u32;
+    let result = unsafe {
+        GetRawInputDeviceList(
+            None,
+            &mut count,
+            std::mem::size_of::<RAWINPUTDEVICELIST>() as u32,
+        )
+    };
+    if result == u32::MAX {
+        None
+    } else {
+        Some(count)
+    }
+}
+
+pub fn user_present() -> Option<bool> {
+    // No public presence/attention sensor API to query
+    None
+}
+
+pub fn lock_screen() -> bool {
+    unsafe { LockWorkStation() }.is_ok()
+}
+
+pub fn login_item_dir() -> Option<PathBuf> {
+    // Login items live in the registry instead
+    None
+}
+
+pub fn set_run_key(enable: bool, exe: &Path) -> bool {
+    // Per-user Run key, read by Explorer at login
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    let reg = |args: &[&str]| {
+        Command::new("reg")
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    };
+    if enable {
+        let value = format!("\"{}\"", exe.display());
+        reg(&[
+            "add", RUN_KEY, "/v", "RMM", "/t", "REG_SZ", "/d", &value, "/f",
+        ])
+    } else {
+        // Nothing to remove unless a previous run added the value
+        !reg(&["query", RUN_KEY, "/v", "RMM"]) || reg(&["delete", RUN_KEY, "/v", "RMM", "/f"])
+    }
+}
+
+pub fn accessibility_trusted() -> Option<bool> {
+    None
+}
+
+pub fn request_accessibility() {}
+
+pub fn is_session_locked() -> Option<bool> {
+    // The input desktop can't be opened while the secure (lock) desktop is up
+    match unsafe { OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_SWITCHDESKTOP) } {
+        Ok(desktop) => {
+            let _ = unsafe { CloseDesktop(desktop) };
+            Some(false)
+        }
+        Err(_) => Some(true),
+    }
+}
+
+pub fn is_fullscreen_app_active() -> Option<bool> {
+    // A captionless foreground window covering its whole monitor, the way
+    // slideshows, players and borderless games present themselves
+    let window = unsafe { GetForegroundWindow() };
+    if window.0 == 0
+        || window == unsafe { GetDesktopWindow() }
+        || window == unsafe { GetShellWindow() }
+    {
+        return Some(false);
+    }
+    let style = unsafe { GetWindowLongW(window, GWL_STYLE) } as u32;
+    if style & WS_CAPTION.0 == WS_CAPTION.0 {
+        return Some(false);
+    }
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(window, &mut rect) }.ok()?;
+    let monitor = unsafe { MonitorFromWindow(window, MONITOR_DEFAULTTONULL) };
+    if monitor.is_invalid() {
+        return None;
+    }
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    if !unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
+        return None;
+    }
+    let screen = info.rcMonitor;
+    Some(
+        rect.left <= screen.left
+            && rect.top <= screen.top
+            && rect.right >= screen.right
+            && rect.bottom >= screen.bottom,
+    )
+}
+
+pub fn focused_process_name() -> Option<String> {
+    // The foreground window's process image, e.g. "Zoom.exe"
+    let window = unsafe { GetForegroundWindow() };
+    if window.0 == 0 {
+        return None;
+    }
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(window, Some(&mut pid)) };
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+    let mut path = [0u16; 1024];
+    let mut len = path.len() as u32;
+    let queried = unsafe {
+        QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(path.as_mut_ptr()),
+            &mut len,
+        )
+    };
+    let _ = unsafe { CloseHandle(process) };
+    queried.ok()?;
+    let path = PathBuf::from(String::from_utf16_lossy(&path[..len as usize]));
+    Some(path.file_name()?.to_string_lossy().into_owned())
+}
+
+pub fn notify(_title: &str, _body: &str) -> bool {
+    // Toast notifications need WinRT bindings; the log has the same message
+    false
+}
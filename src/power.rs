@@ -0,0 +1,86 @@
+use crate::platform;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Heartbeats arriving this much later than expected (by the wall clock) are
+/// treated as a resume from sleep
+const WAKE_GAP_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Whether the laptop lid is closed. `None` when unknown or there is no lid.
+pub fn lid_closed() -> Option<bool> {
+    platform::lid_closed()
+}
+
+/// Whether moves should be paused for the lid state. Unknown state never
+/// pauses. `lid_closed` is only queried when `pause_when_lid_closed` is set.
+pub fn should_pause_for_lid(
+    pause_when_lid_closed: bool,
+    lid_closed: impl FnOnce() -> Option<bool>,
+) -> bool {
+    pause_when_lid_closed && lid_closed() == Some(true)
+}
+
+/// Whether at least one display is powered on. `None` when unknown.
+pub fn any_display_on() -> Option<bool> {
+    platform::any_display_on()
+}
+
+/// Whether moves should be skipped because every display is off. Unknown
+/// state never skips. `display_on` is only queried when `require_display_on`
+/// is set.
+pub fn should_skip_for_display_off(
+    require_display_on: bool,
+    display_on: impl FnOnce() -> Option<bool>,
+) -> bool {
+    require_display_on && display_on() == Some(false)
+}
+
+/// Lock the screen. Returns whether the platform accepted the request.
+pub fn lock_screen() -> bool {
+    platform::lock_screen()
+}
+
+/// Whether the screen should be locked after `idle`: only when `lock_after_secs`
+/// is set, the user has been idle at least that long, and RMM is still running
+/// (after locking it pauses, so this fires once).
+pub fn should_lock_screen(lock_after_secs: Option<u64>, idle: Duration, running: bool) -> bool {
+    running && lock_after_secs.is_some_and(|after| idle >= Duration::from_secs(after))
+}
+
+/// Detects resume from sleep by watching for wall-clock gaps between heartbeats.
+///
+/// The monotonic clock doesn't advance while suspended on macOS/Linux, so the
+/// wall clock is the reliable signal that a long time passed between ticks.
+pub struct WakeDetector {
+    expected_interval: Duration,
+    last_tick: SystemTime,
+}
+
+impl WakeDetector {
+    pub fn new(expected_interval: Duration) -> Self {
+        Self::new_at(expected_interval, SystemTime::now())
+    }
+
+    pub fn new_at(expected_interval: Duration, now: SystemTime) -> Self {
+        Self {
+            expected_interval,
+            last_tick: now,
+        }
+    }
+
+    /// Expect `interval` between heartbeats, e.g. after the interval was changed at runtime
+    pub fn set_expected_interval(&mut self, interval: Duration) {
+        self.expected_interval = interval;
+    }
+
+    /// Record a heartbeat at `now`; returns true if the machine appears to have slept since the last one.
+    pub fn tick(&mut self, now: SystemTime) -> bool {
+        let gap = now.duration_since(self.last_tick).unwrap_or_default();
+        self.last_tick = now;
+        gap > self.expected_interval + WAKE_GAP_THRESHOLD
+    }
+}
+
+/// Whether moves are held back because the machine recently woke up.
+pub fn in_wake_grace(grace_until: Option<Instant>, now: Instant) -> bool {
+    matches!(grace_until, Some(until) if now < until)
+}
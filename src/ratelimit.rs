@@ -0,0 +1,68 @@
+use std::time::Instant;
+
+/// Token bucket limiting how many synthetic moves may happen per minute.
+///
+/// Every path that moves the cursor must call [`MoveBudget::try_acquire`] first,
+/// so no combination of triggers can exceed the configured rate.
+#[derive(Debug, Clone)]
+pub struct MoveBudget {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl MoveBudget {
+    /// Create a full bucket allowing `max_moves_per_minute` moves. Zero means unlimited.
+    pub fn new(max_moves_per_minute: u32) -> Self {
+        Self::new_at(max_moves_per_minute, Instant::now())
+    }
+
+    pub fn new_at(max_moves_per_minute: u32, now: Instant) -> Self {
+        let capacity = max_moves_per_minute as f64;
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            last_refill: now,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.capacity == 0.0
+    }
+
+    /// Take one token if available at `now`.
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        if self.is_unlimited() {
+            return true;
+        }
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whole tokens currently available at `now`.
+    pub fn available(&mut self, now: Instant) -> u32 {
+        self.refill(now);
+        self.tokens as u32
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+impl Default for MoveBudget {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
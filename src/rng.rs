@@ -0,0 +1,49 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::sync::{Mutex, OnceLock};
+use tracing::info;
+
+/// Random number generator shared by every randomized behavior (jitter,
+/// patterns, direction).
+///
+/// Seeding it with `random_seed` makes a run reproducible for debugging;
+/// without a seed it draws from OS entropy.
+pub struct SharedRng {
+    inner: Mutex<StdRng>,
+}
+
+impl SharedRng {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            inner: Mutex::new(make_rng(seed)),
+        }
+    }
+
+    /// Replace the generator, e.g. once the configuration has been loaded.
+    pub fn reseed(&self, seed: Option<u64>) {
+        if let Some(seed) = seed {
+            info!("Using fixed random seed {}", seed);
+        }
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        *inner = make_rng(seed);
+    }
+
+    /// Run `f` with exclusive access to the generator.
+    pub fn with<T>(&self, f: impl FnOnce(&mut StdRng) -> T) -> T {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut inner)
+    }
+}
+
+fn make_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Process-wide generator, unseeded until [`SharedRng::reseed`] is called
+pub fn shared() -> &'static SharedRng {
+    static RNG: OnceLock<SharedRng> = OnceLock::new();
+    RNG.get_or_init(|| SharedRng::new(None))
+}
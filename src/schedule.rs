@@ -0,0 +1,89 @@
+use crate::config::Config;
+use chrono::{Duration, NaiveDateTime};
+use std::fmt::Write;
+
+/// Span covered by `--show-schedule`
+pub const SHOW_SPAN_HOURS: i64 = 24;
+
+/// Whether RMM may move the mouse at local time `at` under `config`.
+///
+/// RMM is active within its working hours (around the clock if none are
+/// set), except in maintenance windows, which always win.
+pub fn is_active_at(config: &Config, at: NaiveDateTime) -> bool {
+    !in_maintenance(config, at) && in_working_hours(config, at)
+}
+
+/// Whether local time `at` falls within the configured working hours.
+pub fn in_working_hours(config: &Config, at: NaiveDateTime) -> bool {
+    config
+        .working_hours()
+        .is_none_or(|hours| hours.contains(at))
+}
+
+/// Whether local time `at` falls in one of `maintenance_windows`.
+pub fn in_maintenance(config: &Config, at: NaiveDateTime) -> bool {
+    config
+        .maintenance_windows
+        .iter()
+        .any(|window| window.contains(at))
+}
+
+/// A contiguous stretch of time that is either active or inactive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub active: bool,
+}
+
+/// Sample `is_active` every `step` from `from` for `span` and merge equal
+/// neighbouring samples into windows. Boundaries are only as precise as `step`.
+pub fn summarize_windows(
+    from: NaiveDateTime,
+    span: Duration,
+    step: Duration,
+    is_active: impl Fn(NaiveDateTime) -> bool,
+) -> Vec<Window> {
+    let end = from + span;
+    let mut windows: Vec<Window> = Vec::new();
+    let mut at = from;
+    while at < end {
+        let next = (at + step).min(end);
+        let active = is_active(at);
+        match windows.last_mut() {
+            Some(last) if last.active == active => last.end = next,
+            _ => windows.push(Window {
+                start: at,
+                end: next,
+                active,
+            }),
+        }
+        at = next;
+    }
+    windows
+}
+
+/// Windows for the next 24 hours from `from` under `config`, at minute resolution.
+pub fn upcoming_windows(config: &Config, from: NaiveDateTime) -> Vec<Window> {
+    summarize_windows(
+        from,
+        Duration::hours(SHOW_SPAN_HOURS),
+        Duration::minutes(1),
+        |at| is_active_at(config, at),
+    )
+}
+
+/// One line per window, e.g. `2024-01-01 09:00 - 2024-01-01 17:00  active`
+pub fn format_windows(windows: &[Window]) -> String {
+    let mut out = String::new();
+    for window in windows {
+        let _ = writeln!(
+            out,
+            "{} - {}  {}",
+            window.start.format("%Y-%m-%d %H:%M"),
+            window.end.format("%Y-%m-%d %H:%M"),
+            if window.active { "active" } else { "inactive" }
+        );
+    }
+    out
+}
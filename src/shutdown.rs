@@ -0,0 +1,123 @@
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{debug, info};
+
+type Hook = Box<dyn FnOnce() + Send>;
+
+/// Registry of cleanup closures run when the application exits.
+///
+/// Features that create resources (pidfiles, lockfiles, sockets, ...) register
+/// a hook; hooks run once, in reverse registration order (LIFO), so later
+/// features are torn down before the ones they may depend on.
+#[derive(Default)]
+pub struct ShutdownHooks {
+    hooks: Mutex<Vec<(String, Hook)>>,
+}
+
+impl ShutdownHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, name: impl Into<String>, hook: impl FnOnce() + Send + 'static) {
+        if let Ok(mut hooks) = self.hooks.lock() {
+            hooks.push((name.into(), Box::new(hook)));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hooks.lock().map(|h| h.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run and remove all registered hooks, most recently registered first.
+    pub fn run(&self) {
+        let hooks = match self.hooks.lock() {
+            Ok(mut hooks) => std::mem::take(&mut *hooks),
+            Err(_) => return,
+        };
+        if !hooks.is_empty() {
+            info!("Running {} shutdown hook(s)", hooks.len());
+        }
+        for (name, hook) in hooks.into_iter().rev() {
+            debug!("Shutdown hook: {}", name);
+            hook();
+        }
+    }
+}
+
+/// Process-wide hook registry
+pub fn hooks() -> &'static ShutdownHooks {
+    static HOOKS: OnceLock<ShutdownHooks> = OnceLock::new();
+    HOOKS.get_or_init(ShutdownHooks::new)
+}
+
+/// Cooperative stop request shared by the main thread and the background loops.
+///
+/// The tray's Stop/Quit (or the heartbeat itself) requests shutdown; loops
+/// sleep through [`ShutdownSignal::sleep`], which returns as soon as that
+/// happens, finish their current iteration and return so they can be joined.
+pub struct ShutdownSignal {
+    requested: Mutex<bool>,
+    changed: Condvar,
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownSignal {
+    /// `const` so a signal can live in a `static`
+    pub const fn new() -> Self {
+        Self {
+            requested: Mutex::new(false),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Ask every loop to stop. Repeated requests are harmless.
+    pub fn request(&self) {
+        if let Ok(mut requested) = self.requested.lock() {
+            *requested = true;
+            self.changed.notify_all();
+        }
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.requested.lock().map(|r| *r).unwrap_or(true)
+    }
+
+    /// Sleep for `duration`, waking early on a request. Returns whether
+    /// shutdown has been requested, i.e. whether the caller should stop.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let Ok(requested) = self.requested.lock() else {
+            return true;
+        };
+        match self
+            .changed
+            .wait_timeout_while(requested, duration, |requested| !*requested)
+        {
+            Ok((requested, _)) => *requested,
+            Err(_) => true,
+        }
+    }
+
+    /// Block until shutdown is requested
+    pub fn wait(&self) {
+        let Ok(requested) = self.requested.lock() else {
+            return;
+        };
+        drop(self.changed.wait_while(requested, |requested| !*requested));
+    }
+}
+
+/// Process-wide shutdown signal
+pub fn signal() -> &'static ShutdownSignal {
+    static SIGNAL: ShutdownSignal = ShutdownSignal::new();
+    &SIGNAL
+}
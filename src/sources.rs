@@ -0,0 +1,216 @@
+// Pluggable activity inputs.
+//
+// Each detector implements `ActivitySource` and reports user activity as
+// `ActivitySignal`s. A `SourceRegistry` polls them all and folds the signals
+// into the shared state, where they feed the idle decision. The heartbeat
+// polls right before deciding to move and the worker on its own interval.
+
+use crate::platform;
+use crate::state::{AppState, InputKind, SharedState};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The OS reports idle time in whole seconds
+const OS_IDLE_RESOLUTION: Duration = Duration::from_secs(1);
+
+/// Upper bound on signals taken from one source per poll, so a source that
+/// never runs dry can't stall the loop
+const MAX_SIGNALS_PER_POLL: usize = 1024;
+
+/// User activity of `kind` seen at `at`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivitySignal {
+    pub kind: InputKind,
+    pub at: Instant,
+}
+
+impl ActivitySignal {
+    pub fn new(kind: InputKind, at: Instant) -> Self {
+        Self { kind, at }
+    }
+}
+
+/// A detector of user activity
+pub trait ActivitySource: Send {
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+
+    /// The next pending signal, or `None` when there is nothing new
+    fn poll(&mut self) -> Option<ActivitySignal>;
+}
+
+/// The registered sources, polled together
+#[derive(Default)]
+pub struct SourceRegistry {
+    sources: Vec<Box<dyn ActivitySource>>,
+}
+
+pub type SharedSources = Arc<Mutex<SourceRegistry>>;
+
+impl SourceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, source: Box<dyn ActivitySource>) {
+        self.sources.push(source);
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.sources.iter().map(|source| source.name()).collect()
+    }
+
+    /// Drain every source into `state`. Returns how many signals were applied.
+    pub fn poll_into(&mut self, state: &mut AppState) -> usize {
+        let mut applied = 0;
+        for source in &mut self.sources {
+            for _ in 0..MAX_SIGNALS_PER_POLL {
+                let Some(signal) = source.poll() else {
+                    break;
+                };
+                if apply_signal(state, signal) {
+                    applied += 1;
+                }
+            }
+        }
+        applied
+    }
+}
+
+/// Record `signal` as input unless it adds nothing: older than the input
+/// already known for its kind, or OS idle time reset by our own last move.
+/// Returns whether it was recorded.
+pub fn apply_signal(state: &mut AppState, signal: ActivitySignal) -> bool {
+    if state
+        .last_input
+        .get(&signal.kind)
+        .is_some_and(|known| signal.at <= *known)
+    {
+        return false;
+    }
+    if signal.kind == InputKind::System && signal.at <= state.last_moved + OS_IDLE_RESOLUTION {
+        return false;
+    }
+    state.last_input.insert(signal.kind, signal.at);
+    state.last_activity = state.last_activity.max(signal.at);
+    true
+}
+
+/// Latest input time of each kind, shared between the `rdev` listener thread,
+/// which reports into it, and its [`ListenerSource`]. Only the newest input of
+/// a kind matters to the idle decision, so a flood of mouse moves between two
+/// polls takes one slot instead of queueing a signal each.
+#[derive(Clone, Default)]
+pub struct LatestInput {
+    latest: Arc<Mutex<BTreeMap<InputKind, Instant>>>,
+}
+
+impl LatestInput {
+    /// Record `signal`, unless newer input of its kind is already waiting
+    pub fn report(&self, signal: ActivitySignal) {
+        let mut latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        let at = latest.entry(signal.kind).or_insert(signal.at);
+        *at = (*at).max(signal.at);
+    }
+
+    fn take(&self) -> Option<ActivitySignal> {
+        let mut latest = self.latest.lock().unwrap_or_else(|e| e.into_inner());
+        latest
+            .pop_first()
+            .map(|(kind, at)| ActivitySignal::new(kind, at))
+    }
+}
+
+/// Keyboard and mouse input forwarded by the `rdev` listener thread
+pub struct ListenerSource {
+    input: LatestInput,
+}
+
+impl ListenerSource {
+    /// The source plus the handle the listener reports input on
+    pub fn pair() -> (Self, LatestInput) {
+        let input = LatestInput::default();
+        (
+            Self {
+                input: input.clone(),
+            },
+            input,
+        )
+    }
+}
+
+impl ActivitySource for ListenerSource {
+    fn name(&self) -> &'static str {
+        "listener"
+    }
+
+    fn poll(&mut self) -> Option<ActivitySignal> {
+        self.input.take()
+    }
+}
+
+/// Input seen by the OS (including devices the listener can't hook), derived
+/// from the system idle time
+pub struct OsIdleSource {
+    read_idle_secs: fn() -> Option<u64>,
+    last_reported: Option<Instant>,
+}
+
+impl Default for OsIdleSource {
+    fn default() -> Self {
+        Self::with_reader(platform::system_idle_secs)
+    }
+}
+
+impl OsIdleSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read idle seconds with `read_idle_secs` instead of asking the OS
+    pub fn with_reader(read_idle_secs: fn() -> Option<u64>) -> Self {
+        Self {
+            read_idle_secs,
+            last_reported: None,
+        }
+    }
+}
+
+impl ActivitySource for OsIdleSource {
+    fn name(&self) -> &'static str {
+        "os_idle"
+    }
+
+    fn poll(&mut self) -> Option<ActivitySignal> {
+        let idle = Duration::from_secs((self.read_idle_secs)()?);
+        let at = Instant::now().checked_sub(idle)?;
+        // The same input read again, give or take rounding
+        if self
+            .last_reported
+            .is_some_and(|last| at <= last + OS_IDLE_RESOLUTION)
+        {
+            return None;
+        }
+        self.last_reported = Some(at);
+        Some(ActivitySignal::new(InputKind::System, at))
+    }
+}
+
+/// Poll `sources` into `state`, as the heartbeat and worker do each tick
+pub fn poll_shared(sources: &SharedSources, state: &SharedState) {
+    let Ok(mut sources) = sources.lock() else {
+        return;
+    };
+    if let Ok(mut state) = state.lock() {
+        sources.poll_into(&mut state);
+    }
+}
@@ -1,12 +1,143 @@
+use crate::activity::{MoveEcho, VelocityTracker};
+use crate::config::{Config, KeepAwakeMode};
+use crate::events::{EventKind, EventLog};
+use crate::metrics::{Metrics, Stats};
+use crate::movement::Orbit;
+use crate::ratelimit::MoveBudget;
+use crate::rng;
+use rand::Rng;
+use rdev::Button;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Kind of user input reported by the activity monitor
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum InputKind {
+    Keyboard,
+    Mouse,
+    Button,
+    /// An input device was plugged in or removed
+    Device,
+    /// Input the OS saw, from its idle time
+    System,
+}
+
+/// Why the running state changed, recorded with every transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cause {
+    Startup,
+    Tray,
+    Hotkey,
+    Schedule,
+    Ipc,
+    Error,
+    /// The user was idle long enough to lock the screen
+    Idle,
+    Shutdown,
+}
+
+impl Cause {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Startup => "startup",
+            Self::Tray => "tray",
+            Self::Hotkey => "hotkey",
+            Self::Schedule => "schedule",
+            Self::Ipc => "ipc",
+            Self::Error => "error",
+            Self::Idle => "idle",
+            Self::Shutdown => "shutdown",
+        }
+    }
+}
+
+fn running_label(running: bool) -> &'static str {
+    if running {
+        "running"
+    } else {
+        "stopped"
+    }
+}
+
+/// Coarse state shown by the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateKind {
+    Active,
+    Paused,
+    /// `max_errors` reached
+    Error,
+}
+
+impl StateKind {
+    pub fn of(state: &AppState, max_errors: u32) -> Self {
+        if state.error_count >= max_errors {
+            Self::Error
+        } else if !state.is_running {
+            Self::Paused
+        } else {
+            Self::Active
+        }
+    }
+
+    /// Tray icon for this state, under `resources/`
+    pub fn icon_file(self) -> &'static str {
+        match self {
+            Self::Active => "mouse.png",
+            Self::Paused => "mouse-paused.png",
+            Self::Error => "mouse-error.png",
+        }
+    }
+}
 
 pub struct AppState {
     pub is_running: bool,
     pub last_activity: Instant,
+    pub last_input: BTreeMap<InputKind, Instant>,
     pub last_moved: Instant,
     pub move_direction: i32,
+    /// Position on the circle when moving with `movement_pattern: circle`
+    pub orbit: Orbit,
+    /// Current keep-awake method, switchable at runtime
+    pub keep_awake_mode: KeepAwakeMode,
     pub error_count: u32,
+    pub move_budget: MoveBudget,
+    /// Moves are held back until this instant after a resume from sleep
+    pub wake_grace_until: Option<Instant>,
+    /// Moves are held back until this instant after a snooze from the tray
+    pub snooze_until: Option<Instant>,
+    /// Moves are held back until this instant after a failed move
+    pub retry_after: Option<Instant>,
+    /// Number of heartbeat ticks processed
+    pub heartbeats: u64,
+    /// Set once the user has been asked to re-grant permissions, until a move succeeds
+    pub permission_prompted: bool,
+    /// Set once the user has been notified about repeated failures, until a move succeeds
+    pub failure_notified: bool,
+    /// Moves that passed verification, see [`AppState::total_moves`]
+    total_moves: u64,
+    /// Moves that failed verification, see [`AppState::failed_moves`]
+    failed_moves: u64,
+    /// When this state was created, i.e. when RMM started
+    pub started_at: Instant,
+    /// Counts from earlier runs, loaded from `stats.json`
+    pub lifetime: Stats,
+    /// Per-day move count, persisted by the heartbeat
+    pub metrics: Metrics,
+    /// Recent significant events, for `GET /events`
+    pub events: EventLog,
+    /// Mouse buttons currently held down
+    pub held_buttons: HashSet<Button>,
+    pub velocity: VelocityTracker,
+    /// The last issued move, until the listener reports it
+    pub move_echo: MoveEcho,
+    /// Input events before this instant come from our own moves and don't count as activity
+    pub ignore_input_until: Option<Instant>,
+    /// Consecutive checks skipped because the user was active
+    pub active_skips: u64,
 }
 
 impl Default for AppState {
@@ -21,11 +152,229 @@ impl AppState {
         Self {
             is_running: false,
             last_activity: now,
+            last_input: BTreeMap::new(),
             last_moved: now,
             move_direction: 1,
+            orbit: Orbit::default(),
+            keep_awake_mode: KeepAwakeMode::default(),
             error_count: 0,
+            move_budget: MoveBudget::default(),
+            wake_grace_until: None,
+            snooze_until: None,
+            retry_after: None,
+            heartbeats: 0,
+            permission_prompted: false,
+            failure_notified: false,
+            total_moves: 0,
+            failed_moves: 0,
+            started_at: now,
+            lifetime: Stats::default(),
+            metrics: Metrics::default(),
+            events: EventLog::default(),
+            held_buttons: HashSet::new(),
+            velocity: VelocityTracker::default(),
+            move_echo: MoveEcho::default(),
+            ignore_input_until: None,
+            active_skips: 0,
         }
     }
+
+    /// Moves that passed verification since start
+    pub fn total_moves(&self) -> u64 {
+        self.total_moves
+    }
+
+    /// Moves that failed verification since start
+    pub fn failed_moves(&self) -> u64 {
+        self.failed_moves
+    }
+
+    /// Count a move that passed verification (or a key tap or scroll, which
+    /// can't be verified)
+    pub fn count_move(&mut self) {
+        self.total_moves += 1;
+    }
+
+    /// Count a move that failed verification
+    pub fn count_failed_move(&mut self) {
+        self.failed_moves += 1;
+    }
+
+    /// Counts over all runs: the loaded [`AppState::lifetime`] plus this run's
+    pub fn lifetime_stats(&self) -> Stats {
+        self.lifetime.plus(self.total_moves(), self.failed_moves())
+    }
+
+    /// Time since RMM started, as of `now`
+    pub fn uptime(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.started_at)
+    }
+
+    /// Mark monitoring as running and apply the runtime settings from `config`.
+    pub fn start(&mut self, config: &Config) {
+        rng::shared().with(|rng| self.start_with_rng(config, rng));
+    }
+
+    /// Like [`AppState::start`], drawing any randomness from `rng`.
+    pub fn start_with_rng<R: Rng + ?Sized>(&mut self, config: &Config, rng: &mut R) {
+        self.set_running(true, Cause::Startup);
+        self.move_budget = MoveBudget::new(config.max_moves_per_minute);
+        self.move_direction = config.initial_direction.resolve(rng);
+        self.keep_awake_mode = config.keep_awake_mode;
+        self.velocity = VelocityTracker::new(
+            config.high_velocity_threshold,
+            Duration::from_secs(config.high_velocity_cooldown_secs),
+        );
+    }
+
+    /// Change the running state, logging the transition and its cause.
+    /// Returns whether the state actually changed.
+    pub fn set_running(&mut self, running: bool, cause: Cause) -> bool {
+        if self.is_running == running {
+            return false;
+        }
+        info!(
+            target: "rmm::state::transition",
+            from = running_label(self.is_running),
+            to = running_label(running),
+            cause = cause.as_str(),
+            "State transition"
+        );
+        self.is_running = running;
+        if running {
+            // Resuming (e.g. once permissions are fixed) starts the failure count over
+            self.error_count = 0;
+            self.retry_after = None;
+        }
+        let kind = if running {
+            EventKind::Resume
+        } else {
+            EventKind::Pause
+        };
+        self.events.record(kind, cause.as_str());
+        true
+    }
+
+    /// Hold moves back until `until`, replacing any snooze already running;
+    /// `None` ends the snooze.
+    pub fn snooze(&mut self, until: Option<Instant>) {
+        match until {
+            Some(until) => info!(
+                "Snoozed for {} min",
+                until
+                    .saturating_duration_since(Instant::now())
+                    .as_secs()
+                    .div_ceil(60)
+            ),
+            None if self.snooze_until.is_some() => info!("Snooze cancelled"),
+            None => {}
+        }
+        self.snooze_until = until;
+    }
+
+    /// Whether a snooze is holding moves back at `now`. A snooze that has run
+    /// out is cleared, which resumes moving.
+    pub fn snoozed(&mut self, now: Instant) -> bool {
+        match self.snooze_until {
+            Some(until) if now < until => true,
+            Some(_) => {
+                info!("Snooze over, resuming");
+                self.snooze_until = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Time left on the snooze at `now`, if one is running
+    pub fn snooze_remaining(&self, now: Instant) -> Option<Duration> {
+        self.snooze_until
+            .filter(|until| now < *until)
+            .map(|until| until - now)
+    }
+
+    /// Record user input of the given kind at `now`.
+    pub fn record_input(&mut self, kind: InputKind, now: Instant) {
+        self.last_input.insert(kind, now);
+        self.last_activity = now;
+    }
+
+    /// Time since the most recent input of any of `kinds`, if there was one.
+    pub fn since_last(&self, kinds: &[InputKind], now: Instant) -> Option<Duration> {
+        kinds
+            .iter()
+            .filter_map(|kind| self.last_input.get(kind))
+            .max()
+            .map(|at| now.saturating_duration_since(*at))
+    }
+
+    /// Treat input until `now + window` as self-generated, e.g. right before a synthetic move.
+    pub fn ignore_own_input(&mut self, now: Instant, window: Duration) {
+        self.ignore_input_until = Some(now + window);
+    }
+
+    /// Whether input at `now` is likely an echo of our own synthetic action
+    pub fn is_own_input(&self, now: Instant) -> bool {
+        self.ignore_input_until.is_some_and(|until| now < until)
+    }
+
+    /// Record a button press; the button counts as held until released.
+    pub fn press_button(&mut self, button: Button, now: Instant) {
+        self.held_buttons.insert(button);
+        self.record_input(InputKind::Button, now);
+    }
+
+    pub fn release_button(&mut self, button: Button, now: Instant) {
+        self.held_buttons.remove(&button);
+        self.record_input(InputKind::Button, now);
+    }
+
+    /// Whether any mouse button is held, e.g. in the middle of a drag
+    pub fn button_held(&self) -> bool {
+        !self.held_buttons.is_empty()
+    }
+
+    /// Forget held buttons when there was no button event for `max_age`, so a
+    /// missed release can't block moves forever. Returns whether any were dropped.
+    pub fn prune_stale_buttons(&mut self, now: Instant, max_age: Duration) -> bool {
+        if self.held_buttons.is_empty()
+            || self
+                .since_last(&[InputKind::Button], now)
+                .is_some_and(|age| age < max_age)
+        {
+            return false;
+        }
+        self.held_buttons.clear();
+        true
+    }
+
+    /// How long the user has been idle, weighted by per-input timeouts.
+    ///
+    /// Input of a kind whose timeout is longer than `default_timeout` keeps the
+    /// user "active" for that much longer (and shorter timeouts the reverse), so
+    /// the result can be compared directly against `default_timeout`. The most
+    /// recent weighted input wins. Without any per-kind input recorded, this is
+    /// simply the time since `last_activity`.
+    pub fn idle_for(
+        &self,
+        now: Instant,
+        timeouts: &BTreeMap<InputKind, u64>,
+        default_timeout: u64,
+    ) -> Duration {
+        self.last_input
+            .iter()
+            .map(|(kind, at)| {
+                let elapsed = now.saturating_duration_since(*at);
+                let timeout = timeouts.get(kind).copied().unwrap_or(default_timeout);
+                if timeout >= default_timeout {
+                    elapsed.saturating_sub(Duration::from_secs(timeout - default_timeout))
+                } else {
+                    elapsed + Duration::from_secs(default_timeout - timeout)
+                }
+            })
+            .min()
+            .unwrap_or_else(|| now.saturating_duration_since(self.last_activity))
+    }
 }
 
 pub type SharedState = Arc<Mutex<AppState>>;
@@ -0,0 +1,91 @@
+// Single-line status display for running in a terminal.
+
+use crate::config::{Config, SharedConfig};
+use crate::metrics;
+use crate::state::{AppState, SharedState};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// How often the status line is redrawn
+const REFRESH: Duration = Duration::from_secs(1);
+
+/// Point-in-time view of what the status line shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusSnapshot {
+    pub running: bool,
+    pub idle: Duration,
+    pub threshold: Duration,
+    pub moves: u64,
+    pub moves_today: u64,
+    pub errors: u32,
+}
+
+impl StatusSnapshot {
+    pub fn capture(state: &AppState, config: &Config, now: Instant) -> Self {
+        Self {
+            running: state.is_running,
+            idle: state.idle_for(
+                now,
+                &config.effective_source_timeouts(),
+                config.inactivity_threshold,
+            ),
+            threshold: Duration::from_secs(config.inactivity_threshold),
+            moves: state.total_moves(),
+            moves_today: state.metrics.moves_on(metrics::local_today()),
+            errors: state.error_count,
+        }
+    }
+}
+
+fn format_secs(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// e.g. `RMM running | idle 1m 05s / 5m 00s | moves 12 (3 today) | errors 0`
+pub fn format_status_line(snapshot: &StatusSnapshot) -> String {
+    format!(
+        "RMM {} | idle {} / {} | moves {} ({} today) | errors {}",
+        if snapshot.running {
+            "running"
+        } else {
+            "paused"
+        },
+        format_secs(snapshot.idle),
+        format_secs(snapshot.threshold),
+        snapshot.moves,
+        snapshot.moves_today,
+        snapshot.errors
+    )
+}
+
+/// Redraw the status line on stdout in place, once a second, from a background thread.
+pub fn start_status_line(state: SharedState, config: SharedConfig) {
+    std::thread::spawn(move || {
+        let mut width = 0;
+        loop {
+            let line = {
+                let config = config.read().unwrap_or_else(|e| e.into_inner()).clone();
+                match state.lock() {
+                    Ok(state) => format_status_line(&StatusSnapshot::capture(
+                        &state,
+                        &config,
+                        Instant::now(),
+                    )),
+                    Err(_) => return,
+                }
+            };
+            // Pad over whatever is left of a longer previous line
+            let mut stdout = std::io::stdout().lock();
+            let _ = write!(stdout, "\r{:<width$}", line, width = width);
+            let _ = stdout.flush();
+            width = width.max(line.len());
+            drop(stdout);
+            std::thread::sleep(REFRESH);
+        }
+    });
+}
@@ -0,0 +1,53 @@
+// Panic logging and restart of worker threads.
+
+use std::backtrace::Backtrace;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread::{self, JoinHandle};
+use tracing::{error, info, warn};
+
+/// Restarts allowed per subsystem before giving up, so a panic on every
+/// iteration doesn't turn into a busy restart loop
+pub const MAX_RESTARTS: u32 = 5;
+
+/// Log every panic, with its thread name and a backtrace, to the tracing
+/// subscriber (and so to `rmm.log`) instead of only stderr.
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        let thread = thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        error!(
+            "Thread '{}' panicked: {}\n{}",
+            name,
+            info,
+            Backtrace::force_capture()
+        );
+    }));
+}
+
+/// Whether a subsystem that just panicked should be started again.
+pub fn should_respawn(restart_on_panic: bool, restarts_so_far: u32) -> bool {
+    restart_on_panic && restarts_so_far < MAX_RESTARTS
+}
+
+/// Run `body` on a named thread. If it panics and `restart_on_panic` is set,
+/// run it again (up to [`MAX_RESTARTS`] times); a normal return ends the thread.
+pub fn spawn_supervised<F>(name: &str, restart_on_panic: bool, body: F) -> JoinHandle<()>
+where
+    F: Fn() + Send + 'static,
+{
+    let name = name.to_string();
+    thread::Builder::new()
+        .name(name.clone())
+        .spawn(move || {
+            let mut restarts = 0;
+            while panic::catch_unwind(AssertUnwindSafe(&body)).is_err() {
+                if !should_respawn(restart_on_panic, restarts) {
+                    warn!("{} stopped after a panic", name);
+                    return;
+                }
+                restarts += 1;
+                info!("Restarting {} ({}/{})", name, restarts, MAX_RESTARTS);
+            }
+        })
+        .expect("failed to spawn thread")
+}
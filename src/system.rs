@@ -0,0 +1,26 @@
+/// One-minute system load average, if available (Unix `getloadavg`).
+#[cfg(unix)]
+pub fn load_average() -> Option<f64> {
+    let mut loads = [0f64; 3];
+    let n = unsafe { libc::getloadavg(loads.as_mut_ptr(), 1) };
+    if n >= 1 {
+        Some(loads[0])
+    } else {
+        None
+    }
+}
+
+/// Load average isn't exposed on Windows
+#[cfg(not(unix))]
+pub fn load_average() -> Option<f64> {
+    None
+}
+
+/// Whether to skip a heartbeat because the system is busy.
+/// No threshold configured, or an unknown load, never skips.
+pub fn should_skip_for_load(load: Option<f64>, max_load_average: Option<f64>) -> bool {
+    match (load, max_load_average) {
+        (Some(load), Some(max)) => load > max,
+        _ => false,
+    }
+}
@@ -1,34 +1,122 @@
 use native_dialog::{MessageDialog, MessageType};
-use std::process;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use rmm::command::{snooze_label, toggle_label};
+use rmm::command::{Command, CommandProcessor, INTERVAL_PRESETS, SNOOZE_MINUTES};
+use rmm::config::{KeepAwakeMode, SharedConfig, TrayConfig};
+use rmm::shutdown;
+use rmm::state::{Cause, SharedState, StateKind};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::info;
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use tracing::warn;
 use tray_item::{IconSource, TrayItem};
 
-pub fn create_tray() -> () {
-    // Platform-specific icon creation
-    #[cfg(any(target_os = "macos", target_os = "linux"))]
-    let icon = {
-        let png_data = include_bytes!("../resources/mouse.png").to_vec();
-        IconSource::Data {
-            data: png_data,
-            height: 16,
-            width: 16,
-        }
-    };
-
-    #[cfg(target_os = "windows")]
-    let icon = IconSource::Resource("mouse-icon");
+/// How often debounced commands are applied and menu labels refreshed
+const REFRESH: Duration = Duration::from_millis(250);
 
+pub fn create_tray(
+    tray_config: TrayConfig,
+    started_at: Instant,
+    config: SharedConfig,
+    state: SharedState,
+    commands: Arc<Mutex<CommandProcessor>>,
+) {
     // Create tray icon
-    let mut tray = TrayItem::new("RMM - Rust Mouse Monitor", icon).unwrap();
+    let mut tray = TrayItem::new(
+        "RMM - Rust Mouse Monitor",
+        state_icon(state_kind(&config, &state)),
+    )
+    .unwrap();
 
     // Add About menu item with native dialog
-    tray.add_menu_item("About", || {
+    tray.add_menu_item("About", move || {
+        let text = tray_config.about_text(env!("CARGO_PKG_VERSION"), started_at.elapsed());
         let _ = MessageDialog::new()
             .set_type(MessageType::Info)
-            .set_title("About RMM")
-            .set_text("RMM - Rust Mouse Monitor\n\nAuthor: Red\n\nCreated with LLM help for learning Rust concepts")
+            .set_title(&tray_config.about_title)
+            .set_text(&text)
             .show_alert();
-    }).unwrap();
+    })
+    .unwrap();
+
+    tray.add_label("---").unwrap();
+
+    // Keep-awake mode "submenu": tray-item has no nested menus on every platform
+    tray.add_label("Keep awake by:").unwrap();
+    for mode in KeepAwakeMode::ALL {
+        let commands = Arc::clone(&commands);
+        let label = format!("  {}", mode_label(mode));
+        tray.add_menu_item(&label, move || {
+            if let Ok(mut commands) = commands.lock() {
+                commands.submit(Command::SetMode(mode), Cause::Tray, Instant::now());
+            }
+        })
+        .unwrap();
+    }
+
+    tray.add_label("Interval:").unwrap();
+    for secs in INTERVAL_PRESETS {
+        let commands = Arc::clone(&commands);
+        let label = format!("  {} seconds", secs);
+        tray.add_menu_item(&label, move || {
+            if let Ok(mut commands) = commands.lock() {
+                commands.submit(Command::SetInterval(secs), Cause::Tray, Instant::now());
+            }
+        })
+        .unwrap();
+    }
+
+    tray.add_label("---").unwrap();
+
+    // Pause/Resume flips the running state without exiting
+    let toggle = {
+        let commands = Arc::clone(&commands);
+        move || {
+            if let Ok(mut commands) = commands.lock() {
+                commands.submit(Command::Toggle, Cause::Tray, Instant::now());
+            }
+        }
+    };
+    let cancel_snooze = {
+        let commands = Arc::clone(&commands);
+        move || {
+            if let Ok(mut commands) = commands.lock() {
+                commands.submit(Command::Snooze(Duration::ZERO), Cause::Tray, Instant::now());
+            }
+        }
+    };
+    // tray-item can't relabel menu items on macOS
+    #[cfg(target_os = "macos")]
+    {
+        tray.add_menu_item("Pause / Resume", toggle).unwrap();
+        tray.add_menu_item("Cancel snooze", cancel_snooze).unwrap();
+        drop((config, state));
+    }
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    let (toggle_id, snooze_id) = {
+        let (toggle_text, snooze_text) = labels(&state);
+        let inner = tray.inner_mut();
+        (
+            inner.add_menu_item_with_id(toggle_text, toggle).unwrap(),
+            inner
+                .add_menu_item_with_id(&snooze_text, cancel_snooze)
+                .unwrap(),
+        )
+    };
+
+    tray.add_label("Snooze for:").unwrap();
+    for minutes in SNOOZE_MINUTES {
+        let commands = Arc::clone(&commands);
+        let label = format!("  {} minutes", minutes);
+        tray.add_menu_item(&label, move || {
+            if let Ok(mut commands) = commands.lock() {
+                let duration = Duration::from_secs(minutes * 60);
+                commands.submit(Command::Snooze(duration), Cause::Tray, Instant::now());
+            }
+        })
+        .unwrap();
+    }
 
     tray.add_label("---").unwrap();
 
@@ -36,13 +124,17 @@ pub fn create_tray() -> () {
     tray.add_menu_item("Stop", || {
         info!("Stopping RMM application...");
         println!("RMM stopped by user");
-        process::exit(0);
+        shutdown::signal().request();
     })
     .unwrap();
 
     // Platform-specific quit handling
     #[cfg(target_os = "macos")]
     {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(REFRESH);
+            apply_pending(&commands);
+        });
         let inner = tray.inner_mut();
         inner.add_quit_item("Quit");
         inner.display();
@@ -53,8 +145,104 @@ pub fn create_tray() -> () {
         // On Linux (ksni) and Windows, add Quit as a regular menu item
         tray.add_menu_item("Quit", || {
             info!("Quitting RMM application...");
-            process::exit(0);
+            shutdown::signal().request();
         })
         .unwrap();
+
+        // The tray moves to this thread, which keeps it alive and its icon
+        // and the Pause/Resume and snooze labels in step with the state,
+        // whatever changed it
+        std::thread::spawn(move || {
+            let mut shown = labels(&state);
+            let mut shown_kind = state_kind(&config, &state);
+            loop {
+                std::thread::sleep(REFRESH);
+                apply_pending(&commands);
+                let (toggle_text, snooze_text) = labels(&state);
+                let inner = tray.inner_mut();
+                if toggle_text != shown.0 {
+                    let _ = inner.set_menu_item_label(toggle_text, toggle_id);
+                }
+                if snooze_text != shown.1 {
+                    let _ = inner.set_menu_item_label(&snooze_text, snooze_id);
+                }
+                shown = (toggle_text, snooze_text);
+
+                let kind = state_kind(&config, &state);
+                if kind != shown_kind {
+                    set_state_icon(&mut tray, kind);
+                    shown_kind = kind;
+                }
+            }
+        });
+    }
+}
+
+/// The tray icon for `kind`
+fn state_icon(kind: StateKind) -> IconSource {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        // The files named by StateKind::icon_file
+        let png_data: &[u8] = match kind {
+            StateKind::Active => include_bytes!("../resources/mouse.png"),
+            StateKind::Paused => include_bytes!("../resources/mouse-paused.png"),
+            StateKind::Error => include_bytes!("../resources/mouse-error.png"),
+        };
+        IconSource::Data {
+            data: png_data.to_vec(),
+            height: 16,
+            width: 16,
+        }
+    }
+
+    // Windows takes icons from the executable's resources, which only have one
+    #[cfg(target_os = "windows")]
+    {
+        let _ = kind;
+        IconSource::Resource("mouse-icon")
+    }
+}
+
+/// Swap the tray icon to the one for `kind`. On macOS the menu bar owns the
+/// main thread once shown, so the icon is only picked at startup there.
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+pub fn set_state_icon(tray: &mut TrayItem, kind: StateKind) {
+    if let Err(e) = tray.set_icon(state_icon(kind)) {
+        warn!("Failed to update the tray icon: {}", e);
+    }
+}
+
+fn state_kind(config: &SharedConfig, state: &SharedState) -> StateKind {
+    let max_errors = config.read().map(|c| c.max_errors).unwrap_or(u32::MAX);
+    match state.lock() {
+        Ok(state) => StateKind::of(&state, max_errors),
+        Err(_) => StateKind::Error,
+    }
+}
+
+/// Apply a debounced command once its window has passed
+fn apply_pending(commands: &Mutex<CommandProcessor>) {
+    if let Ok(mut commands) = commands.lock() {
+        commands.poll(Instant::now());
+    }
+}
+
+/// Current labels of the Pause/Resume and snooze status items
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn labels(state: &SharedState) -> (&'static str, String) {
+    match state.lock() {
+        Ok(state) => (
+            toggle_label(state.is_running),
+            snooze_label(state.snooze_remaining(Instant::now())),
+        ),
+        Err(_) => (toggle_label(false), snooze_label(None)),
+    }
+}
+
+fn mode_label(mode: KeepAwakeMode) -> &'static str {
+    match mode {
+        KeepAwakeMode::Mouse => "Moving the mouse",
+        KeepAwakeMode::Key => "Tapping F15",
+        KeepAwakeMode::Scroll => "Scrolling",
     }
 }
@@ -0,0 +1,88 @@
+// Config hot reload: poll the config file and apply it when it changes.
+//
+// Polling the modification time keeps this dependency-free and behaves the
+// same on every platform; a change is picked up within `WATCH_INTERVAL`.
+
+use crate::config::{Config, ConfigChange, SharedConfig};
+use crate::error::Result;
+use crate::events::EventKind;
+use crate::shutdown::ShutdownSignal;
+use crate::state::SharedState;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+/// How often the config file is checked for changes
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What identifies a version of the file: modification time and size
+type Fingerprint = (SystemTime, u64);
+
+fn fingerprint(path: &Path) -> Option<Fingerprint> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Detects edits to the config file since the last check
+pub struct ConfigWatcher {
+    path: PathBuf,
+    seen: Option<Fingerprint>,
+}
+
+impl ConfigWatcher {
+    /// Watch `path`, taking its current contents as already applied.
+    pub fn new(path: PathBuf) -> Self {
+        let seen = fingerprint(&path);
+        Self { path, seen }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reload into `shared` if the file changed since the last poll. `None`
+    /// when unchanged or missing (e.g. mid-save by an editor), so a missing
+    /// file never resets the live config to defaults.
+    pub fn poll(&mut self, shared: &SharedConfig) -> Option<Result<Vec<ConfigChange>>> {
+        let current = fingerprint(&self.path)?;
+        if self.seen == Some(current) {
+            return None;
+        }
+        self.seen = Some(current);
+        Some(Config::reload(shared, &self.path))
+    }
+}
+
+/// Check the config file every [`WATCH_INTERVAL`] in a background thread and
+/// apply edits; a file that fails to parse is reported and skipped. The
+/// thread ends once `shutdown` is requested.
+pub fn start(
+    shared: SharedConfig,
+    state: SharedState,
+    path: PathBuf,
+    shutdown: &'static ShutdownSignal,
+) -> JoinHandle<()> {
+    let mut watcher = ConfigWatcher::new(path);
+    std::thread::spawn(move || {
+        while !shutdown.sleep(WATCH_INTERVAL) {
+            match watcher.poll(&shared) {
+                None => {}
+                Some(Ok(changes)) => {
+                    info!("Config file changed, {} setting(s) updated", changes.len());
+                    if let Ok(mut state) = state.lock() {
+                        state
+                            .events
+                            .record(EventKind::Reload, format!("{} change(s)", changes.len()));
+                    }
+                }
+                Some(Err(e)) => warn!(
+                    "Ignoring invalid {}, keeping current config: {}",
+                    watcher.path().display(),
+                    e
+                ),
+            }
+        }
+    })
+}
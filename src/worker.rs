@@ -0,0 +1,116 @@
+// Periodic housekeeping on `worker_interval`, separate from the heartbeat.
+//
+// The heartbeat (`heartbeat_interval`) decides whether to move the mouse; the
+// worker keeps long-running state healthy: it polls activity sources, flushes the daily move counter
+// and lifetime stats, prunes stale input state and re-checks permissions after repeated failures.
+
+use crate::config::{Config, SharedConfig};
+use crate::metrics::{self, Metrics, Stats};
+use crate::permissions;
+use crate::sources::{self, SharedSources};
+use crate::state::SharedState;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// A held button with no button events for this long is assumed to be a
+/// missed release, and no longer blocks moves
+pub const STALE_BUTTON_HOLD: Duration = Duration::from_secs(600);
+
+fn read_config(config: &SharedConfig) -> Config {
+    config.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub struct Worker {
+    config: SharedConfig,
+    state: SharedState,
+    metrics_path: Option<PathBuf>,
+    saved_metrics: Option<Metrics>,
+    stats_path: Option<PathBuf>,
+    saved_stats: Option<Stats>,
+    sources: Option<SharedSources>,
+}
+
+impl Worker {
+    pub fn new(config: SharedConfig, state: SharedState) -> Self {
+        Self {
+            config,
+            state,
+            metrics_path: None,
+            saved_metrics: None,
+            stats_path: None,
+            saved_stats: None,
+            sources: None,
+        }
+    }
+
+    /// Persist the daily move counter to `path` whenever it changes.
+    pub fn with_metrics_path(mut self, path: PathBuf) -> Self {
+        self.metrics_path = Some(path);
+        self
+    }
+
+    /// Persist the lifetime move counts to `path` whenever they change.
+    pub fn with_stats_path(mut self, path: PathBuf) -> Self {
+        self.stats_path = Some(path);
+        self
+    }
+
+    /// Also poll these activity sources, between heartbeats.
+    pub fn with_sources(mut self, sources: SharedSources) -> Self {
+        self.sources = Some(sources);
+        self
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(read_config(&self.config).worker_interval)
+    }
+
+    pub fn tick(&mut self) {
+        let config = read_config(&self.config);
+        if let Some(sources) = &self.sources {
+            sources::poll_shared(sources, &self.state);
+        }
+        let (metrics, stats) = {
+            let Ok(mut state) = self.state.lock() else {
+                return;
+            };
+            if state.metrics.roll_over(metrics::local_today()) {
+                info!("New day, resetting the daily move counter");
+            }
+            if state.prune_stale_buttons(Instant::now(), STALE_BUTTON_HOLD) {
+                info!("No button events for a while, forgetting held buttons");
+            }
+            permissions::self_heal(&mut state, config.max_errors);
+            (state.metrics.clone(), state.lifetime_stats())
+        };
+        self.save_metrics(metrics);
+        self.save_stats(stats);
+    }
+
+    fn save_metrics(&mut self, metrics: Metrics) {
+        let Some(path) = &self.metrics_path else {
+            return;
+        };
+        if self.saved_metrics.as_ref() == Some(&metrics) {
+            return;
+        }
+        match metrics.save(path) {
+            Ok(()) => self.saved_metrics = Some(metrics),
+            Err(e) => warn!("Failed to save metrics to {}: {}", path.display(), e),
+        }
+    }
+
+    fn save_stats(&mut self, stats: Stats) {
+        let Some(path) = &self.stats_path else {
+            return;
+        };
+        if self.saved_stats.as_ref() == Some(&stats) {
+            return;
+        }
+        match stats.save(path) {
+            Ok(()) => self.saved_stats = Some(stats),
+            Err(e) => warn!("Failed to save stats to {}: {}", path.display(), e),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use rmm::activity::{device_event_between, on_device_event, DeviceEvent};
+use rmm::state::{AppState, InputKind};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_device_event_between() {
+    // Test that count changes map to added/removed and unknown counts are ignored
+    assert_eq!(
+        device_event_between(Some(3), Some(4)),
+        Some(DeviceEvent::Added)
+    );
+    assert_eq!(
+        device_event_between(Some(4), Some(3)),
+        Some(DeviceEvent::Removed)
+    );
+    assert_eq!(device_event_between(Some(3), Some(3)), None);
+    assert_eq!(device_event_between(None, Some(3)), None);
+    assert_eq!(device_event_between(Some(3), None), None);
+}
+
+#[test]
+fn test_device_event_marks_active() {
+    // Test that a device event resets idle time like any other input
+    let start = Instant::now();
+    let mut state = AppState::new();
+    state.record_input(InputKind::Mouse, start);
+
+    let plugged_at = start + Duration::from_secs(60);
+    on_device_event(&mut state, DeviceEvent::Added, plugged_at);
+
+    assert_eq!(state.last_activity, plugged_at);
+    assert_eq!(state.last_input.get(&InputKind::Device), Some(&plugged_at));
+    let idle = state.idle_for(plugged_at + Duration::from_secs(2), &BTreeMap::new(), 10);
+    assert_eq!(idle, Duration::from_secs(2));
+}
+
+#[test]
+fn test_velocity_computation() {
+    // Test pixels-per-second between samples, and no value for a zero interval
+    use rmm::activity::velocity;
+
+    let speed = velocity((0.0, 0.0), (300.0, 400.0), Duration::from_millis(500)).unwrap();
+    assert!((speed - 1000.0).abs() < 1e-9);
+    assert_eq!(velocity((0.0, 0.0), (10.0, 0.0), Duration::ZERO), None);
+}
+
+#[test]
+fn test_sustained_fast_movement_extends_cooldown() {
+    // Test that only sustained fast movement marks the user present, for the cooldown
+    use rmm::activity::VelocityTracker;
+
+    let start = Instant::now();
+    let mut tracker = VelocityTracker::new(Some(1000.0), Duration::from_secs(60));
+    let step = Duration::from_millis(10);
+
+    // 50 px per 10 ms = 5000 px/s; the first sample has nothing to compare with
+    let mut at = start;
+    for i in 0..3 {
+        tracker.sample((i as f64 * 50.0, 0.0), at);
+        at += step;
+        assert!(!tracker.is_present(at));
+    }
+    tracker.sample((150.0, 0.0), at);
+    assert!(tracker.is_present(at));
+    assert!(tracker.is_present(at + Duration::from_secs(59)));
+    assert!(!tracker.is_present(at + Duration::from_secs(60)));
+
+    // Slow movement doesn't count and resets the run of fast samples
+    let mut slow = VelocityTracker::new(Some(1000.0), Duration::from_secs(60));
+    let mut at = start;
+    for x in [0.0, 50.0, 100.0, 101.0, 151.0] {
+        slow.sample((x, 0.0), at);
+        at += step;
+    }
+    assert!(!slow.is_present(at));
+}
+
+#[test]
+fn test_velocity_tracking_disabled_by_default() {
+    // Test that without a threshold fast movement never marks presence
+    use rmm::activity::VelocityTracker;
+
+    let start = Instant::now();
+    let mut tracker = VelocityTracker::default();
+    for i in 0..10 {
+        let speed = tracker.sample((i as f64 * 500.0, 0.0), start + Duration::from_millis(i));
+        assert!(i == 0 || speed.is_some());
+    }
+    assert!(!tracker.is_present(start + Duration::from_millis(10)));
+}
+
+#[test]
+fn test_presence_gate() {
+    // Test that only a definite "present" suppresses moves, and only when enabled
+    use rmm::activity::should_pause_for_presence;
+
+    assert!(should_pause_for_presence(true, || Some(true)));
+    assert!(!should_pause_for_presence(true, || Some(false)));
+    assert!(!should_pause_for_presence(true, || None));
+    assert!(!should_pause_for_presence(false, || Some(true)));
+    assert!(!should_pause_for_presence(false, || panic!(
+        "presence queried while disabled"
+    )));
+}
+
+#[test]
+fn test_move_echo_correlation() {
+    // Test that only a listener event near the issued target confirms the move
+    use rmm::activity::{EchoStatus, MoveEcho};
+
+    let start = Instant::now();
+    let window = Duration::from_millis(200);
+    let mut echo = MoveEcho::default();
+    assert_eq!(echo.status(start, window), EchoStatus::Idle);
+    assert!(!echo.observe((100, 100)), "nothing tracked yet");
+
+    echo.expect((100, 100), 2, start);
+    assert_eq!(echo.status(start, window), EchoStatus::Pending);
+    assert!(
+        !echo.observe((95, 95)),
+        "intermediate step of a smooth move"
+    );
+    assert_eq!(echo.status(start, window), EchoStatus::Pending);
+    assert!(echo.observe((101, 99)));
+    assert_eq!(
+        echo.status(start + Duration::from_secs(5), window),
+        EchoStatus::Observed,
+        "an observed move stays confirmed after the window"
+    );
+
+    echo.clear();
+    assert_eq!(echo.status(start, window), EchoStatus::Idle);
+}
+
+#[test]
+fn test_move_echo_timeout() {
+    // Test that a move with no matching event times out after the window
+    use rmm::activity::{EchoStatus, MoveEcho};
+
+    let start = Instant::now();
+    let window = Duration::from_millis(200);
+    let mut echo = MoveEcho::default();
+    echo.expect((10, 10), 0, start);
+    assert!(!echo.observe((11, 10)), "strict tolerance");
+    assert_eq!(
+        echo.status(start + Duration::from_millis(200), window),
+        EchoStatus::Pending
+    );
+    assert_eq!(
+        echo.status(start + Duration::from_millis(201), window),
+        EchoStatus::TimedOut
+    );
+
+    // A new move restarts the window
+    echo.expect((20, 20), 0, start + Duration::from_secs(1));
+    assert_eq!(
+        echo.status(start + Duration::from_secs(1), window),
+        EchoStatus::Pending
+    );
+}
+
+#[test]
+fn test_own_mouse_move_does_not_count_as_activity() {
+    // Test that a MouseMove inside the self-move window leaves last_activity
+    // alone but still confirms the move, and that later moves count again
+    use rmm::activity::{on_mouse_move, EchoStatus, MoveEcho};
+    use rmm::sources::apply_signal;
+
+    let start = Instant::now();
+    let mut state = AppState::new();
+    state.last_activity = start;
+    state.move_echo = MoveEcho::default();
+
+    // Programmatic move: suppress, then the listener reports it
+    state.ignore_own_input(start, Duration::from_millis(300));
+    state.move_echo.expect((110, 110), 0, start);
+    let signal = on_mouse_move(
+        &mut state,
+        (110.0, 110.0),
+        start + Duration::from_millis(50),
+    );
+    assert_eq!(signal, None);
+    assert_eq!(state.last_activity, start);
+    assert!(state.last_input.is_empty());
+    assert_eq!(
+        state.move_echo.status(start, Duration::from_secs(1)),
+        EchoStatus::Observed
+    );
+
+    // The user moving afterwards is activity again
+    let later = start + Duration::from_millis(400);
+    let signal = on_mouse_move(&mut state, (300.0, 200.0), later).unwrap();
+    assert!(apply_signal(&mut state, signal));
+    assert_eq!(state.last_activity, later);
+    assert_eq!(state.last_input.get(&InputKind::Mouse), Some(&later));
+}
+
+#[test]
+fn test_listen_retry_delays_grow_to_the_cap() {
+    // Test that restart delays double per consecutive failure up to max_delay
+    use rmm::activity::LISTEN_RETRY;
+
+    let delays: Vec<u64> = (1..=8).map(|n| LISTEN_RETRY.delay(n).as_secs()).collect();
+    assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+    assert_eq!(LISTEN_RETRY.delay(u32::MAX), LISTEN_RETRY.max_delay);
+}
+
+#[test]
+fn test_listen_with_retry_recovers() {
+    // Test that a listener failing a few times is restarted until it runs,
+    // and that one that never runs is given up with an ActivityMonitor error
+    use rmm::activity::{listen_with_retry, ListenRetry};
+    use rmm::shutdown::ShutdownSignal;
+    use rmm::RmmError;
+
+    static STOP: ShutdownSignal = ShutdownSignal::new();
+    let retry = ListenRetry {
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(50),
+        max_failures: 5,
+    };
+
+    let mut calls = 0;
+    let result = listen_with_retry(retry, &STOP, || {
+        calls += 1;
+        if calls < 4 {
+            return Err("display changed");
+        }
+        // Runs until shutdown
+        STOP.request();
+        Ok(())
+    });
+    assert!(result.is_ok());
+    assert_eq!(calls, 4);
+
+    let idle = ShutdownSignal::new();
+    let mut calls = 0;
+    let result = listen_with_retry(retry, &idle, || {
+        calls += 1;
+        Err("no display")
+    });
+    assert!(matches!(result, Err(RmmError::ActivityMonitor(_))));
+    assert_eq!(calls, 5);
+}
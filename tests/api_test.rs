@@ -0,0 +1,169 @@
+use rmm::api::{self, ApiContext, ApiRequest};
+use rmm::config::Config;
+use rmm::mouse::MoveOutcome;
+use rmm::state::AppState;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+
+fn temp_config(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rmm-{}-{}.json", name, std::process::id()));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn context(config_path: PathBuf) -> ApiContext {
+    ApiContext {
+        state: Arc::new(Mutex::new(AppState::new())),
+        config: Arc::new(RwLock::new(Config::default())),
+        config_path: Some(config_path),
+        log_path: PathBuf::from("unused.log"),
+        mouse_available: || true,
+        move_now: |_, _| Ok(MoveOutcome::Skipped),
+    }
+}
+
+#[test]
+fn test_reload_applies_changed_config() {
+    // Test that POST /reload re-reads the file, applies it and reports the diff
+    let path = temp_config(
+        "reload",
+        r#"{"inactivity_threshold": 120, "heartbeat_interval": "30s"}"#,
+    );
+    let ctx = context(path.clone());
+
+    let response = api::handle(&ctx, &ApiRequest::new("POST", "/reload"));
+    assert_eq!(response.status, 200);
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    let changes = body["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0]["field"], "heartbeat_interval");
+    assert_eq!(changes[0]["old"], 10);
+    assert_eq!(changes[0]["new"], 30);
+    assert_eq!(changes[1]["field"], "inactivity_threshold");
+    assert_eq!(changes[1]["new"], 120);
+
+    let live = ctx.config.read().unwrap();
+    assert_eq!(live.inactivity_threshold, 120);
+    assert_eq!(live.heartbeat_interval, 30);
+    drop(live);
+
+    // Reloading an unchanged file reports no changes
+    let response = api::handle(&ctx, &ApiRequest::new("POST", "/reload"));
+    assert_eq!(response.body, r#"{"changes":[]}"#);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_reload_keeps_config_on_error() {
+    // Test that an invalid file is rejected and the live config is untouched
+    let path = temp_config("reload-bad", r#"{"heartbeat_interval": "soon"}"#);
+    let ctx = context(path.clone());
+
+    let response = api::handle(&ctx, &ApiRequest::new("POST", "/reload"));
+    assert_eq!(response.status, 400);
+    assert!(response.body.contains("invalid duration"));
+    assert_eq!(ctx.config.read().unwrap().heartbeat_interval, 10);
+
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/reload"));
+    assert_eq!(response.status, 405);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_reload_fails_when_the_file_is_gone() {
+    // Test that reloading a missing file is refused instead of applying defaults
+    let missing = std::env::temp_dir().join("rmm-reload-missing.json");
+    let ctx = context(missing.clone());
+    ctx.config.write().unwrap().inactivity_threshold = 90;
+
+    let response = api::handle(&ctx, &ApiRequest::new("POST", "/reload"));
+    assert_eq!(response.status, 409);
+    assert!(
+        response.body.contains("does not exist"),
+        "{}",
+        response.body
+    );
+    assert_eq!(ctx.config.read().unwrap().inactivity_threshold, 90);
+
+    let live = Arc::new(RwLock::new(Config::default()));
+    assert!(Config::reload(&live, &missing).is_err());
+}
+
+#[test]
+fn test_events_endpoint() {
+    // Test that GET /events returns the state's event log as JSON
+    use rmm::events::EventKind;
+
+    let ctx = context(PathBuf::from("unused.json"));
+    ctx.state
+        .lock()
+        .unwrap()
+        .events
+        .record(EventKind::Error, "boom");
+
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/events"));
+    assert_eq!(response.status, 200);
+    assert_eq!(response.content_type, "application/json");
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(body["events"][0]["kind"], "error");
+    assert_eq!(body["events"][0]["detail"], "boom");
+
+    let response = api::handle(&ctx, &ApiRequest::new("DELETE", "/events"));
+    assert_eq!(response.status, 405);
+}
+
+#[test]
+fn test_healthz_is_always_ok() {
+    // Test that liveness only needs the process to answer, even when degraded
+    let mut ctx = context(PathBuf::from("unused.json"));
+    ctx.mouse_available = || false;
+    ctx.state.lock().unwrap().error_count = 1000;
+
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/healthz"));
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body, "ok\n");
+    let response = api::handle(&ctx, &ApiRequest::new("POST", "/healthz"));
+    assert_eq!(response.status, 405);
+}
+
+#[test]
+fn test_readyz_healthy_and_degraded() {
+    // Test that readiness is 200 when healthy and 503 listing the failing
+    // checks when mouse control is unavailable or errors hit max_errors
+    let ctx = context(PathBuf::from("unused.json"));
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/readyz"));
+    assert_eq!(response.status, 200);
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(body["ready"], true);
+
+    let max_errors = ctx.config.read().unwrap().max_errors;
+    ctx.state.lock().unwrap().error_count = max_errors;
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/readyz"));
+    assert_eq!(response.status, 503);
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(body["ready"], false);
+    assert_eq!(body["failing"], serde_json::json!(["errors"]));
+
+    let mut ctx = context(PathBuf::from("unused.json"));
+    ctx.mouse_available = || false;
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/readyz"));
+    assert_eq!(response.status, 503);
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(body["failing"], serde_json::json!(["mouse_control"]));
+}
+
+#[test]
+fn test_readiness_failures() {
+    // Test each readiness check, including revoked permissions
+    use rmm::api::readiness_failures;
+
+    assert!(readiness_failures(true, Some(true), 0, 10).is_empty());
+    assert!(readiness_failures(true, None, 9, 10).is_empty());
+    assert_eq!(
+        readiness_failures(false, Some(false), 10, 10),
+        vec!["mouse_control", "permissions", "errors"]
+    );
+}
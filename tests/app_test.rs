@@ -0,0 +1,171 @@
+use rmm::app::Runner;
+use rmm::clock::{self, Clock};
+use rmm::config::Config;
+use rmm::error::Result;
+use rmm::mouse::MouseBackend;
+use rmm::shutdown::ShutdownSignal;
+use rmm::sources::SourceRegistry;
+use rmm::state::AppState;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+static MOVES: AtomicUsize = AtomicUsize::new(0);
+static STOP: ShutdownSignal = ShutdownSignal::new();
+
+/// Backend that follows every move and counts them in `MOVES`
+struct CountingBackend {
+    position: (i32, i32),
+}
+
+impl MouseBackend for CountingBackend {
+    fn get_position(&mut self) -> Result<(i32, i32)> {
+        Ok(self.position)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
+        MOVES.fetch_add(1, Ordering::SeqCst);
+        self.position = (x, y);
+        Ok(())
+    }
+}
+
+fn counting_backend() -> Result<CountingBackend> {
+    Ok(CountingBackend {
+        position: (100, 100),
+    })
+}
+
+static MOCK_MOVES: AtomicUsize = AtomicUsize::new(0);
+static MOCK_STOP: ShutdownSignal = ShutdownSignal::new();
+
+/// Backend that follows every move and counts them in `MOCK_MOVES`
+struct MockMouse {
+    position: (i32, i32),
+}
+
+impl MouseBackend for MockMouse {
+    fn get_position(&mut self) -> Result<(i32, i32)> {
+        Ok(self.position)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
+        MOCK_MOVES.fetch_add(1, Ordering::SeqCst);
+        self.position = (x, y);
+        Ok(())
+    }
+}
+
+fn mock_mouse() -> Result<MockMouse> {
+    Ok(MockMouse {
+        position: (100, 100),
+    })
+}
+
+/// The system clock plus however far the test has advanced it
+#[derive(Default)]
+struct MockClock {
+    offset: Mutex<Duration>,
+}
+
+impl MockClock {
+    fn advance(&self, by: Duration) {
+        *self.offset.lock().unwrap() += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        Instant::now() + *self.offset.lock().unwrap()
+    }
+}
+
+/// Poll `done` every 20ms for up to ten seconds
+fn wait_until(done: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !done() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    true
+}
+
+#[test]
+fn test_run_moves_idle_user_and_stops_on_shutdown() {
+    // Test that a headless run with a one-second heartbeat moves the mouse
+    // for an idle user, and returns once shutdown is requested
+    let config = Config {
+        heartbeat_interval: 1,
+        worker_interval: 1,
+        inactivity_threshold: 5,
+        verify_delay_ms: 0,
+        ..Default::default()
+    };
+    let state = Arc::new(Mutex::new(AppState::new()));
+    state.lock().unwrap().last_activity = Instant::now() - Duration::from_secs(60);
+
+    let run = {
+        let state = Arc::clone(&state);
+        std::thread::spawn(move || {
+            Runner::with_controller(config, state, counting_backend)
+                .with_sources(Arc::new(Mutex::new(SourceRegistry::new())))
+                .with_shutdown(&STOP)
+                .quiet(true)
+                .run()
+        })
+    };
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while MOVES.load(Ordering::SeqCst) == 0 && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert!(MOVES.load(Ordering::SeqCst) > 0);
+    assert!(state.lock().unwrap().is_running);
+
+    STOP.request();
+    run.join().unwrap().unwrap();
+    assert!(!state.lock().unwrap().is_running);
+}
+
+#[test]
+fn test_run_moves_once_clock_shows_idle() {
+    // Test the full loop against a mock mouse and clock: nothing moves while
+    // the user was just active, advancing the clock past the threshold makes
+    // the next heartbeat move, and shutdown stops the run cleanly
+    let mock_clock = Arc::new(MockClock::default());
+    clock::shared().install(Arc::clone(&mock_clock) as Arc<dyn Clock>);
+
+    let config = Config {
+        heartbeat_interval: 1,
+        worker_interval: 1,
+        inactivity_threshold: 5,
+        verify_delay_ms: 0,
+        ..Default::default()
+    };
+    let state = Arc::new(Mutex::new(AppState::new()));
+    state.lock().unwrap().last_activity = Instant::now();
+
+    let run = {
+        let state = Arc::clone(&state);
+        std::thread::spawn(move || {
+            Runner::with_controller(config, state, mock_mouse)
+                .with_sources(Arc::new(Mutex::new(SourceRegistry::new())))
+                .with_shutdown(&MOCK_STOP)
+                .quiet(true)
+                .run()
+        })
+    };
+
+    assert!(wait_until(|| state.lock().unwrap().heartbeats >= 2));
+    assert_eq!(MOCK_MOVES.load(Ordering::SeqCst), 0, "user still active");
+
+    mock_clock.advance(Duration::from_secs(60));
+    assert!(wait_until(|| MOCK_MOVES.load(Ordering::SeqCst) > 0));
+    assert!(state.lock().unwrap().is_running);
+
+    MOCK_STOP.request();
+    run.join().unwrap().unwrap();
+    assert!(!state.lock().unwrap().is_running);
+}
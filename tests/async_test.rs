@@ -0,0 +1,90 @@
+use rmm::config::{Config, HttpApiConfig};
+use rmm::heartbeat::run_async_with_sources;
+use rmm::sources::SourceRegistry;
+use rmm::state::AppState;
+use std::net::{Ipv4Addr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test(start_paused = true)]
+async fn test_run_async_ticks_and_stops_on_shutdown() {
+    // Test that run_async drives heartbeats on the interval and returns once
+    // the shutdown future resolves
+    let config = Config {
+        heartbeat_interval: 10,
+        // Never idle long enough to move during the test
+        inactivity_threshold: 3600,
+        ..Default::default()
+    };
+    let state = Arc::new(Mutex::new(AppState::new()));
+
+    // Shut down once three heartbeats have been observed
+    let observed = Arc::clone(&state);
+    let shutdown = async move {
+        while observed.lock().unwrap().heartbeats < 3 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    };
+    // No sources, so the test doesn't hook global input
+    let sources = Arc::new(Mutex::new(SourceRegistry::new()));
+    run_async_with_sources(config, Arc::clone(&state), sources, shutdown)
+        .await
+        .expect("run_async failed");
+
+    let state = state.lock().unwrap();
+    assert!(state.heartbeats >= 3);
+    assert!(!state.is_running);
+}
+
+#[tokio::test]
+async fn test_run_async_serves_the_http_api() {
+    // Test that run_async serves the HTTP API on the runtime while it runs
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let config = Config {
+        heartbeat_interval: 10,
+        inactivity_threshold: 3600,
+        http_api: HttpApiConfig {
+            enabled: true,
+            port,
+            token: Some("test-token".into()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let state = Arc::new(Mutex::new(AppState::new()));
+
+    // Shut down once /healthz has answered
+    let response = Arc::new(Mutex::new(String::new()));
+    let received = Arc::clone(&response);
+    let shutdown = async move {
+        let mut stream = loop {
+            match tokio::net::TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        };
+        stream
+            .write_all(
+                b"GET /healthz HTTP/1.1\r\nHost: localhost\r\n\
+                  Authorization: Bearer test-token\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut text = String::new();
+        stream.read_to_string(&mut text).await.unwrap();
+        *received.lock().unwrap() = text;
+    };
+    let sources = Arc::new(Mutex::new(SourceRegistry::new()));
+    run_async_with_sources(config, state, sources, shutdown)
+        .await
+        .expect("run_async failed");
+
+    let response = response.lock().unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.ends_with("ok\n"));
+}
@@ -0,0 +1,58 @@
+use chrono::{Local, TimeZone};
+use rmm::audit::{append, format_record, Decision};
+use rmm::config::KeepAwakeMode;
+
+fn decision() -> Decision {
+    Decision {
+        idle_secs: 312,
+        mode: KeepAwakeMode::Mouse,
+        dx: 10,
+        dy: -10,
+    }
+}
+
+#[test]
+fn test_record_format() {
+    // Test that an audit record is a single JSON line with timestamp, event and decision fields
+    let at = Local.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap();
+    let line = format_record(&decision(), at);
+    assert!(!line.contains('\n'));
+
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert!(value["ts"]
+        .as_str()
+        .unwrap()
+        .starts_with("2024-03-01T09:30:00"));
+    assert_eq!(value["event"], "would_keep_awake");
+    assert_eq!(value["idle_secs"], 312);
+    assert_eq!(value["mode"], "mouse");
+    assert_eq!(value["dx"], 10);
+    assert_eq!(value["dy"], -10);
+    assert_eq!(value.as_object().unwrap().len(), 6);
+}
+
+#[test]
+fn test_append_adds_lines() {
+    // Test that records are appended one per line
+    let dir = std::env::temp_dir().join(format!("rmm-audit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("audit.log");
+
+    let at = Local.with_ymd_and_hms(2024, 3, 1, 9, 30, 0).unwrap();
+    append(&path, &decision(), at).unwrap();
+    let scroll = Decision {
+        mode: KeepAwakeMode::Scroll,
+        dx: 0,
+        dy: 0,
+        ..decision()
+    };
+    append(&path, &scroll, at).unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains("\"mode\":\"scroll\""));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,73 @@
+use rmm::autostart::{is_permitted, should_apply};
+
+#[test]
+fn test_permission_sources() {
+    // Test that either the env var or the sentinel file permits enabling
+    assert!(!is_permitted(None, false));
+    assert!(is_permitted(None, true));
+    assert!(is_permitted(Some("1"), false));
+    assert!(is_permitted(Some(" TRUE "), false));
+    assert!(!is_permitted(Some("0"), false));
+    assert!(!is_permitted(Some("yes please"), false));
+    assert!(!is_permitted(Some(""), false));
+}
+
+#[test]
+fn test_guarded_apply() {
+    // Test that enabling needs permission while disabling is always applied
+    assert!(!should_apply(true, false));
+    assert!(should_apply(true, true));
+    assert!(should_apply(false, false));
+    assert!(should_apply(false, true));
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("rmm-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_sync_installs_updates_and_removes() {
+    // Test that syncing writes the login item once, rewrites it for a new
+    // executable path and removes it when disabled
+    use rmm::autostart::{sync_in, LOGIN_ITEM_FILE};
+    use std::path::Path;
+
+    let dir = temp_dir("autostart");
+    let path = dir.join(LOGIN_ITEM_FILE);
+    let exe = Path::new("/opt/rmm/bin/rmm");
+
+    assert!(!sync_in(&dir, false, exe).unwrap(), "nothing to remove");
+    assert!(sync_in(&dir, true, exe).unwrap());
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("/opt/rmm/bin/rmm"), "{}", content);
+    assert!(!sync_in(&dir, true, exe).unwrap(), "already installed");
+
+    assert!(sync_in(&dir, true, Path::new("/usr/local/bin/rmm")).unwrap());
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .contains("/usr/local/bin/rmm"));
+
+    assert!(sync_in(&dir, false, exe).unwrap());
+    assert!(!path.exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_login_item_contents() {
+    // Test the desktop entry and LaunchAgent plist for a given executable
+    use rmm::autostart::{desktop_entry, launch_agent};
+    use std::path::Path;
+
+    let exe = Path::new("/Applications/RMM.app/Contents/MacOS/rmm");
+    let entry = desktop_entry(exe);
+    assert!(entry.starts_with("[Desktop Entry]\n"));
+    assert!(entry.contains("Exec=\"/Applications/RMM.app/Contents/MacOS/rmm\"\n"));
+
+    let plist = launch_agent(exe);
+    assert!(plist.contains("<string>com.rmm.rmm</string>"));
+    assert!(plist.contains("<string>/Applications/RMM.app/Contents/MacOS/rmm</string>"));
+    assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+}
@@ -0,0 +1,673 @@
+use rmm::config::Config;
+use rmm::error::{Result, RmmError};
+use rmm::mouse::{check_and_move, nudge, LazyController, MouseBackend, MoveOutcome};
+use rmm::state::{AppState, SharedState};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// check_and_move allows one move at a time process-wide, so tests take turns
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Scripted backend: tracks a cursor position and records every move
+#[derive(Default)]
+struct MockBackend {
+    position: (i32, i32),
+    moves: Vec<(i32, i32)>,
+    /// Accept moves without the cursor actually going anywhere
+    stuck: bool,
+    fail_reads: bool,
+    screen: Option<(i32, i32)>,
+    key_taps: u32,
+    scrolls: Vec<(i32, i32)>,
+}
+
+impl MouseBackend for MockBackend {
+    fn get_position(&mut self) -> Result<(i32, i32)> {
+        if self.fail_reads {
+            return Err(RmmError::MouseControl("scripted read failure".into()));
+        }
+        Ok(self.position)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
+        self.moves.push((x, y));
+        if !self.stuck {
+            self.position = (x, y);
+        }
+        Ok(())
+    }
+
+    fn get_screen_size(&mut self) -> Option<(i32, i32)> {
+        self.screen
+    }
+
+    fn tap_key(&mut self) -> Result<()> {
+        self.key_taps += 1;
+        Ok(())
+    }
+
+    fn scroll(&mut self, dx: i32, dy: i32) -> Result<()> {
+        self.scrolls.push((dx, dy));
+        Ok(())
+    }
+}
+
+fn mock_at_100() -> Result<MockBackend> {
+    Ok(MockBackend {
+        position: (100, 100),
+        ..Default::default()
+    })
+}
+
+fn stuck_mock() -> Result<MockBackend> {
+    Ok(MockBackend {
+        position: (100, 100),
+        stuck: true,
+        ..Default::default()
+    })
+}
+
+/// A 100x100 screen with the cursor close to the bottom-right corner
+fn near_edge_mock() -> Result<MockBackend> {
+    Ok(MockBackend {
+        position: (95, 95),
+        screen: Some((100, 100)),
+        ..Default::default()
+    })
+}
+
+fn stuck_near_edge_mock() -> Result<MockBackend> {
+    Ok(MockBackend {
+        stuck: true,
+        ..near_edge_mock()?
+    })
+}
+
+fn test_config() -> Config {
+    Config {
+        inactivity_threshold: 5,
+        verify_delay_ms: 0,
+        ..Default::default()
+    }
+}
+
+fn idle_state(config: &Config) -> SharedState {
+    let mut state = AppState::new();
+    state.start(config);
+    state.last_activity = Instant::now() - Duration::from_secs(60);
+    Arc::new(Mutex::new(state))
+}
+
+#[test]
+fn test_move_cycle_moves_verifies_and_flips() {
+    // Test a successful move: the mock is moved by movement_delta, the move is
+    // verified and the direction flips for the next one
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        movement_delta: 25,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    assert_eq!(controller.get().unwrap().moves, vec![(125, 125)]);
+    {
+        let state = state.lock().unwrap();
+        assert_eq!(state.move_direction, -1);
+        assert_eq!(state.total_moves(), 1);
+        assert_eq!(state.error_count, 0);
+    }
+
+    // Still idle (our own move isn't activity), so the next check moves back
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    assert_eq!(controller.get().unwrap().position, (100, 100));
+}
+
+#[test]
+fn test_verification_failure_counts_errors() {
+    // Test that a move the cursor doesn't follow fails verification, counts an
+    // error and keeps the direction
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        max_errors: 2,
+        failure_backoff_ms: 0,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(stuck_mock);
+
+    for expected_errors in 1..=2 {
+        let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+        assert_eq!(outcome, MoveOutcome::Failed);
+        let state = state.lock().unwrap();
+        assert_eq!(state.error_count, expected_errors);
+        assert_eq!(state.move_direction, 1);
+        assert_eq!(state.total_moves(), 0);
+    }
+}
+
+#[test]
+fn test_failures_back_off_until_success() {
+    // Test that each failed move holds retries back for a growing window,
+    // and that a verified move clears it
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        failure_backoff_ms: 10,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(stuck_mock);
+
+    let mut windows = Vec::new();
+    for _ in 0..3 {
+        let before = Instant::now();
+        let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+        assert_eq!(outcome, MoveOutcome::Failed);
+        let retry_after = state.lock().unwrap().retry_after.unwrap();
+        windows.push(retry_after - before);
+
+        let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+        assert_eq!(outcome, MoveOutcome::Skipped);
+        std::thread::sleep(retry_after - Instant::now().min(retry_after));
+    }
+    assert!(windows[0] >= Duration::from_millis(20), "{:?}", windows);
+    assert!(windows[1] >= Duration::from_millis(40), "{:?}", windows);
+    assert!(windows[2] >= Duration::from_millis(80), "{:?}", windows);
+    assert!(windows[0] < windows[1] && windows[1] < windows[2]);
+
+    controller.get().unwrap().stuck = false;
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    let state = state.lock().unwrap();
+    assert_eq!(state.retry_after, None);
+    assert_eq!(state.error_count, 0);
+}
+
+#[test]
+fn test_max_errors_pauses_until_resumed() {
+    // Test that reaching max_errors pauses moves until resumed, and that with
+    // disable_on_max_errors off the moves keep being tried
+    use rmm::state::Cause;
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        max_errors: 2,
+        failure_backoff_ms: 0,
+        notifications_enabled: false,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(stuck_mock);
+
+    for _ in 0..2 {
+        let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+        assert_eq!(outcome, MoveOutcome::Failed);
+    }
+    assert!(!state.lock().unwrap().is_running);
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Skipped);
+    assert_eq!(controller.get().unwrap().moves.len(), 2);
+
+    state.lock().unwrap().set_running(true, Cause::Tray);
+    assert_eq!(state.lock().unwrap().error_count, 0);
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Failed);
+
+    let keep_going = Config {
+        disable_on_max_errors: false,
+        ..config
+    };
+    let state = idle_state(&keep_going);
+    for _ in 0..4 {
+        let outcome = check_and_move(Arc::clone(&state), &keep_going, &mut controller).unwrap();
+        assert_eq!(outcome, MoveOutcome::Failed);
+    }
+    let state = state.lock().unwrap();
+    assert!(state.is_running);
+    assert_eq!(state.error_count, 4);
+}
+
+#[test]
+fn test_move_counters_across_cycles() {
+    // Test that total and failed move counts add up over alternating
+    // failures and successes, unlike error_count which resets on success
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        failure_backoff_ms: 0,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(stuck_mock);
+
+    for cycle in 1..=3 {
+        controller.get().unwrap().stuck = true;
+        for _ in 0..2 {
+            let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+            assert_eq!(outcome, MoveOutcome::Failed);
+        }
+        controller.get().unwrap().stuck = false;
+        let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+        assert_eq!(outcome, MoveOutcome::Moved);
+
+        let state = state.lock().unwrap();
+        assert_eq!(state.total_moves(), cycle);
+        assert_eq!(state.failed_moves(), 2 * cycle);
+        assert_eq!(state.error_count, 0);
+    }
+}
+
+#[test]
+fn test_backend_errors_propagate() {
+    // Test that a failing backend read surfaces as an error, not a move
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    fn broken() -> Result<MockBackend> {
+        Ok(MockBackend {
+            fail_reads: true,
+            ..Default::default()
+        })
+    }
+    let config = test_config();
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(broken);
+    assert!(check_and_move(Arc::clone(&state), &config, &mut controller).is_err());
+    assert!(controller.get().unwrap().moves.is_empty());
+}
+
+#[test]
+fn test_flip_on_failure() {
+    // Test that a failed move flips the direction with flip_on_failure set
+    // and keeps it without
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    for (flip_on_failure, expected) in [(true, -1), (false, 1)] {
+        let config = Config {
+            flip_on_failure,
+            ..test_config()
+        };
+        let state = idle_state(&config);
+        let mut controller = LazyController::with_factory(stuck_mock);
+
+        let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+        assert_eq!(outcome, MoveOutcome::Failed);
+        assert_eq!(
+            state.lock().unwrap().move_direction,
+            expected,
+            "flip_on_failure: {}",
+            flip_on_failure
+        );
+    }
+}
+
+#[test]
+fn test_move_clamped_to_screen_edge() {
+    // Test that a move past the edge of a tiny screen stops at the last pixel,
+    // still verifies, and the next move heads back inward
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = test_config();
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(near_edge_mock);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    assert_eq!(controller.get().unwrap().position, (99, 99));
+    assert_eq!(state.lock().unwrap().move_direction, -1);
+
+    check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(controller.get().unwrap().position, (89, 89));
+}
+
+#[test]
+fn test_move_from_screen_corner_is_skipped() {
+    // Test that with the cursor already on the last pixel the move is skipped
+    // without moving or verifying, and the next move heads back inward
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = test_config();
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(|| {
+        Ok(MockBackend {
+            position: (99, 99),
+            ..near_edge_mock()?
+        })
+    });
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Skipped);
+    assert!(controller.get().unwrap().moves.is_empty());
+    assert_eq!(state.lock().unwrap().move_direction, -1);
+    assert_eq!(state.lock().unwrap().error_count, 0);
+
+    check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(controller.get().unwrap().position, (89, 89));
+}
+
+#[test]
+fn test_clamped_failure_still_flips_direction() {
+    // Test that when a clamped move fails verification the direction is flipped
+    // anyway, so the retry doesn't push against the edge again
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        strict_verify: true,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(stuck_near_edge_mock);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Failed);
+    assert_eq!(controller.get().unwrap().moves, vec![(99, 99)]);
+    let state = state.lock().unwrap();
+    assert_eq!(state.move_direction, -1);
+    assert_eq!(state.error_count, 1);
+}
+
+#[test]
+fn test_circle_moves_stay_on_radius() {
+    // Test that with movement_pattern circle each tick lands movement_delta
+    // away from where the cursor started
+    use rmm::movement::MovementStrategy;
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        movement_pattern: MovementStrategy::Circle,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    for _ in 0..6 {
+        let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+        assert_eq!(outcome, MoveOutcome::Moved);
+        let (x, y) = controller.get().unwrap().position;
+        let distance = f64::from(x - 100).hypot(f64::from(y - 100));
+        assert!(
+            (distance - 10.0).abs() <= 1.0,
+            "({}, {}) is off the circle",
+            x,
+            y
+        );
+    }
+    let moves = &controller.get().unwrap().moves;
+    assert_eq!(moves[0], (110, 100));
+    assert_eq!(moves[2], (100, 110));
+}
+
+#[test]
+fn test_key_mode_taps_key_without_moving() {
+    // Test that the key simulation mode goes through the keyboard method and
+    // never touches move_mouse
+    use rmm::config::KeepAwakeMode;
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        keep_awake_mode: KeepAwakeMode::Key,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    let mock = controller.get().unwrap();
+    assert_eq!(mock.key_taps, 1);
+    assert!(mock.moves.is_empty());
+    assert_eq!(mock.position, (100, 100));
+    assert_eq!(state.lock().unwrap().total_moves(), 1);
+}
+
+#[test]
+fn test_scroll_mode_scrolls_up_and_back_down() {
+    // Test that the scroll simulation mode issues a balanced up/down pair and
+    // never calls move_mouse
+    use rmm::config::KeepAwakeMode;
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        keep_awake_mode: KeepAwakeMode::Scroll,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    let mock = controller.get().unwrap();
+    assert_eq!(mock.scrolls, vec![(0, -1), (0, 1)]);
+    let net = mock
+        .scrolls
+        .iter()
+        .fold((0, 0), |acc, s| (acc.0 + s.0, acc.1 + s.1));
+    assert_eq!(net, (0, 0));
+    assert!(mock.moves.is_empty());
+    assert_eq!(mock.key_taps, 0);
+}
+
+#[test]
+fn test_oversized_move_is_clamped() {
+    // Test that a computed move larger than max_move_distance reaches the
+    // backend clamped, and still verifies
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        movement_delta: 150,
+        max_move_distance: 20,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    assert_eq!(controller.get().unwrap().moves, vec![(120, 120)]);
+}
+
+#[test]
+fn test_snooze_skips_moves_until_it_runs_out() {
+    // Test that a snooze 1s ahead holds moves back, and that moving resumes
+    // on its own once it has elapsed
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = test_config();
+    let state = idle_state(&config);
+    state
+        .lock()
+        .unwrap()
+        .snooze(Some(Instant::now() + Duration::from_secs(1)));
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Skipped);
+    assert!(controller.get().unwrap().moves.is_empty());
+    assert!(state
+        .lock()
+        .unwrap()
+        .snooze_remaining(Instant::now())
+        .is_some());
+
+    std::thread::sleep(Duration::from_millis(1100));
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    assert_eq!(state.lock().unwrap().snooze_until, None);
+}
+
+/// Desktop stub with fixed answers
+#[derive(Default)]
+struct StubDesktop {
+    locked: Option<bool>,
+    fullscreen: Option<bool>,
+    process: Option<&'static str>,
+}
+
+impl rmm::desktop::Desktop for StubDesktop {
+    fn session_locked(&self) -> Option<bool> {
+        self.locked
+    }
+
+    fn fullscreen_app_active(&self) -> Option<bool> {
+        self.fullscreen
+    }
+
+    fn focused_process_name(&self) -> Option<String> {
+        self.process.map(str::to_string)
+    }
+}
+
+#[test]
+fn test_locked_session_skips_moves() {
+    // Test that no move happens while the stubbed session is locked, unless
+    // move_when_locked is set
+    use rmm::desktop::{self, SystemDesktop};
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    desktop::shared().install(Arc::new(StubDesktop {
+        locked: Some(true),
+        ..Default::default()
+    }));
+    let config = test_config();
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller);
+    let moves = controller.get().unwrap().moves.len();
+
+    let unlocked = Config {
+        move_when_locked: true,
+        ..test_config()
+    };
+    let forced = check_and_move(Arc::clone(&state), &unlocked, &mut controller);
+    desktop::shared().install(Arc::new(SystemDesktop));
+
+    assert_eq!(outcome.unwrap(), MoveOutcome::Skipped);
+    assert_eq!(moves, 0);
+    assert_eq!(forced.unwrap(), MoveOutcome::Moved);
+}
+
+#[test]
+fn test_fullscreen_app_skips_moves() {
+    // Test that no move happens while the stub reports a fullscreen app and
+    // pause_on_fullscreen is set, and that it's ignored otherwise
+    use rmm::desktop::{self, SystemDesktop};
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    desktop::shared().install(Arc::new(StubDesktop {
+        fullscreen: Some(true),
+        ..Default::default()
+    }));
+    let config = Config {
+        pause_on_fullscreen: true,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller);
+    let moves = controller.get().unwrap().moves.len();
+    let ignored = check_and_move(Arc::clone(&state), &test_config(), &mut controller);
+    desktop::shared().install(Arc::new(SystemDesktop));
+
+    assert_eq!(outcome.unwrap(), MoveOutcome::Skipped);
+    assert_eq!(moves, 0);
+    assert_eq!(ignored.unwrap(), MoveOutcome::Moved);
+}
+
+#[test]
+fn test_blocklisted_process_skips_moves() {
+    // Test that a focused process on the blocklist holds moves back
+    // (ignoring case), while other processes and an empty blocklist don't
+    use rmm::desktop::{self, SystemDesktop};
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    desktop::shared().install(Arc::new(StubDesktop {
+        process: Some("Zoom.exe"),
+        ..Default::default()
+    }));
+    let blocking = |entries: &[&str]| Config {
+        blocklist_processes: entries.iter().map(|e| e.to_string()).collect(),
+        ..test_config()
+    };
+    let state = idle_state(&test_config());
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let matched = check_and_move(Arc::clone(&state), &blocking(&["ZOOM"]), &mut controller);
+    let moves = controller.get().unwrap().moves.len();
+    let unmatched = check_and_move(Arc::clone(&state), &blocking(&["steam"]), &mut controller);
+    let empty = check_and_move(Arc::clone(&state), &blocking(&[]), &mut controller);
+    desktop::shared().install(Arc::new(SystemDesktop));
+
+    assert_eq!(matched.unwrap(), MoveOutcome::Skipped);
+    assert_eq!(moves, 0);
+    assert_eq!(unmatched.unwrap(), MoveOutcome::Moved);
+    assert_eq!(empty.unwrap(), MoveOutcome::Moved);
+}
+
+#[test]
+fn test_move_pinned_in_region_corner_is_skipped() {
+    // Test that a move the region clamps back onto the cursor isn't counted
+    // as verified, and that the next one heads back inward
+    use rmm::config::Region;
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        movement_delta: 10,
+        move_region: Some(Region {
+            x: 0,
+            y: 0,
+            width: 101,
+            height: 101,
+        }),
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Skipped);
+    assert!(controller.get().unwrap().moves.is_empty());
+    {
+        let state = state.lock().unwrap();
+        assert_eq!(state.total_moves(), 0);
+        assert_eq!(state.move_direction, -1);
+    }
+
+    let outcome = check_and_move(Arc::clone(&state), &config, &mut controller).unwrap();
+    assert_eq!(outcome, MoveOutcome::Moved);
+    assert_eq!(controller.get().unwrap().position, (90, 90));
+}
+
+#[test]
+fn test_nudge_goes_through_the_gates() {
+    // Test that burst nudges draw from the move budget, stop while snoozed
+    // and never happen outside Mouse mode
+    use rmm::config::KeepAwakeMode;
+
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let config = Config {
+        max_moves_per_minute: 2,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+    assert!(nudge(&state, &config, &mut controller, 1).unwrap());
+    assert!(nudge(&state, &config, &mut controller, -1).unwrap());
+    assert!(!nudge(&state, &config, &mut controller, 1).unwrap());
+    assert_eq!(controller.get().unwrap().moves.len(), 2);
+
+    let state = idle_state(&test_config());
+    state
+        .lock()
+        .unwrap()
+        .snooze(Some(Instant::now() + Duration::from_secs(60)));
+    let mut controller = LazyController::with_factory(mock_at_100);
+    assert!(!nudge(&state, &test_config(), &mut controller, 1).unwrap());
+    assert!(controller.get().unwrap().moves.is_empty());
+
+    let config = Config {
+        keep_awake_mode: KeepAwakeMode::Key,
+        ..test_config()
+    };
+    let state = idle_state(&config);
+    let mut controller = LazyController::with_factory(mock_at_100);
+    assert!(!nudge(&state, &config, &mut controller, 1).unwrap());
+    let backend = controller.get().unwrap();
+    assert!(backend.moves.is_empty());
+    assert_eq!(backend.key_taps, 0);
+}
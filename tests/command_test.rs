@@ -0,0 +1,310 @@
+use rmm::command::{Command, CommandProcessor};
+use rmm::state::{AppState, Cause};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_toggle_burst_is_coalesced() {
+    // Test that a rapid burst of toggles collapses into a single transition
+    let state = Arc::new(Mutex::new(AppState::new()));
+    state.lock().unwrap().is_running = true;
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::from_millis(300));
+
+    let start = Instant::now();
+    for i in 0..5 {
+        processor.submit(
+            Command::Toggle,
+            Cause::Tray,
+            start + Duration::from_millis(i * 50),
+        );
+        assert_eq!(
+            processor.poll(start + Duration::from_millis(i * 50 + 10)),
+            None
+        );
+    }
+
+    // Five toggles from running means paused; nothing applied until the window settles
+    assert!(state.lock().unwrap().is_running);
+    assert_eq!(
+        processor.poll(start + Duration::from_millis(200 + 300)),
+        Some(false)
+    );
+    assert!(!state.lock().unwrap().is_running);
+    assert_eq!(processor.transitions(), 1);
+}
+
+#[test]
+fn test_burst_ending_in_original_state_applies_nothing() {
+    // Test that pause followed quickly by resume leaves the state untouched
+    let state = Arc::new(Mutex::new(AppState::new()));
+    state.lock().unwrap().is_running = true;
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::from_millis(300));
+
+    let start = Instant::now();
+    processor.submit(Command::Pause, Cause::Tray, start);
+    processor.submit(
+        Command::Resume,
+        Cause::Tray,
+        start + Duration::from_millis(100),
+    );
+
+    assert_eq!(processor.poll(start + Duration::from_secs(1)), None);
+    assert!(state.lock().unwrap().is_running);
+    assert_eq!(processor.transitions(), 0);
+}
+
+#[test]
+fn test_toggle_kept_while_state_unavailable() {
+    // Test that a settled toggle stays queued while the state lock fails and
+    // is applied by the next poll once it works again
+    let state = Arc::new(Mutex::new(AppState::new()));
+    state.lock().unwrap().is_running = true;
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::from_millis(300));
+    let start = Instant::now();
+    processor.submit(Command::Pause, Cause::Tray, start);
+
+    let poisoner = Arc::clone(&state);
+    let _ = std::thread::spawn(move || {
+        let _guard = poisoner.lock().unwrap();
+        panic!("poison the state");
+    })
+    .join();
+    assert_eq!(processor.poll(start + Duration::from_secs(1)), None);
+
+    state.clear_poison();
+    assert_eq!(processor.poll(start + Duration::from_secs(2)), Some(false));
+    assert!(!state.lock().unwrap().is_running);
+}
+
+#[test]
+fn test_zero_debounce_applies_immediately() {
+    // Test that disabling the debounce applies every command right away
+    let state = Arc::new(Mutex::new(AppState::new()));
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::ZERO);
+
+    let now = Instant::now();
+    processor.submit(Command::Resume, Cause::Tray, now);
+    assert!(state.lock().unwrap().is_running);
+    processor.submit(Command::Toggle, Cause::Tray, now);
+    assert!(!state.lock().unwrap().is_running);
+    assert_eq!(processor.transitions(), 2);
+}
+
+#[test]
+fn test_set_mode_applies_immediately_and_persists() {
+    // Test that a mode switch bypasses the debounce, leaves the running state
+    // alone and is saved to the config file
+    use rmm::config::{Config, KeepAwakeMode};
+    use std::sync::RwLock;
+
+    let dir = std::env::temp_dir().join(format!("rmm-mode-{}", std::process::id()));
+    let path = dir.join("config.json");
+    let config = Arc::new(RwLock::new(Config::default()));
+    let state = Arc::new(Mutex::new(AppState::new()));
+    state.lock().unwrap().is_running = true;
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::from_millis(300))
+        .with_persistence(Arc::clone(&config), path.clone());
+
+    let now = Instant::now();
+    processor.submit(Command::SetMode(KeepAwakeMode::Scroll), Cause::Tray, now);
+    assert_eq!(state.lock().unwrap().keep_awake_mode, KeepAwakeMode::Scroll);
+    assert!(state.lock().unwrap().is_running);
+    assert_eq!(processor.poll(now + Duration::from_secs(1)), None);
+    assert_eq!(processor.transitions(), 0);
+
+    assert_eq!(
+        config.read().unwrap().keep_awake_mode,
+        KeepAwakeMode::Scroll
+    );
+    let saved = Config::load_from(&path).unwrap();
+    assert_eq!(saved.keep_awake_mode, KeepAwakeMode::Scroll);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_set_mode_without_persistence() {
+    // Test switching modes with no config attached, including a no-op switch
+    use rmm::config::KeepAwakeMode;
+
+    let state = Arc::new(Mutex::new(AppState::new()));
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::ZERO);
+    let now = Instant::now();
+    processor.submit(Command::SetMode(KeepAwakeMode::Mouse), Cause::Ipc, now);
+    assert_eq!(state.lock().unwrap().keep_awake_mode, KeepAwakeMode::Mouse);
+    processor.submit(Command::SetMode(KeepAwakeMode::Key), Cause::Ipc, now);
+    assert_eq!(state.lock().unwrap().keep_awake_mode, KeepAwakeMode::Key);
+}
+
+#[test]
+fn test_pause_stops_moves_until_resumed() {
+    // Test that the tray's pause action clears is_running, that the move
+    // check is then a no-op for an idle user, and that resume re-enables it
+    use rmm::command::toggle_label;
+    use rmm::config::Config;
+    use rmm::mouse::{check_and_move, LazyController, MoveOutcome};
+
+    let config = Config {
+        verify_delay_ms: 0,
+        ..Default::default()
+    };
+    let state = Arc::new(Mutex::new(AppState::new()));
+    state.lock().unwrap().start(&config);
+    state.lock().unwrap().last_activity = Instant::now() - Duration::from_secs(60);
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::ZERO);
+    assert_eq!(toggle_label(true), "Pause");
+
+    processor.submit(Command::Toggle, Cause::Tray, Instant::now());
+    assert!(!state.lock().unwrap().is_running);
+    assert_eq!(toggle_label(false), "Resume");
+
+    let mut controller =
+        LazyController::with_factory(|| -> rmm::Result<rmm::mouse::MouseController> {
+            panic!("a paused check must not touch the mouse")
+        });
+    assert_eq!(
+        check_and_move(Arc::clone(&state), &config, &mut controller).unwrap(),
+        MoveOutcome::Skipped
+    );
+
+    processor.submit(Command::Toggle, Cause::Tray, Instant::now());
+    assert!(state.lock().unwrap().is_running);
+}
+
+#[test]
+fn test_snooze_command_and_label() {
+    // Test that a snooze command sets the deadline, a zero one cancels it,
+    // and the tray label rounds the time left up to whole minutes
+    use rmm::command::snooze_label;
+
+    let state = Arc::new(Mutex::new(AppState::new()));
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::from_millis(300));
+    let now = Instant::now();
+
+    processor.submit(
+        Command::Snooze(Duration::from_secs(15 * 60)),
+        Cause::Tray,
+        now,
+    );
+    assert_eq!(
+        state.lock().unwrap().snooze_until,
+        Some(now + Duration::from_secs(15 * 60))
+    );
+    processor.submit(Command::Snooze(Duration::ZERO), Cause::Tray, now);
+    assert_eq!(state.lock().unwrap().snooze_until, None);
+
+    assert_eq!(snooze_label(None), "Not snoozed");
+    assert_eq!(
+        snooze_label(Some(Duration::from_secs(61))),
+        "Snoozed, 2 min left (click to resume)"
+    );
+}
+
+#[test]
+fn test_set_interval_changes_next_heartbeat_sleep() {
+    // Test that an interval picked from the tray updates the live config,
+    // which the heartbeat loop reads for its next sleep, and is saved
+    use rmm::config::Config;
+    use rmm::heartbeat::Heartbeat;
+    use std::sync::RwLock;
+
+    let path = std::env::temp_dir().join(format!("rmm-interval-{}.json", std::process::id()));
+    let config = Arc::new(RwLock::new(Config::default()));
+    let state = Arc::new(Mutex::new(AppState::new()));
+    let heartbeat = Heartbeat::new(Arc::clone(&config), Arc::clone(&state));
+    assert_eq!(heartbeat.interval(), Duration::from_secs(10));
+
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::from_millis(300))
+        .with_persistence(Arc::clone(&config), path.clone());
+    processor.submit(Command::SetInterval(120), Cause::Tray, Instant::now());
+    assert_eq!(heartbeat.interval(), Duration::from_secs(120));
+    assert_eq!(Config::load_from(&path).unwrap().heartbeat_interval, 120);
+
+    // Zero would spin the loop, so it's ignored
+    processor.submit(Command::SetInterval(0), Cause::Tray, Instant::now());
+    assert_eq!(heartbeat.interval(), Duration::from_secs(120));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_set_interval_saves_only_the_interval() {
+    // Test that changing the interval writes just heartbeat_interval into the
+    // file and leaves out values that only live in memory
+    use rmm::config::Config;
+    use std::sync::RwLock;
+
+    let path = std::env::temp_dir().join(format!("rmm-interval-field-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{"inactivity_threshold": 42}"#).unwrap();
+    let config = Arc::new(RwLock::new(Config {
+        // As if set by RMM_MOVEMENT_DELTA
+        movement_delta: 3,
+        ..Default::default()
+    }));
+    let state = Arc::new(Mutex::new(AppState::new()));
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::ZERO)
+        .with_persistence(Arc::clone(&config), path.clone());
+
+    processor.submit(Command::SetInterval(60), Cause::Tray, Instant::now());
+    assert_eq!(config.read().unwrap().heartbeat_interval, 60);
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(
+        saved,
+        serde_json::json!({"heartbeat_interval": 60, "inactivity_threshold": 42})
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_set_mode_saves_only_the_mode() {
+    // Test that switching modes writes just keep_awake_mode into the file,
+    // replacing its old alias, and not the rest of the live config; a TOML
+    // file stays TOML
+    use rmm::config::{Config, KeepAwakeMode};
+    use std::sync::RwLock;
+
+    let dir = std::env::temp_dir().join(format!("rmm-mode-field-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = Arc::new(RwLock::new(Config {
+        // As if set by RMM_HEARTBEAT_INTERVAL
+        heartbeat_interval: 7,
+        ..Default::default()
+    }));
+    let state = Arc::new(Mutex::new(AppState::new()));
+
+    let path = dir.join("config.json");
+    std::fs::write(
+        &path,
+        r#"{"simulation_mode": "key", "inactivity_threshold": 42}"#,
+    )
+    .unwrap();
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::ZERO)
+        .with_persistence(Arc::clone(&config), path.clone());
+    processor.submit(
+        Command::SetMode(KeepAwakeMode::Scroll),
+        Cause::Tray,
+        Instant::now(),
+    );
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(
+        saved,
+        serde_json::json!({"keep_awake_mode": "scroll", "inactivity_threshold": 42})
+    );
+
+    let path = dir.join("config.toml");
+    std::fs::write(&path, "inactivity_threshold = 42\n").unwrap();
+    let mut processor = CommandProcessor::new(Arc::clone(&state), Duration::ZERO)
+        .with_persistence(Arc::clone(&config), path.clone());
+    processor.submit(
+        Command::SetMode(KeepAwakeMode::Key),
+        Cause::Tray,
+        Instant::now(),
+    );
+    let saved: toml::Table = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved["keep_awake_mode"].as_str(), Some("key"));
+    assert_eq!(saved["inactivity_threshold"].as_integer(), Some(42));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
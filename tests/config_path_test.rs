@@ -0,0 +1,48 @@
+// Kept in its own test binary: it sets RMM_CONFIG for the whole process.
+
+use rmm::config::{Config, CONFIG_PATH_ENV};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_rmm_config_redirects_load_and_save() {
+    // Test that RMM_CONFIG points both save and load at the given file
+    let path = std::env::temp_dir()
+        .join(format!("rmm-config-path-{}", std::process::id()))
+        .join("custom.json");
+    std::env::set_var(CONFIG_PATH_ENV, &path);
+    assert_eq!(Config::config_path().unwrap(), path);
+
+    let config = Config {
+        inactivity_threshold: 77,
+        ..Default::default()
+    };
+    config.save().unwrap();
+    assert!(path.exists());
+    assert_eq!(Config::load().unwrap().inactivity_threshold, 77);
+
+    std::env::remove_var(CONFIG_PATH_ENV);
+    assert_ne!(Config::config_path().unwrap(), path);
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_config_path_override_resolution() {
+    // Test that an explicit override wins and an empty one falls back to the default
+    let custom = Config::config_path_from(Some("/tmp/elsewhere.toml".into())).unwrap();
+    assert_eq!(custom, PathBuf::from("/tmp/elsewhere.toml"));
+    let default = Config::config_path_from(None).unwrap();
+    assert!(default.ends_with("config.json"));
+    assert_eq!(Config::config_path_from(Some("".into())).unwrap(), default);
+}
+
+#[test]
+fn test_cli_config_flag() {
+    // Test that --config takes the following argument as the path
+    use rmm::cli::Cli;
+
+    let cli = Cli::parse(["--config", "/etc/rmm.json", "--quiet"]).unwrap();
+    assert_eq!(cli.config, Some(PathBuf::from("/etc/rmm.json")));
+    assert!(cli.quiet);
+    assert!(Cli::parse(["--config"]).is_err());
+}
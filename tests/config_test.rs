@@ -0,0 +1,510 @@
+use rmm::config::{format_uptime, TrayConfig};
+use std::time::Duration;
+
+#[test]
+fn test_default_about_text() {
+    // Test that the default About text keeps the original wording plus runtime info
+    let text = TrayConfig::default().about_text("0.1.0", Duration::from_secs(3900));
+
+    assert_eq!(
+        text,
+        "RMM - Rust Mouse Monitor\n\nAuthor: Red\n\n\
+         Created with LLM help for learning Rust concepts\n\n\
+         Version: 0.1.0\nUptime: 1h 05m"
+    );
+}
+
+#[test]
+fn test_custom_about_text_from_config() {
+    // Test that a fork can override the About text via config
+    let config: rmm::Config =
+        serde_json::from_str(r#"{"tray": {"app_name": "Acme Keepalive", "author": "IT Dept"}}"#)
+            .unwrap();
+
+    let text = config.tray.about_text("2.0.0", Duration::from_secs(59));
+
+    assert!(text.starts_with("Acme Keepalive\n\nAuthor: IT Dept\n\n"));
+    assert!(text.ends_with("Version: 2.0.0\nUptime: 0m"));
+    assert_eq!(config.tray.about_title, "About RMM");
+}
+
+#[test]
+fn test_format_uptime() {
+    // Test uptime formatting across minutes, hours and days
+    assert_eq!(format_uptime(Duration::from_secs(7 * 60)), "7m");
+    assert_eq!(format_uptime(Duration::from_secs(3600 + 4 * 60)), "1h 04m");
+    assert_eq!(
+        format_uptime(Duration::from_secs(2 * 86400 + 3 * 3600 + 60)),
+        "2d 3h 01m"
+    );
+}
+
+#[test]
+fn test_parse_duration_strings() {
+    // Test each accepted duration format
+    use rmm::config::parse_duration_secs;
+
+    assert_eq!(parse_duration_secs("30s"), Some(30));
+    assert_eq!(parse_duration_secs("5m"), Some(300));
+    assert_eq!(parse_duration_secs("1h"), Some(3600));
+    assert_eq!(parse_duration_secs("1d"), Some(86400));
+    assert_eq!(parse_duration_secs("1h30m"), Some(5400));
+    assert_eq!(parse_duration_secs("45"), Some(45));
+    assert_eq!(parse_duration_secs(""), None);
+    assert_eq!(parse_duration_secs("5 minutes"), None);
+    assert_eq!(parse_duration_secs("1h30"), None);
+    assert_eq!(parse_duration_secs("-5s"), None);
+}
+
+#[test]
+fn test_interval_fields_accept_numbers_and_strings() {
+    // Test that interval fields take either seconds or duration strings
+    let config: rmm::Config = serde_json::from_str(
+        r#"{
+            "heartbeat_interval": "5m",
+            "worker_interval": 20,
+            "inactivity_threshold": "1h",
+            "post_wake_grace_secs": "90s",
+            "keyboard_idle_threshold": "2m"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(config.heartbeat_interval, 300);
+    assert_eq!(config.worker_interval, 20);
+    assert_eq!(config.inactivity_threshold, 3600);
+    assert_eq!(config.post_wake_grace_secs, 90);
+    assert_eq!(config.keyboard_idle_threshold, Some(120));
+
+    // Numbers still round-trip as numbers
+    let saved = serde_json::to_value(&config).unwrap();
+    assert_eq!(saved["heartbeat_interval"], 300);
+}
+
+#[test]
+fn test_invalid_duration_string_is_rejected() {
+    // Test that an unparseable duration fails with a message naming the value
+    let err = match serde_json::from_str::<rmm::Config>(r#"{"heartbeat_interval": "soon"}"#) {
+        Ok(_) => panic!("expected an invalid duration error"),
+        Err(e) => e.to_string(),
+    };
+    assert!(err.contains("invalid duration \"soon\""), "{}", err);
+}
+
+#[test]
+fn test_platform_defaults() {
+    // Test that platform tuning only touches the fields meant for that platform
+    use rmm::Config;
+
+    let neutral = Config::default();
+    let platform = Config::platform_defaults();
+    assert_eq!(neutral.verify_delay_ms, 100);
+    assert_eq!(neutral.post_wake_grace_secs, 60);
+    assert_eq!(platform.inactivity_threshold, neutral.inactivity_threshold);
+
+    if cfg!(target_os = "macos") {
+        assert_eq!(platform.verify_delay_ms, 250);
+    } else {
+        assert_eq!(platform.verify_delay_ms, neutral.verify_delay_ms);
+    }
+    if cfg!(target_os = "windows") {
+        assert_eq!(platform.post_wake_grace_secs, 120);
+    } else {
+        assert_eq!(platform.post_wake_grace_secs, neutral.post_wake_grace_secs);
+    }
+
+    // Fields missing from the file come from the platform defaults
+    let loaded: Config = serde_json::from_str("{}").unwrap();
+    assert_eq!(loaded.verify_delay_ms, platform.verify_delay_ms);
+    assert_eq!(loaded.post_wake_grace_secs, platform.post_wake_grace_secs);
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("rmm-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Wrapper whose serialization always fails
+struct Unserializable;
+
+impl serde::Serialize for Unserializable {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("cannot serialize"))
+    }
+}
+
+#[test]
+fn test_save_round_trips() {
+    // Test that save_to writes a complete file that loads back
+    use rmm::Config;
+
+    let dir = temp_dir("save");
+    let path = dir.join("config.json");
+    let config = Config {
+        inactivity_threshold: 42,
+        ..Default::default()
+    };
+    config.save_to(&path).unwrap();
+
+    assert_eq!(Config::resolve(&path).unwrap().inactivity_threshold, 42);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_save_writes_complete_private_file() {
+    // Test that a save over an existing world-readable file leaves the full
+    // config behind, readable by the owner only on Unix
+    use rmm::Config;
+
+    let dir = temp_dir("save-private");
+    let path = dir.join("config.json");
+    std::fs::write(&path, "{}").unwrap();
+    let config = Config {
+        movement_delta: 7,
+        ..Default::default()
+    };
+    config.save_to(&path).unwrap();
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(written, serde_json::to_string_pretty(&config).unwrap());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_serialize_error_leaves_file_untouched() {
+    // Test that a serialization failure never touches the existing config
+    use rmm::config::write_json_atomic;
+
+    let dir = temp_dir("save-serialize");
+    let path = dir.join("config.json");
+    std::fs::write(&path, "{\"inactivity_threshold\": 5}").unwrap();
+
+    assert!(write_json_atomic(&path, &Unserializable).is_err());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"inactivity_threshold\": 5}"
+    );
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_write_error_keeps_original_and_cleans_up() {
+    // Test that failing to write or rename keeps the original and removes the temp file
+    use rmm::config::write_json_atomic;
+    use rmm::Config;
+
+    let dir = temp_dir("save-write");
+    let path = dir.join("config.json");
+    std::fs::write(&path, "original").unwrap();
+
+    // The temporary file can't be created when a directory occupies its name
+    let blocker = dir.join(".config.json.tmp");
+    std::fs::create_dir(&blocker).unwrap();
+    assert!(write_json_atomic(&path, &Config::default()).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+    std::fs::remove_dir(&blocker).unwrap();
+
+    // The rename can't replace a non-empty directory; the temp file is rolled back
+    let occupied = dir.join("occupied");
+    std::fs::create_dir(&occupied).unwrap();
+    std::fs::write(occupied.join("keep"), "x").unwrap();
+    assert!(write_json_atomic(&occupied, &Config::default()).is_err());
+    assert!(!dir.join(".occupied.tmp").exists());
+    assert!(occupied.join("keep").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_load_json_with_any_extension() {
+    // Test that a JSON file named .conf is detected by content
+    use rmm::Config;
+
+    let dir = temp_dir("format-json");
+    let path = dir.join("rmm.conf");
+    std::fs::write(
+        &path,
+        r#"{"inactivity_threshold": 300, "easing": "linear"}"#,
+    )
+    .unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.inactivity_threshold, 300);
+    assert_eq!(config.easing, rmm::config::Easing::Linear);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_load_toml_with_any_extension() {
+    // Test that a TOML file named .txt is detected by content
+    use rmm::Config;
+
+    let dir = temp_dir("format-toml");
+    let path = dir.join("settings.txt");
+    std::fs::write(
+        &path,
+        "inactivity_threshold = \"5m\"\nmovement_delta = 25\n\n[tray]\napp_name = \"Jiggler\"\n",
+    )
+    .unwrap();
+
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.inactivity_threshold, 300);
+    assert_eq!(config.movement_delta, 25);
+    assert_eq!(config.tray.app_name, "Jiggler");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_load_reports_both_format_errors() {
+    // Test that content that is neither JSON nor TOML names the file and both parsers
+    use rmm::Config;
+
+    let dir = temp_dir("format-bad");
+    let path = dir.join("config");
+    std::fs::write(&path, "inactivity_threshold: [oops").unwrap();
+
+    let err = match Config::load_from(&path) {
+        Ok(_) => panic!("expected a parse error"),
+        Err(e) => e.to_string(),
+    };
+    assert!(err.contains("config"), "{}", err);
+    assert!(err.contains("not valid JSON"), "{}", err);
+    assert!(err.contains("or TOML"), "{}", err);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_env_lines_round_trip() {
+    // Test that exported lines, sourced by a shell and applied to a default
+    // config, reproduce the original
+    use rmm::config::{Easing, Region};
+    use rmm::Config;
+    use std::process::Command;
+
+    let mut original = Config {
+        inactivity_threshold: 300,
+        easing: Easing::Linear,
+        move_region: Some(Region {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+        }),
+        keyboard_idle_threshold: Some(45),
+        pause_lock_file: Some("/tmp/my rmm.lock".into()),
+        blocklist_processes: vec!["Zoom Meeting".into(), "#it's $HOME".into()],
+        ..Default::default()
+    };
+    original.tray.app_name = "My Jiggler".into();
+
+    let lines = original.to_env_lines();
+    assert!(lines.contains(&"RMM_INACTIVITY_THRESHOLD=300".to_string()));
+    assert!(lines.contains(&"RMM_EASING=linear".to_string()));
+    assert!(lines.contains(&"RMM_RANDOM_SEED=".to_string()));
+
+    assert!(lines.contains(&"RMM_PAUSE_LOCK_FILE='/tmp/my rmm.lock'".to_string()));
+
+    let output = Command::new("sh")
+        .args(["-c", "set -a; eval \"$1\"; exec env", "sh"])
+        .arg(lines.join("\n"))
+        .env_clear()
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let env = String::from_utf8(output.stdout).unwrap();
+    let vars = env
+        .lines()
+        .filter(|line| line.starts_with("RMM_"))
+        .map(|line| {
+            let (name, value) = line.split_once('=').unwrap();
+            (name.to_string(), value.to_string())
+        });
+    let mut restored = Config::default();
+    restored.apply_env_overrides(vars).unwrap();
+
+    assert_eq!(restored.to_env_lines(), lines);
+    assert_eq!(restored.move_region, original.move_region);
+    assert_eq!(restored.pause_lock_file, original.pause_lock_file);
+    assert_eq!(restored.blocklist_processes, original.blocklist_processes);
+    assert_eq!(restored.tray.app_name, "My Jiggler");
+}
+
+#[test]
+fn test_env_overrides() {
+    // Test single overrides, clearing an option, ignored names and a bad value
+    use rmm::Config;
+
+    let vars = |pairs: &[(&str, &str)]| {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut config = Config {
+        random_seed: Some(7),
+        ..Default::default()
+    };
+    config
+        .apply_env_overrides(vars(&[
+            ("RMM_HEARTBEAT_INTERVAL", "\"2m\""),
+            ("RMM_RANDOM_SEED", ""),
+            ("RMM_CONFIG", "/elsewhere.json"),
+            ("PATH", "/usr/bin"),
+        ]))
+        .unwrap();
+    assert_eq!(config.heartbeat_interval, 120);
+    assert_eq!(config.random_seed, None);
+
+    let err = config
+        .apply_env_overrides(vars(&[("RMM_MAX_ERRORS", "lots")]))
+        .map(|_| ())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("RMM_"), "{}", err);
+    assert_eq!(config.max_errors, 10);
+}
+
+#[test]
+fn test_simulation_mode_alias() {
+    // Test that simulation_mode is read as keep_awake_mode, defaulting to mouse
+    use rmm::config::{Config, KeepAwakeMode};
+
+    let config: Config = serde_json::from_str(r#"{"simulation_mode": "key"}"#).unwrap();
+    assert_eq!(config.keep_awake_mode, KeepAwakeMode::Key);
+    let config: Config = serde_json::from_str("{}").unwrap();
+    assert_eq!(config.keep_awake_mode, KeepAwakeMode::Mouse);
+}
+
+#[test]
+fn test_idle_source_alias() {
+    // Test that idle_source = "system" is read as the poll activity mode
+    use rmm::config::{ActivityMode, Config};
+
+    let config: Config = serde_json::from_str(r#"{"idle_source": "system"}"#).unwrap();
+    assert_eq!(config.activity_mode, ActivityMode::Poll);
+    let config: Config = serde_json::from_str(r#"{"idle_source": "listener"}"#).unwrap();
+    assert_eq!(config.activity_mode, ActivityMode::Listener);
+}
+
+#[test]
+fn test_validate_accepts_defaults() {
+    // Test that the default and platform-tuned configs are valid
+    use rmm::Config;
+
+    assert!(Config::default().validate().is_ok());
+    assert!(Config::platform_defaults().validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_each_invalid_field() {
+    // Test that every bounded field is rejected with a message naming it
+    use rmm::Config;
+
+    let cases: [(&str, Config); 7] = [
+        (
+            "heartbeat_interval",
+            Config {
+                heartbeat_interval: 0,
+                ..Default::default()
+            },
+        ),
+        (
+            "worker_interval",
+            Config {
+                worker_interval: 0,
+                ..Default::default()
+            },
+        ),
+        (
+            "inactivity_threshold",
+            Config {
+                inactivity_threshold: 0,
+                ..Default::default()
+            },
+        ),
+        (
+            "movement_delta",
+            Config {
+                movement_delta: 0,
+                ..Default::default()
+            },
+        ),
+        (
+            "min_movement_delta",
+            Config {
+                min_movement_delta: 50,
+                max_movement_delta: 20,
+                ..Default::default()
+            },
+        ),
+        (
+            "max_errors",
+            Config {
+                max_errors: 0,
+                ..Default::default()
+            },
+        ),
+        (
+            "verify_poll_ms",
+            Config {
+                verify_poll_ms: 0,
+                ..Default::default()
+            },
+        ),
+    ];
+    for (field, config) in cases {
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains(field), "{} not mentioned in {:?}", field, err);
+    }
+}
+
+#[test]
+fn test_reload_rejects_invalid_values() {
+    // Test that a reload with a zero interval keeps the live config
+    use rmm::Config;
+    use std::sync::{Arc, RwLock};
+
+    let path = std::env::temp_dir().join(format!("rmm-validate-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{"heartbeat_interval": 0}"#).unwrap();
+    let shared = Arc::new(RwLock::new(Config::default()));
+    assert!(Config::reload(&shared, &path).is_err());
+    assert_eq!(shared.read().unwrap().heartbeat_interval, 10);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_load_migrates_unversioned_file() {
+    // Test that a config file written before versioning is loaded with its
+    // values, stamped with the current version and saved back with every field
+    use rmm::config::CONFIG_VERSION;
+    use rmm::Config;
+
+    let path = std::env::temp_dir().join(format!("rmm-migrate-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{"inactivity_threshold": 42}"#).unwrap();
+
+    let config = Config::load_at(&path).unwrap();
+    assert_eq!(config.version, CONFIG_VERSION);
+    assert_eq!(config.inactivity_threshold, 42);
+
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["version"], CONFIG_VERSION);
+    assert_eq!(saved["inactivity_threshold"], 42);
+    assert!(saved.get("max_move_distance").is_some());
+
+    // Already current: loading again leaves it as is
+    let mut again = Config::load_from(&path).unwrap();
+    assert!(!again.migrate());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,51 @@
+use rmm::coordination::{is_locked, LockCheck};
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn lock_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rmm-{}-{}.lock", name, std::process::id()));
+    fs::write(&path, "").unwrap();
+    path
+}
+
+#[test]
+fn test_is_locked_detects_holder() {
+    // Test that a held lock is detected and a released or missing one isn't
+    let path = lock_path("coord");
+    assert!(!is_locked(&path));
+
+    let holder = File::open(&path).unwrap();
+    holder.lock().unwrap();
+    assert!(is_locked(&path));
+
+    holder.unlock().unwrap();
+    assert!(!is_locked(&path));
+
+    fs::remove_file(&path).unwrap();
+    assert!(!is_locked(&path));
+}
+
+#[test]
+fn test_lock_check_rechecks_per_interval() {
+    // Test that the cached result is reused within the interval and refreshed after
+    let path = lock_path("coord-interval");
+    let mut check = LockCheck::new();
+    let every = Duration::from_secs(10);
+    let start = Instant::now();
+
+    assert!(!check.is_paused(None, every, start));
+    assert!(!check.is_paused(Some(&path), every, start));
+
+    let holder = File::open(&path).unwrap();
+    holder.lock().unwrap();
+    // Still within the interval: cached "unlocked"
+    assert!(!check.is_paused(Some(&path), every, start + Duration::from_secs(5)));
+    assert!(check.is_paused(Some(&path), every, start + Duration::from_secs(10)));
+
+    drop(holder);
+    assert!(check.is_paused(Some(&path), every, start + Duration::from_secs(15)));
+    assert!(!check.is_paused(Some(&path), every, start + Duration::from_secs(20)));
+
+    fs::remove_file(&path).unwrap();
+}
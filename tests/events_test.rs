@@ -0,0 +1,58 @@
+use chrono::{Duration, Local, TimeZone};
+use rmm::events::{EventKind, EventLog};
+
+#[test]
+fn test_push_evicts_oldest() {
+    // Test that the log keeps only the newest `capacity` events, oldest first
+    let start = Local.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+    let mut log = EventLog::new(3);
+    assert!(log.is_empty());
+    for i in 0..5 {
+        log.push(
+            EventKind::Move,
+            format!("move {}", i),
+            start + Duration::seconds(i),
+        );
+    }
+    assert_eq!(log.len(), 3);
+    let details: Vec<&str> = log.iter().map(|event| event.detail.as_str()).collect();
+    assert_eq!(details, ["move 2", "move 3", "move 4"]);
+
+    let mut disabled = EventLog::new(0);
+    disabled.push(EventKind::Error, "dropped", start);
+    assert!(disabled.is_empty());
+}
+
+#[test]
+fn test_json_shape() {
+    // Test that the JSON is an object with an "events" array of at/kind/detail
+    let at = Local.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+    let mut log = EventLog::new(10);
+    log.push(EventKind::Pause, "tray", at);
+    log.push(EventKind::Reload, "2 change(s)", at);
+
+    let json = log.to_json();
+    let events = json["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["kind"], "pause");
+    assert_eq!(events[0]["detail"], "tray");
+    assert!(events[0]["at"]
+        .as_str()
+        .unwrap()
+        .starts_with("2024-03-01T09:00:00"));
+    assert_eq!(events[1]["kind"], "reload");
+    assert_eq!(events[0].as_object().unwrap().len(), 3);
+}
+
+#[test]
+fn test_state_transitions_are_logged() {
+    // Test that pausing and resuming the app state lands in its event log
+    use rmm::state::{AppState, Cause};
+
+    let mut state = AppState::new();
+    state.set_running(true, Cause::Startup);
+    state.set_running(true, Cause::Tray);
+    state.set_running(false, Cause::Hotkey);
+    let kinds: Vec<EventKind> = state.events.iter().map(|event| event.kind).collect();
+    assert_eq!(kinds, [EventKind::Resume, EventKind::Pause]);
+}
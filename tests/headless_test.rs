@@ -0,0 +1,211 @@
+// These tests only touch library code, so they must pass with
+// `cargo test --no-default-features` (no tray, no native dialogs).
+use rmm::config::Config;
+use rmm::mouse::should_move;
+use rmm::state::AppState;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_should_move_when_idle_and_running() {
+    // Test that an idle, running state triggers a move
+    let mut state = AppState::new();
+    state.is_running = true;
+    state.last_activity = Instant::now() - Duration::from_secs(30);
+
+    assert!(should_move(&state, &Config::default()));
+}
+
+#[test]
+fn test_should_not_move_when_recently_active() {
+    // Test that recent activity suppresses the move
+    let mut state = AppState::new();
+    state.is_running = true;
+    state.last_activity = Instant::now();
+
+    assert!(!should_move(&state, &Config::default()));
+}
+
+#[test]
+fn test_should_not_move_when_stopped() {
+    // Test that a stopped state never moves, even when idle
+    let mut state = AppState::new();
+    state.last_activity = Instant::now() - Duration::from_secs(30);
+
+    assert!(!should_move(&state, &Config::default()));
+}
+
+#[test]
+fn test_display_gate_dormant_without_displays() {
+    // Test that dormant mode sleeps only when zero displays are reported
+    use rmm::config::NoDisplayAction;
+    use rmm::mouse::{display_gate, DisplayGate};
+
+    assert_eq!(
+        display_gate(NoDisplayAction::Dormant, Some(0)),
+        DisplayGate::Dormant
+    );
+    assert_eq!(
+        display_gate(NoDisplayAction::Dormant, Some(1)),
+        DisplayGate::Active
+    );
+    assert_eq!(
+        display_gate(NoDisplayAction::Dormant, None),
+        DisplayGate::Active
+    );
+}
+
+#[test]
+fn test_display_gate_quit_and_ignore() {
+    // Test the quit and ignore actions given the display count
+    use rmm::config::NoDisplayAction;
+    use rmm::mouse::{display_gate, DisplayGate};
+
+    assert_eq!(
+        display_gate(NoDisplayAction::Quit, Some(0)),
+        DisplayGate::Quit
+    );
+    assert_eq!(
+        display_gate(NoDisplayAction::Quit, Some(2)),
+        DisplayGate::Active
+    );
+    assert_eq!(
+        display_gate(NoDisplayAction::Ignore, Some(0)),
+        DisplayGate::Active
+    );
+}
+
+#[test]
+fn test_typing_pause_does_not_trigger_move() {
+    // Test that a short pause after typing isn't treated as idle when a
+    // higher keyboard threshold is configured, while mouse idleness still counts
+    use rmm::state::InputKind;
+
+    let config = Config {
+        inactivity_threshold: 5,
+        keyboard_idle_threshold: Some(60),
+        ..Default::default()
+    };
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.is_running = true;
+    state.record_input(InputKind::Mouse, now - Duration::from_secs(120));
+    state.record_input(InputKind::Keyboard, now - Duration::from_secs(10));
+
+    // Mouse idle for 2 minutes, but the user stopped typing only 10s ago
+    assert!(!should_move(&state, &config));
+
+    // Once the keyboard has been quiet past its own threshold, move
+    state.record_input(InputKind::Keyboard, now - Duration::from_secs(61));
+    state
+        .last_input
+        .insert(InputKind::Mouse, now - Duration::from_secs(120));
+    assert!(should_move(&state, &config));
+}
+
+#[test]
+fn test_recent_mouse_activity_still_blocks_move() {
+    // Test that mouse activity within the regular threshold blocks a move
+    // even when the keyboard has long been idle
+    use rmm::state::InputKind;
+
+    let config = Config {
+        inactivity_threshold: 30,
+        keyboard_idle_threshold: Some(60),
+        ..Default::default()
+    };
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.is_running = true;
+    state.record_input(InputKind::Keyboard, now - Duration::from_secs(600));
+    state.record_input(InputKind::Mouse, now - Duration::from_secs(5));
+
+    assert!(!should_move(&state, &config));
+}
+
+#[test]
+fn test_explicit_source_timeout_overrides_keyboard_threshold() {
+    // Test that a keyboard entry in source_timeouts wins over keyboard_idle_threshold
+    use rmm::state::InputKind;
+    use std::collections::BTreeMap;
+
+    let config = Config {
+        keyboard_idle_threshold: Some(60),
+        source_timeouts: BTreeMap::from([(InputKind::Keyboard, 90)]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        config.effective_source_timeouts().get(&InputKind::Keyboard),
+        Some(&90)
+    );
+}
+
+#[test]
+fn test_should_not_move_while_fast_movement_cooldown() {
+    // Test that the high-velocity cooldown suppresses moves even when idle
+    let config = Config {
+        high_velocity_threshold: Some(100.0),
+        ..Default::default()
+    };
+    let mut state = AppState::new();
+    state.start(&config);
+    let past = Instant::now() - Duration::from_secs(30);
+    for i in 0..4 {
+        state.velocity.sample(
+            (i as f64 * 100.0, 0.0),
+            past + Duration::from_millis(i * 10),
+        );
+    }
+    state.last_activity = past;
+
+    assert!(!should_move(&state, &config));
+}
+
+#[test]
+fn test_typing_presence_mode() {
+    // Test that the typing mode needs both an idle pointer and a recent keystroke
+    use rmm::mouse::typing_while_pointer_idle;
+    use rmm::state::InputKind;
+
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.record_input(InputKind::Mouse, now - Duration::from_secs(60));
+    state.record_input(InputKind::Keyboard, now - Duration::from_secs(5));
+
+    // Pointer idle 60s, typed 5s ago
+    assert!(typing_while_pointer_idle(&state, now, 30, 10));
+    // Keystroke outside the window: the user is away
+    assert!(!typing_while_pointer_idle(&state, now, 30, 2));
+    // Pointer used too recently
+    assert!(!typing_while_pointer_idle(&state, now, 120, 10));
+
+    // A recent button press counts as pointer activity
+    state.record_input(InputKind::Button, now - Duration::from_secs(1));
+    assert!(!typing_while_pointer_idle(&state, now, 30, 10));
+
+    // Never typed: no move even though the pointer is idle
+    let mut away = AppState::new();
+    away.record_input(InputKind::Mouse, now - Duration::from_secs(60));
+    assert!(!typing_while_pointer_idle(&away, now, 30, 10));
+}
+
+#[test]
+fn test_should_move_uses_typing_mode() {
+    // Test that should_move switches to the typing condition when configured
+    use rmm::state::InputKind;
+
+    let config = Config {
+        inactivity_threshold: 30,
+        typing_presence_window: Some(10),
+        ..Default::default()
+    };
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.is_running = true;
+    state.record_input(InputKind::Mouse, now - Duration::from_secs(60));
+    state.record_input(InputKind::Keyboard, now - Duration::from_secs(2));
+
+    // Normal mode would see recent keyboard input and hold off
+    assert!(!should_move(&state, &Config::default()));
+    assert!(should_move(&state, &config));
+}
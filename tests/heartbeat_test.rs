@@ -0,0 +1,93 @@
+use rmm::config::Config;
+use rmm::error::Result;
+use rmm::heartbeat::{sub_tick_offsets, Heartbeat};
+use rmm::mouse::{LazyController, MouseBackend};
+use rmm::shutdown::ShutdownSignal;
+use rmm::state::AppState;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Moves made by any `CountingMouse`
+static MOVES: AtomicUsize = AtomicUsize::new(0);
+
+/// Backend whose cursor follows every move, counted in [`MOVES`]
+struct CountingMouse {
+    position: (i32, i32),
+}
+
+impl MouseBackend for CountingMouse {
+    fn get_position(&mut self) -> Result<(i32, i32)> {
+        Ok(self.position)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
+        MOVES.fetch_add(1, Ordering::SeqCst);
+        self.position = (x, y);
+        Ok(())
+    }
+}
+
+fn counting_mouse() -> Result<CountingMouse> {
+    Ok(CountingMouse {
+        position: (100, 100),
+    })
+}
+
+#[test]
+fn test_sub_tick_offsets_spread_evenly() {
+    // Test that follow-up moves are evenly spaced across the interval
+    let interval = Duration::from_secs(10);
+    assert_eq!(
+        sub_tick_offsets(interval, 4),
+        vec![
+            Duration::from_millis(2500),
+            Duration::from_secs(5),
+            Duration::from_millis(7500)
+        ]
+    );
+    assert_eq!(sub_tick_offsets(interval, 2), vec![Duration::from_secs(5)]);
+
+    let offsets = sub_tick_offsets(Duration::from_secs(1), 7);
+    assert_eq!(offsets.len(), 6);
+    assert!(offsets.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(offsets
+        .iter()
+        .all(|offset| *offset < Duration::from_secs(1)));
+}
+
+#[test]
+fn test_sub_tick_offsets_single_move() {
+    // Test that one move per interval (or zero) schedules no follow-ups
+    assert!(sub_tick_offsets(Duration::from_secs(10), 1).is_empty());
+    assert!(sub_tick_offsets(Duration::from_secs(10), 0).is_empty());
+}
+
+#[test]
+fn test_burst_stops_on_shutdown() {
+    // Test that a tick with follow-up moves returns right after its first
+    // move once shutdown is requested, instead of sleeping through the burst
+    static SIGNAL: ShutdownSignal = ShutdownSignal::new();
+    let config = Config {
+        heartbeat_interval: 60,
+        moves_per_interval: 3,
+        inactivity_threshold: 5,
+        verify_delay_ms: 0,
+        ..Default::default()
+    };
+    let mut state = AppState::new();
+    state.start(&config);
+    state.last_activity = Instant::now() - Duration::from_secs(60);
+    let mut heartbeat = Heartbeat::with_controller(
+        Arc::new(RwLock::new(config)),
+        Arc::new(Mutex::new(state)),
+        LazyController::with_factory(counting_mouse),
+    )
+    .with_shutdown(&SIGNAL);
+
+    SIGNAL.request();
+    let started = Instant::now();
+    heartbeat.tick();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(MOVES.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,293 @@
+use rmm::api::ApiContext;
+use rmm::config::{Config, HttpApiConfig};
+use rmm::error::Result;
+use rmm::mouse::{self, LazyController, MouseBackend, MoveOutcome};
+use rmm::state::{AppState, SharedState};
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Backend whose cursor follows every move
+struct FollowingBackend {
+    position: (i32, i32),
+}
+
+impl MouseBackend for FollowingBackend {
+    fn get_position(&mut self) -> Result<(i32, i32)> {
+        Ok(self.position)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
+        self.position = (x, y);
+        Ok(())
+    }
+}
+
+fn move_with_mock(state: SharedState, config: &Config) -> Result<MoveOutcome> {
+    let mut controller = LazyController::with_factory(|| {
+        Ok(FollowingBackend {
+            position: (100, 100),
+        })
+    });
+    mouse::move_now(state, config, &mut controller)
+}
+
+/// Token the test server is configured with
+const TOKEN: &str = "test-token";
+
+/// Serve the API on an ephemeral local port
+fn start_server() -> (SocketAddr, SharedState) {
+    start_server_with(HttpApiConfig::default())
+}
+
+/// Serve the API with `http`, on an ephemeral port and with [`TOKEN`]
+fn start_server_with(http: HttpApiConfig) -> (SocketAddr, SharedState) {
+    let mut state = AppState::new();
+    let config = Config {
+        verify_delay_ms: 0,
+        ..Default::default()
+    };
+    state.start(&config);
+    state.started_at = Instant::now() - Duration::from_secs(30);
+    let state = Arc::new(Mutex::new(state));
+    let ctx = ApiContext {
+        state: Arc::clone(&state),
+        config: Arc::new(RwLock::new(config)),
+        config_path: Some(PathBuf::from("unused.json")),
+        log_path: PathBuf::from("unused.log"),
+        mouse_available: || true,
+        move_now: move_with_mock,
+    };
+    let http = HttpApiConfig {
+        enabled: true,
+        port: 0,
+        token: Some(TOKEN.to_string()),
+        ..http
+    };
+    let addr = rmm::http::start(&http, ctx).unwrap();
+    (addr, state)
+}
+
+/// Send one request and return the status code and JSON body
+fn request(addr: SocketAddr, method: &str, path: &str) -> (u16, serde_json::Value) {
+    let (status, body) = request_text(addr, method, path);
+    (status, serde_json::from_str(&body).unwrap_or_default())
+}
+
+/// Send one request and return the status code and body
+fn request_text(addr: SocketAddr, method: &str, path: &str) -> (u16, String) {
+    let headers = format!("Host: localhost\r\nAuthorization: Bearer {}\r\n", TOKEN);
+    send(addr, method, path, &headers)
+}
+
+/// Send one request with exactly these `headers` (each ending in CRLF)
+fn send(addr: SocketAddr, method: &str, path: &str, headers: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\n{}Content-Length: 0\r\n\r\n",
+        method, path, headers
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, body.to_string())
+}
+
+#[test]
+fn test_binds_to_localhost_by_default() {
+    // Test that the API is off and only reachable locally unless configured
+    let http = HttpApiConfig::default();
+    assert!(!http.enabled);
+    assert!(http.bind.is_loopback());
+}
+
+#[test]
+fn test_not_served_without_token() {
+    // Test that the API refuses to start when no token is configured
+    let (_, state) = start_server();
+    let ctx = ApiContext {
+        state,
+        config: Arc::new(RwLock::new(Config::default())),
+        config_path: Some(PathBuf::from("unused.json")),
+        log_path: PathBuf::from("unused.log"),
+        mouse_available: || true,
+        move_now: move_with_mock,
+    };
+    for token in [None, Some(String::new())] {
+        let http = HttpApiConfig {
+            enabled: true,
+            port: 0,
+            token,
+            ..Default::default()
+        };
+        assert!(rmm::http::start(&http, ctx.clone()).is_err());
+    }
+}
+
+#[test]
+fn test_requests_need_the_token() {
+    // Test that a request without the bearer token, or with a wrong one, is
+    // refused before reaching any endpoint
+    let (addr, state) = start_server();
+    let cases = [
+        ("Host: localhost\r\n", 401),
+        ("Host: localhost\r\nAuthorization: Bearer wrong\r\n", 401),
+        (
+            "Host: localhost\r\nAuthorization: Basic test-token\r\n",
+            401,
+        ),
+        (
+            "Host: localhost\r\nAuthorization: bearer test-token\r\n",
+            200,
+        ),
+    ];
+    for (headers, expected) in cases {
+        let (status, _) = send(addr, "GET", "/status", headers);
+        assert_eq!(status, expected, "{:?}", headers);
+    }
+    for path in ["/pause", "/move", "/reload"] {
+        let (status, _) = send(addr, "POST", path, "Host: localhost\r\n");
+        assert_eq!(status, 401, "{}", path);
+    }
+    let (status, _) = send(addr, "GET", "/logs", "Host: localhost\r\n");
+    assert_eq!(status, 401);
+    assert!(state.lock().unwrap().is_running);
+}
+
+#[test]
+fn test_foreign_host_is_refused() {
+    // Test that only requests naming the local machine are answered, so a web
+    // page can't reach the API through a rebound DNS name
+    let (addr, _state) = start_server();
+    let auth = format!("Authorization: Bearer {}\r\n", TOKEN);
+    let hosts = [
+        ("localhost", 200),
+        ("LOCALHOST:8765", 200),
+        ("127.0.0.1", 200),
+        ("[::1]:8765", 200),
+        ("attacker.example", 403),
+        ("attacker.example:8765", 403),
+        ("127.0.0.1.attacker.example", 403),
+    ];
+    for (host, expected) in hosts {
+        let headers = format!("Host: {}\r\n{}", host, auth);
+        let (status, _) = send(addr, "GET", "/status", &headers);
+        assert_eq!(status, expected, "{}", host);
+    }
+    let (status, _) = send(addr, "GET", "/status", &auth);
+    assert_eq!(status, 403);
+}
+
+#[test]
+fn test_non_loopback_bind_accepts_allowed_hosts() {
+    // Test that on a non-loopback bind a request with the token is answered
+    // when it names the machine as configured, and still refused otherwise
+    let (addr, _state) = start_server_with(HttpApiConfig {
+        bind: Ipv4Addr::UNSPECIFIED.into(),
+        allowed_hosts: vec!["rmm.lan".into()],
+        ..Default::default()
+    });
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, addr.port()));
+    let auth = format!("Authorization: Bearer {}\r\n", TOKEN);
+    let hosts = [
+        ("rmm.lan", 200),
+        ("RMM.lan:8765", 200),
+        ("localhost", 200),
+        ("attacker.example", 403),
+    ];
+    for (host, expected) in hosts {
+        let headers = format!("Host: {}\r\n{}", host, auth);
+        let (status, _) = send(addr, "GET", "/status", &headers);
+        assert_eq!(status, expected, "{}", host);
+    }
+    let (status, _) = send(addr, "GET", "/status", "Host: rmm.lan\r\n");
+    assert_eq!(status, 401);
+}
+
+#[test]
+fn test_status_reports_state() {
+    // Test that GET /status reports running state, failures and uptime
+    let (addr, state) = start_server();
+    state.lock().unwrap().error_count = 2;
+
+    let (status, body) = request(addr, "GET", "/status");
+    assert_eq!(status, 200);
+    assert_eq!(body["is_running"], true);
+    assert_eq!(body["error_count"], 2);
+    assert_eq!(body["total_moves"], 0);
+    assert_eq!(body["failed_moves"], 0);
+    assert!(body["uptime_secs"].as_u64().unwrap() >= 30);
+    assert!(body["last_moved_secs_ago"].is_u64());
+
+    let (status, _) = request(addr, "POST", "/status");
+    assert_eq!(status, 405);
+}
+
+#[test]
+fn test_pause_and_resume() {
+    // Test that POST /pause and /resume change the shared running state
+    let (addr, state) = start_server();
+
+    let (status, body) = request(addr, "POST", "/pause");
+    assert_eq!(status, 200);
+    assert_eq!(body["is_running"], false);
+    assert!(!state.lock().unwrap().is_running);
+
+    let (status, body) = request(addr, "POST", "/resume");
+    assert_eq!(status, 200);
+    assert_eq!(body["is_running"], true);
+    assert!(state.lock().unwrap().is_running);
+
+    let (status, _) = request(addr, "GET", "/pause");
+    assert_eq!(status, 405);
+}
+
+#[test]
+fn test_move_triggers_one_move() {
+    // Test that POST /move moves right away even though the user isn't idle,
+    // and that a paused RMM doesn't move
+    let (addr, state) = start_server();
+    state.lock().unwrap().last_activity = Instant::now();
+
+    let (status, body) = request(addr, "POST", "/move");
+    assert_eq!(status, 200);
+    assert_eq!(body["outcome"], "moved");
+    assert_eq!(state.lock().unwrap().total_moves(), 1);
+
+    request(addr, "POST", "/pause");
+    let (_, body) = request(addr, "POST", "/move");
+    assert_eq!(body["outcome"], "skipped");
+    assert_eq!(state.lock().unwrap().total_moves(), 1);
+}
+
+#[test]
+fn test_metrics_count_moves() {
+    // Test that GET /metrics serves the Prometheus counters and gauges and
+    // that rmm_moves_total goes up after a move
+    let (addr, _state) = start_server();
+    let sample = |body: &str, name: &str| -> u64 {
+        body.lines()
+            .find_map(|line| line.strip_prefix(&format!("{} ", name)))
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+
+    let (status, before) = request_text(addr, "GET", "/metrics");
+    assert_eq!(status, 200);
+    assert!(before.contains("# TYPE rmm_moves_total counter"));
+    assert_eq!(sample(&before, "rmm_moves_total"), 0);
+    assert_eq!(sample(&before, "rmm_move_failures_total"), 0);
+    assert_eq!(sample(&before, "rmm_error_count"), 0);
+    assert_eq!(sample(&before, "rmm_is_running"), 1);
+    assert!(sample(&before, "rmm_uptime_seconds") >= 30);
+
+    request(addr, "POST", "/move");
+    let (_, after) = request_text(addr, "GET", "/metrics");
+    assert_eq!(sample(&after, "rmm_moves_total"), 1);
+}
@@ -0,0 +1,28 @@
+use rmm::instance::InstanceLock;
+use rmm::RmmError;
+use std::fs;
+
+#[test]
+fn test_second_acquire_fails_until_released() {
+    // Test that the lock can't be taken twice, names the holder's pid, and
+    // can be taken again once released
+    let path = std::env::temp_dir().join(format!("rmm-instance-{}.lock", std::process::id()));
+
+    let first = InstanceLock::acquire(&path).unwrap();
+    assert_eq!(
+        fs::read_to_string(first.path()).unwrap().trim(),
+        std::process::id().to_string()
+    );
+
+    match InstanceLock::acquire(&path) {
+        Err(RmmError::AlreadyRunning(holder)) => {
+            assert_eq!(holder, format!("pid {}", std::process::id()))
+        }
+        other => panic!("expected AlreadyRunning, got {:?}", other),
+    }
+
+    drop(first);
+    let again = InstanceLock::acquire(&path).unwrap();
+    drop(again);
+    fs::remove_file(&path).unwrap();
+}
@@ -114,6 +114,7 @@ fn test_config_custom_values() {
         movement_delta: 5,
         max_errors: 3,
         auto_start: true,
+        ..Default::default()
     };
 
     assert_eq!(config.heartbeat_interval, 60);
@@ -153,3 +154,40 @@ fn test_error_count_increment() {
     state.error_count += 1;
     assert_eq!(state.error_count, 2);
 }
+
+#[test]
+fn test_heartbeat_and_worker_timers_independent() {
+    // Test that distinct heartbeat and worker intervals drive separate timers,
+    // and that worker ticks do housekeeping without counting as heartbeats
+    use rmm::config::Config;
+    use rmm::heartbeat::Heartbeat;
+    use rmm::state::AppState;
+    use rmm::worker::Worker;
+    use std::sync::{Arc, Mutex, RwLock};
+    use std::time::Duration;
+
+    let config = Arc::new(RwLock::new(Config {
+        heartbeat_interval: 60,
+        worker_interval: 5,
+        ..Default::default()
+    }));
+    let state = Arc::new(Mutex::new(AppState::new()));
+    let heartbeat = Heartbeat::new(Arc::clone(&config), Arc::clone(&state));
+    let mut worker = Worker::new(Arc::clone(&config), Arc::clone(&state));
+
+    assert_eq!(heartbeat.interval(), Duration::from_secs(60));
+    assert_eq!(worker.interval(), Duration::from_secs(5));
+
+    config.write().unwrap().heartbeat_interval = 15;
+    assert_eq!(heartbeat.interval(), Duration::from_secs(15));
+    assert_eq!(worker.interval(), Duration::from_secs(5));
+
+    worker.tick();
+    worker.tick();
+    let state = state.lock().unwrap();
+    assert_eq!(state.heartbeats, 0);
+    assert!(
+        state.metrics.day.is_some(),
+        "worker rolled the daily counter"
+    );
+}
@@ -0,0 +1,103 @@
+use rmm::api::{self, ApiContext, ApiRequest, MAX_LOG_LINES};
+use rmm::config::Config;
+use rmm::logging::tail_lines;
+use rmm::mouse::MoveOutcome;
+use rmm::state::AppState;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+
+fn temp_log(name: &str, lines: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rmm-{}-{}.log", name, std::process::id()));
+    let content: String = (1..=lines).map(|i| format!("line {}\n", i)).collect();
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_tail_returns_last_lines() {
+    // Test tailing a file with many more lines than requested (spans several chunks)
+    let path = temp_log("tail", 5000);
+
+    let tail = tail_lines(&path, 3).unwrap();
+    assert_eq!(tail, vec!["line 4998", "line 4999", "line 5000"]);
+
+    let tail = tail_lines(&path, 2000).unwrap();
+    assert_eq!(tail.len(), 2000);
+    assert_eq!(tail[0], "line 3001");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_tail_short_file() {
+    // Test that asking for more lines than exist returns the whole file
+    let path = temp_log("short", 2);
+
+    assert_eq!(tail_lines(&path, 10).unwrap(), vec!["line 1", "line 2"]);
+    assert!(tail_lines(&path, 0).unwrap().is_empty());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_logs_endpoint_bounds_line_count() {
+    // Test that /logs?lines=N is served and N is capped at MAX_LOG_LINES
+    let path = temp_log("endpoint", MAX_LOG_LINES + 500);
+    let ctx = ApiContext {
+        state: Arc::new(Mutex::new(AppState::new())),
+        config: Arc::new(RwLock::new(Config::default())),
+        config_path: Some(PathBuf::from("unused-config.json")),
+        log_path: path.clone(),
+        mouse_available: || true,
+        move_now: |_, _| Ok(MoveOutcome::Skipped),
+    };
+
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/logs?lines=2"));
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body, "line 1499\nline 1500\n");
+
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/logs?lines=999999"));
+    assert_eq!(response.body.lines().count(), MAX_LOG_LINES);
+
+    let response = api::handle(&ctx, &ApiRequest::new("GET", "/logs?lines=abc"));
+    assert_eq!(response.status, 400);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_truncate_keeps_tail() {
+    // Test that a large log is cut down to roughly the requested tail, on a line boundary
+    use rmm::logging::truncate_to_tail;
+
+    let path = temp_log("truncate", 20000);
+    let original = fs::metadata(&path).unwrap().len();
+    assert!(original > 100_000);
+
+    assert!(truncate_to_tail(&path, 4096).unwrap());
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.len() <= 4096);
+    assert!(content.len() > 4096 - 16);
+    assert!(content.starts_with("line "));
+    assert!(content.ends_with("line 20000\n"));
+
+    // Already small enough: left alone
+    assert!(!truncate_to_tail(&path, 4096).unwrap());
+    assert_eq!(fs::read_to_string(&path).unwrap(), content);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_should_log_startup_line() {
+    // Test that quiet mode drops routine INFO startup lines but keeps warnings and errors
+    use rmm::logging::should_log_startup;
+    use tracing::Level;
+
+    assert!(should_log_startup(false, Level::INFO));
+    assert!(!should_log_startup(true, Level::INFO));
+    assert!(!should_log_startup(true, Level::DEBUG));
+    assert!(should_log_startup(true, Level::WARN));
+    assert!(should_log_startup(true, Level::ERROR));
+}
@@ -0,0 +1,98 @@
+use chrono::NaiveDate;
+use rmm::metrics::Metrics;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+}
+
+#[test]
+fn test_midnight_rollover_resets_daily_count() {
+    // Test that the daily count resets only when the calendar day changes
+    let mut metrics = Metrics::default();
+    assert!(!metrics.roll_over(day(1)), "first day is not a reset");
+
+    metrics.record_move(day(1));
+    metrics.record_move(day(1));
+    assert_eq!(metrics.moves_on(day(1)), 2);
+    assert!(!metrics.roll_over(day(1)));
+    assert_eq!(metrics.moves_today, 2);
+
+    assert_eq!(metrics.moves_on(day(2)), 0, "stale count reads as zero");
+    assert!(metrics.roll_over(day(2)));
+    assert_eq!(metrics.moves_today, 0);
+    assert_eq!(metrics.day, Some(day(2)));
+
+    // A move recorded before the heartbeat noticed the new day starts the count at 1
+    metrics.record_move(day(3));
+    assert_eq!(metrics.moves_on(day(3)), 1);
+}
+
+#[test]
+fn test_save_and_load() {
+    // Test that metrics survive a save/load cycle and a missing file gives defaults
+    let dir = std::env::temp_dir().join(format!("rmm_metrics_test_{}", std::process::id()));
+    let path = dir.join("metrics.json");
+    assert_eq!(Metrics::load(&path), Metrics::default());
+
+    let mut metrics = Metrics::default();
+    metrics.record_move(day(5));
+    metrics.save(&path).unwrap();
+    assert_eq!(Metrics::load(&path), metrics);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_stats_roundtrip_across_runs() {
+    // Test that lifetime stats load, add up this run's moves, save and reload,
+    // keeping the first run timestamp
+    use rmm::metrics::Stats;
+    use rmm::state::AppState;
+
+    let dir = std::env::temp_dir().join(format!("rmm_stats_test_{}", std::process::id()));
+    let path = dir.join("stats.json");
+    let mut first_run = Vec::new();
+    for (moves, failures) in [(3, 1), (2, 0)] {
+        let mut state = AppState::new();
+        state.lifetime = Stats::load(&path);
+        first_run.push(state.lifetime.first_run);
+        (0..moves).for_each(|_| state.count_move());
+        (0..failures).for_each(|_| state.count_failed_move());
+        state.lifetime_stats().save(&path).unwrap();
+    }
+
+    let reloaded = Stats::load(&path);
+    assert_eq!(reloaded.total_moves, 5);
+    assert_eq!(reloaded.failed_moves, 1);
+    assert_eq!(first_run[1], first_run[0]);
+    assert_eq!(reloaded.first_run, first_run[0]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_corrupt_stats_kept_aside() {
+    // Test that an unparseable stats.json starts a new count and is kept as
+    // stats.json.bad instead of being overwritten
+    use rmm::metrics::Stats;
+
+    let dir = std::env::temp_dir().join(format!("rmm_stats_bad_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("stats.json");
+    std::fs::write(&path, "{\"total_moves\": 12").unwrap();
+
+    let stats = Stats::load(&path);
+    assert_eq!(stats.total_moves, 0);
+    assert!(!path.exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("stats.json.bad")).unwrap(),
+        "{\"total_moves\": 12"
+    );
+
+    // Missing: a fresh count with nothing set aside
+    std::fs::remove_file(dir.join("stats.json.bad")).unwrap();
+    assert_eq!(Stats::load(&path).total_moves, 0);
+    assert!(!dir.join("stats.json.bad").exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -116,3 +116,503 @@ fn test_heartbeat_interval_configuration() {
 
     assert_eq!(config.heartbeat_interval, 60);
 }
+
+#[test]
+fn test_smooth_steps_end_at_target() {
+    // Test that both easings finish exactly on the target position
+    use rmm::config::Easing;
+    use rmm::mouse::smooth_steps;
+
+    for easing in [Easing::Linear, Easing::EaseInOut] {
+        let path = smooth_steps((100, 100), (140, 80), 8, easing);
+        assert_eq!(path.len(), 8);
+        assert_eq!(*path.last().unwrap(), (140, 80));
+    }
+
+    // A single step jumps straight to the target
+    assert_eq!(
+        smooth_steps((0, 0), (10, 10), 1, Easing::EaseInOut),
+        vec![(10, 10)]
+    );
+}
+
+#[test]
+fn test_linear_vs_eased_velocity_profile() {
+    // Test that linear steps have constant speed while eased steps
+    // start and end slowly and peak in the middle
+    use rmm::config::Easing;
+    use rmm::mouse::smooth_steps;
+
+    let speeds = |easing| {
+        let path = smooth_steps((0, 0), (1000, 0), 10, easing);
+        let mut prev = 0;
+        path.iter()
+            .map(|&(x, _)| {
+                let speed = x - prev;
+                prev = x;
+                speed
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let linear = speeds(Easing::Linear);
+    assert!(linear.iter().all(|&v| v == 100));
+
+    let eased = speeds(Easing::EaseInOut);
+    let peak = *eased.iter().max().unwrap();
+    assert!(eased[0] < linear[0]);
+    assert!(eased[9] < linear[9]);
+    assert!(peak > 100);
+    assert!(eased[4] == peak || eased[5] == peak);
+}
+
+#[test]
+fn test_verify_by_delta_accepts_shifted_absolute_position() {
+    // Test that delta verification passes when the OS reports positions in a
+    // shifted coordinate space (e.g. a scaled secondary display) but the
+    // cursor still travelled the requested distance
+    use rmm::mouse::{delta_matches, within_tolerance};
+
+    // Mock backend: reports every position offset by (+37, -12)
+    let reported = |x: i32, y: i32| (x + 37, y - 12);
+    let before = reported(500, 400);
+    let after = reported(510, 410);
+
+    // Absolute check against the requested target fails...
+    assert!(!within_tolerance(after, (510, 410), 5));
+    // ...but the travelled delta matches
+    assert!(delta_matches(before, after, (10, 10), 5));
+}
+
+#[test]
+fn test_verify_by_delta_rejects_wrong_movement() {
+    // Test that delta verification fails when the cursor didn't move as requested
+    use rmm::mouse::delta_matches;
+
+    // Cursor clamped at the screen edge: didn't move at all
+    assert!(!delta_matches((1919, 1079), (1919, 1079), (10, 10), 5));
+    // Moved the wrong way
+    assert!(!delta_matches((100, 100), (90, 90), (10, 10), 5));
+    // Within tolerance of the requested delta
+    assert!(delta_matches((100, 100), (113, 106), (10, 10), 5));
+}
+
+#[test]
+fn test_confine_move_inside_region() {
+    // Test that a move well inside the region is untouched
+    use rmm::config::Region;
+    use rmm::mouse::confine_move;
+
+    let region = Region {
+        x: 100,
+        y: 100,
+        width: 200,
+        height: 100,
+    };
+
+    assert_eq!(
+        confine_move(&region, (150, 150), (160, 160)),
+        ((150, 150), (160, 160))
+    );
+}
+
+#[test]
+fn test_confine_move_on_edge_is_clamped() {
+    // Test that a move starting on the edge is clamped to stay inside
+    use rmm::config::Region;
+    use rmm::mouse::confine_move;
+
+    let region = Region {
+        x: 100,
+        y: 100,
+        width: 200,
+        height: 100,
+    };
+
+    // Bottom-right corner is (299, 199)
+    assert_eq!(
+        confine_move(&region, (299, 199), (309, 209)),
+        ((299, 199), (299, 199))
+    );
+    assert_eq!(
+        confine_move(&region, (100, 100), (90, 90)),
+        ((100, 100), (100, 100))
+    );
+}
+
+#[test]
+fn test_confine_move_pulls_far_outside_cursor_in() {
+    // Test that a cursor far outside is first pulled in, then moved from there
+    use rmm::config::Region;
+    use rmm::mouse::confine_move;
+
+    let region = Region {
+        x: 100,
+        y: 100,
+        width: 200,
+        height: 100,
+    };
+
+    // Far left on another monitor: pulled to the left edge, then moved right
+    assert_eq!(
+        confine_move(&region, (-1500, 150), (-1490, 160)),
+        ((100, 150), (110, 160))
+    );
+    // Far below-right: pulled to the corner, move back inward keeps it inside
+    assert_eq!(
+        confine_move(&region, (5000, 5000), (4990, 4990)),
+        ((299, 199), (289, 189))
+    );
+}
+
+#[test]
+fn test_move_guard_allows_only_one_holder() {
+    // Test that two threads racing for the guard can't both proceed
+    use rmm::mouse::MoveGuard;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Barrier;
+    use std::thread;
+
+    static FLAG: AtomicBool = AtomicBool::new(false);
+    let proceeded = Arc::new(AtomicUsize::new(0));
+    let busy = Arc::new(AtomicUsize::new(0));
+    // Both threads try at the same moment and hold on until both have tried
+    let start = Arc::new(Barrier::new(2));
+    let tried = Arc::new(Barrier::new(2));
+
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let (proceeded, busy) = (Arc::clone(&proceeded), Arc::clone(&busy));
+            let (start, tried) = (Arc::clone(&start), Arc::clone(&tried));
+            thread::spawn(move || {
+                start.wait();
+                let guard = MoveGuard::try_acquire(&FLAG);
+                if guard.is_some() {
+                    proceeded.fetch_add(1, Ordering::SeqCst);
+                } else {
+                    busy.fetch_add(1, Ordering::SeqCst);
+                }
+                tried.wait();
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(proceeded.load(Ordering::SeqCst), 1);
+    assert_eq!(busy.load(Ordering::SeqCst), 1);
+    // Released once the holder dropped it
+    assert!(MoveGuard::try_acquire(&FLAG).is_some());
+}
+
+#[test]
+fn test_move_log_level() {
+    // Test that verified moves log at INFO, or DEBUG when quiet, and
+    // failures stay at WARN either way
+    use rmm::mouse::move_log_level;
+    use tracing::Level;
+
+    assert_eq!(move_log_level(true, false), Level::INFO);
+    assert_eq!(move_log_level(true, true), Level::DEBUG);
+    assert_eq!(move_log_level(false, false), Level::WARN);
+    assert_eq!(move_log_level(false, true), Level::WARN);
+}
+
+#[test]
+fn test_should_log_move_summary() {
+    // Test that the periodic summary only fires in quiet mode on every Nth move
+    use rmm::mouse::should_log_move_summary;
+
+    assert!(should_log_move_summary(100, true, 100));
+    assert!(should_log_move_summary(200, true, 100));
+    assert!(!should_log_move_summary(99, true, 100));
+    assert!(!should_log_move_summary(100, false, 100));
+    assert!(!should_log_move_summary(100, true, 0));
+    assert!(!should_log_move_summary(0, true, 100));
+}
+
+#[test]
+fn test_strict_verify_rejects_one_pixel() {
+    // Test that strict mode drops the tolerance to zero so a 1-pixel miss fails
+    use rmm::mouse::{delta_matches, verify_tolerance, within_tolerance};
+
+    let strict = Config {
+        strict_verify: true,
+        ..Default::default()
+    };
+    let tolerance = verify_tolerance(&strict);
+    assert_eq!(tolerance, 0);
+    assert!(!within_tolerance((511, 410), (510, 410), tolerance));
+    assert!(within_tolerance((510, 410), (510, 410), tolerance));
+    assert!(!delta_matches((500, 400), (510, 411), (10, 10), tolerance));
+
+    // The default still allows a small drift
+    let relaxed = verify_tolerance(&Config::default());
+    assert!(within_tolerance((511, 410), (510, 410), relaxed));
+}
+
+#[test]
+fn test_blocked_by_drag() {
+    // Test that a held button blocks moves unless the gate is disabled
+    use rdev::Button;
+    use rmm::mouse::blocked_by_drag;
+    use std::time::Instant;
+
+    let mut state = AppState::new();
+    let config = Config::default();
+    assert!(!blocked_by_drag(&state, &config));
+
+    state.press_button(Button::Left, Instant::now());
+    assert!(blocked_by_drag(&state, &config));
+
+    let disabled = Config {
+        skip_while_button_held: false,
+        ..Default::default()
+    };
+    assert!(!blocked_by_drag(&state, &disabled));
+
+    state.release_button(Button::Left, Instant::now());
+    assert!(!blocked_by_drag(&state, &config));
+}
+
+#[test]
+fn test_should_log_liveness() {
+    // Test that the reassurance line fires once per run of N skipped checks
+    use rmm::mouse::should_log_liveness;
+
+    let fired: Vec<u64> = (1..=70).filter(|n| should_log_liveness(*n, 30)).collect();
+    assert_eq!(fired, vec![30, 60]);
+    assert!(!should_log_liveness(0, 30));
+    assert!(!should_log_liveness(30, 0));
+}
+
+#[test]
+fn test_poll_until_within_converges() {
+    // Test that polling returns as soon as the (mock) cursor reaches the target
+    use rmm::mouse::poll_until_within;
+    use std::time::Duration;
+
+    let mut positions = vec![(500, 400), (505, 405), (510, 410)].into_iter();
+    let mut reads = 0;
+    let verified = poll_until_within(
+        || {
+            reads += 1;
+            Ok(positions.next().unwrap_or((999, 999)))
+        },
+        (510, 410),
+        0,
+        Duration::from_millis(1),
+        Duration::from_secs(5),
+    )
+    .unwrap();
+    assert!(verified);
+    assert_eq!(reads, 3);
+}
+
+#[test]
+fn test_poll_until_within_times_out() {
+    // Test that a cursor that never arrives fails once the timeout passes
+    use rmm::mouse::poll_until_within;
+    use std::time::{Duration, Instant};
+
+    let start = Instant::now();
+    let mut reads = 0;
+    let verified = poll_until_within(
+        || {
+            reads += 1;
+            Ok((0, 0))
+        },
+        (510, 410),
+        5,
+        Duration::from_millis(5),
+        Duration::from_millis(50),
+    )
+    .unwrap();
+    assert!(!verified);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert!(reads >= 2);
+}
+
+#[test]
+fn test_poll_until_within_propagates_read_errors() {
+    // Test that a failing position read is reported as an error, not a mismatch
+    use rmm::mouse::poll_until_within;
+    use rmm::RmmError;
+    use std::time::Duration;
+
+    let result = poll_until_within(
+        || Err(RmmError::MouseControl("no cursor".into())),
+        (0, 0),
+        5,
+        Duration::from_millis(1),
+        Duration::from_millis(10),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_planned_step_uses_movement_delta() {
+    // Test that the configured movement_delta sets the move size, within its bounds
+    use rmm::mouse::planned_step;
+    use rmm::movement::{Move, MovementPattern};
+
+    let config = Config {
+        movement_delta: 25,
+        ..Default::default()
+    };
+    assert_eq!(
+        planned_step(&config, MovementPattern::Diagonal, 1),
+        Move::new(25, 25)
+    );
+    assert_eq!(
+        planned_step(&config, MovementPattern::Horizontal, -1),
+        Move::new(-25, 0)
+    );
+
+    let capped = Config {
+        movement_delta: 500,
+        max_movement_delta: 40,
+        ..Default::default()
+    };
+    assert_eq!(
+        planned_step(&capped, MovementPattern::Diagonal, 1),
+        Move::new(40, 40)
+    );
+}
+
+#[test]
+fn test_record_failure_honors_max_errors() {
+    // Test that the error path fires on the max_errors-th consecutive failure
+    use rmm::mouse::record_failure;
+
+    let mut state = AppState::new();
+    assert!(!record_failure(&mut state, 2));
+    assert_eq!(state.error_count, 1);
+    assert!(record_failure(&mut state, 2));
+    assert_eq!(state.error_count, 2);
+
+    let mut state = AppState::new();
+    for _ in 0..9 {
+        assert!(!record_failure(&mut state, 10));
+    }
+    assert!(record_failure(&mut state, 10));
+}
+
+#[test]
+fn test_controller_initialized_once_across_ticks() {
+    // Test that the lazily created controller is built on first use and the
+    // same one is handed out on every later tick
+    use rmm::error::Result;
+    use rmm::mouse::LazyController;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+    /// Stands in for the real controller, numbered in creation order
+    struct Counted(usize);
+    fn counting() -> Result<Counted> {
+        Ok(Counted(CREATED.fetch_add(1, Ordering::SeqCst)))
+    }
+
+    let mut controller = LazyController::with_factory(counting);
+    assert_eq!(CREATED.load(Ordering::SeqCst), 0, "nothing built up front");
+
+    for _ in 0..10 {
+        assert_eq!(controller.get().unwrap().0, 0);
+    }
+    assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_clamp_to_screen() {
+    // Test that targets past either edge are pulled onto the screen and flagged
+    use rmm::mouse::clamp_to_screen;
+
+    let screen = (100, 100);
+    assert_eq!(
+        clamp_to_screen((50, 50), (60, 60), screen),
+        ((60, 60), false)
+    );
+    assert_eq!(
+        clamp_to_screen((95, 95), (105, 105), screen),
+        ((99, 99), true)
+    );
+    assert_eq!(clamp_to_screen((5, 95), (-5, 105), screen), ((0, 99), true));
+    // Cursor on another display (beyond the main one): left alone
+    assert_eq!(
+        clamp_to_screen((150, 50), (160, 60), screen),
+        ((160, 60), false)
+    );
+}
+
+#[test]
+fn test_jittered_tolerance_stays_in_bounds() {
+    // Test that a seeded jitter varies the tolerance within base +/- jitter,
+    // never below zero, and reproducibly
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rmm::mouse::{jittered_tolerance, verify_tolerance};
+
+    let config = Config {
+        verify_tolerance_jitter: 3,
+        ..Default::default()
+    };
+    let base = verify_tolerance(&config);
+    let mut rng = StdRng::seed_from_u64(7);
+    let draws: Vec<i32> = (0..500)
+        .map(|_| jittered_tolerance(&config, &mut rng))
+        .collect();
+    assert!(draws.iter().all(|t| (base - 3..=base + 3).contains(t)));
+    assert!(draws.iter().any(|&t| t != base), "jitter should vary");
+
+    let mut again = StdRng::seed_from_u64(7);
+    let replay: Vec<i32> = (0..500)
+        .map(|_| jittered_tolerance(&config, &mut again))
+        .collect();
+    assert_eq!(draws, replay);
+
+    // Large jitter is clamped at zero; strict and zero jitter are left alone
+    let wide = Config {
+        verify_tolerance_jitter: 50,
+        ..Default::default()
+    };
+    assert!((0..200).all(|_| jittered_tolerance(&wide, &mut rng) >= 0));
+    let strict = Config {
+        strict_verify: true,
+        ..wide
+    };
+    assert_eq!(jittered_tolerance(&strict, &mut rng), 0);
+    assert_eq!(jittered_tolerance(&Config::default(), &mut rng), base);
+}
+
+#[test]
+fn test_cap_move_clamps_oversized_moves() {
+    // Test that a move beyond max_move_distance is cut per axis and reported
+    use rmm::mouse::cap_move;
+    use rmm::movement::Move;
+
+    assert_eq!(
+        cap_move(Move::new(10, -10), 50),
+        (Move::new(10, -10), false)
+    );
+    assert_eq!(
+        cap_move(Move::new(5000, -30), 50),
+        (Move::new(50, -30), true)
+    );
+    assert_eq!(cap_move(Move::new(-900, 900), 0), (Move::new(0, 0), true));
+}
+
+#[test]
+fn test_failure_backoff_doubles_up_to_cap() {
+    // Test that the failure backoff doubles per error and stops growing at the cap
+    use rmm::mouse::{failure_backoff, FAILURE_BACKOFF_CAP};
+    use std::time::Duration;
+
+    let base = Duration::from_secs(5);
+    assert_eq!(failure_backoff(base, 0), base);
+    assert_eq!(failure_backoff(base, 1), Duration::from_secs(10));
+    assert_eq!(failure_backoff(base, 3), Duration::from_secs(40));
+    let capped = failure_backoff(base, FAILURE_BACKOFF_CAP);
+    assert_eq!(capped, Duration::from_secs(320));
+    assert_eq!(failure_backoff(base, FAILURE_BACKOFF_CAP + 5), capped);
+}
@@ -0,0 +1,147 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rmm::movement::Move;
+
+#[test]
+fn test_scale_and_negate() {
+    // Test that scaling by the direction and negating flip both axes
+    let step = Move::diagonal(10);
+    assert_eq!(step.scale(-1), Move::new(-10, -10));
+    assert_eq!(step.scale(3), Move::new(30, 30));
+    assert_eq!(Move::new(4, -2).negate(), Move::new(-4, 2));
+    assert_eq!(step.negate().negate(), step);
+}
+
+#[test]
+fn test_clamp_to() {
+    // Test that each axis is limited independently, keeping its sign
+    assert_eq!(Move::new(50, -50).clamp_to(20), Move::new(20, -20));
+    assert_eq!(Move::new(5, -3).clamp_to(20), Move::new(5, -3));
+    assert_eq!(Move::new(5, -3).clamp_to(-2), Move::new(2, -2));
+}
+
+#[test]
+fn test_jitter_stays_within_amount() {
+    // Test that jitter offsets each axis by at most the amount, reproducibly per seed
+    let mut rng = StdRng::seed_from_u64(1);
+    let base = Move::diagonal(10);
+    for _ in 0..200 {
+        let jittered = base.jitter(3, &mut rng);
+        assert!((7..=13).contains(&jittered.dx));
+        assert!((7..=13).contains(&jittered.dy));
+    }
+    assert_eq!(base.jitter(0, &mut rng), base);
+
+    let a = base.jitter(3, &mut StdRng::seed_from_u64(9));
+    let b = base.jitter(3, &mut StdRng::seed_from_u64(9));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_apply() {
+    // Test that applying a move offsets the position and saturates at the edges
+    assert_eq!(Move::new(10, -10).apply((100, 100)), (110, 90));
+    assert_eq!(Move::diagonal(1).apply((i32::MAX, 0)), (i32::MAX, 1));
+    assert_eq!(
+        Move::diagonal(10).scale(-1).clamp_to(5).apply((0, 0)),
+        (-5, -5)
+    );
+}
+
+#[test]
+fn test_clamp_movement_delta() {
+    // Test below-min, above-max and in-range deltas, keeping the sign
+    use rmm::movement::clamp_movement_delta;
+
+    assert_eq!(clamp_movement_delta(0, 1, 200), 1);
+    assert_eq!(clamp_movement_delta(5000, 1, 200), 200);
+    assert_eq!(clamp_movement_delta(-5000, 1, 200), -200);
+    assert_eq!(clamp_movement_delta(25, 1, 200), 25);
+    assert_eq!(clamp_movement_delta(-25, 1, 200), -25);
+    assert_eq!(clamp_movement_delta(i32::MIN, 1, 200), -200);
+    // Swapped bounds are normalized
+    assert_eq!(clamp_movement_delta(500, 200, 1), 200);
+}
+
+#[test]
+fn test_choose_pattern_varies_per_seed() {
+    // Test that patterns drawn from a set vary but repeat exactly for the same seed
+    use rmm::movement::{choose_pattern, MovementPattern};
+
+    let set = [
+        MovementPattern::Diagonal,
+        MovementPattern::Horizontal,
+        MovementPattern::Vertical,
+    ];
+    let draw = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..50)
+            .map(|_| choose_pattern(&set, &mut rng))
+            .collect::<Vec<_>>()
+    };
+
+    let first = draw(42);
+    assert_eq!(first, draw(42));
+    for pattern in set {
+        assert!(first.contains(&pattern), "{:?} never chosen", pattern);
+    }
+
+    let mut rng = StdRng::seed_from_u64(42);
+    assert_eq!(choose_pattern(&[], &mut rng), MovementPattern::Diagonal);
+    assert_eq!(MovementPattern::Horizontal.step(10), Move::new(10, 0));
+    assert_eq!(MovementPattern::AntiDiagonal.step(10), Move::new(10, -10));
+}
+
+#[test]
+fn test_orbit_points_stay_on_radius() {
+    // Test that successive circle targets all lie movement_delta from the start
+    use rmm::movement::{Orbit, ORBIT_STEP};
+
+    let mut orbit = Orbit::default();
+    let start = (500, 300);
+    let mut current = start;
+    let mut seen = Vec::new();
+    for _ in 0..8 {
+        current = orbit.target(current, 10);
+        orbit.advance();
+        let distance = f64::from(current.0 - start.0).hypot(f64::from(current.1 - start.1));
+        assert!(
+            (distance - 10.0).abs() <= 1.0,
+            "{:?} is off the circle",
+            current
+        );
+        seen.push(current);
+    }
+    assert_eq!(orbit.center, Some(start));
+    assert_eq!(seen[0], (510, 300));
+    assert_eq!(seen[2], (500, 310));
+    // A full turn of distinct points, then back to the first
+    seen.dedup();
+    assert_eq!(seen.len(), 8);
+    assert!((ORBIT_STEP * 8.0 - std::f64::consts::TAU).abs() < 1e-9);
+    assert_eq!(orbit.target(current, 10), (510, 300));
+}
+
+#[test]
+fn test_orbit_recenters_after_user_moves() {
+    // Test that a cursor found away from the circle starts a new one around it
+    use rmm::movement::Orbit;
+
+    let mut orbit = Orbit::default();
+    orbit.target((100, 100), 10);
+    orbit.advance();
+    assert_eq!(orbit.target((800, 600), 10), (810, 600));
+    assert_eq!(orbit.center, Some((800, 600)));
+}
+
+#[test]
+fn test_movement_strategy_names() {
+    // Test that the strategy is read from lowercase strings and defaults to linear
+    use rmm::movement::MovementStrategy;
+
+    let circle: MovementStrategy = serde_json::from_str("\"circle\"").unwrap();
+    assert_eq!(circle, MovementStrategy::Circle);
+    let linear: MovementStrategy = serde_json::from_str("\"linear\"").unwrap();
+    assert_eq!(linear, MovementStrategy::Linear);
+    assert_eq!(MovementStrategy::default(), MovementStrategy::Linear);
+}
@@ -0,0 +1,59 @@
+use rmm::cli::Cli;
+use rmm::oneshot::{should_move_once, OneShotState};
+
+#[test]
+fn test_once_moves_only_when_os_reports_idle() {
+    // Test the one-shot decision from OS idle time
+    assert!(should_move_once(Some(600), 300));
+    assert!(should_move_once(Some(300), 300));
+    assert!(!should_move_once(Some(299), 300));
+}
+
+#[test]
+fn test_once_never_moves_with_unknown_idle() {
+    // Test that an unavailable OS idle time is treated as "not idle"
+    assert!(!should_move_once(None, 0));
+}
+
+#[test]
+fn test_once_state_roundtrip() {
+    // Test that the persisted direction survives a save/load cycle
+    let path = std::env::temp_dir().join(format!("rmm-once-{}.json", std::process::id()));
+
+    assert_eq!(OneShotState::load(&path), OneShotState::default());
+
+    OneShotState { move_direction: -1 }.save(&path).unwrap();
+    assert_eq!(OneShotState::load(&path).move_direction, -1);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cli_parses_once_and_exit() {
+    // Test that the one-shot flag is recognised and unknown flags are rejected
+    assert!(!Cli::parse(Vec::<String>::new()).unwrap().once_and_exit);
+    assert!(Cli::parse(["--once-and-exit"]).unwrap().once_and_exit);
+    assert!(Cli::parse(["--bogus"]).is_err());
+}
+
+#[test]
+fn test_cli_parses_show_schedule() {
+    // Test that --show-schedule is recognized alongside other flags
+    let cli = rmm::cli::Cli::parse(["--show-schedule"]).unwrap();
+    assert!(cli.show_schedule);
+    assert!(!cli.once_and_exit);
+}
+
+#[test]
+fn test_cli_parses_status_line() {
+    // Test that --status-line is recognized
+    let cli = rmm::cli::Cli::parse(["--status-line"]).unwrap();
+    assert!(cli.status_line);
+}
+
+#[test]
+fn test_cli_parses_quiet() {
+    // Test that --quiet is recognized
+    assert!(rmm::cli::Cli::parse(["--quiet"]).unwrap().quiet);
+    assert!(!rmm::cli::Cli::parse(["--status-line"]).unwrap().quiet);
+}
@@ -0,0 +1,84 @@
+use rmm::permissions::{recheck_decision, PermissionAction};
+
+#[test]
+fn test_prompt_when_permission_revoked_at_threshold() {
+    // Test that reaching max errors with a revoked permission prompts the user
+    assert_eq!(
+        recheck_decision(10, 10, Some(false), false),
+        PermissionAction::Prompt
+    );
+}
+
+#[test]
+fn test_no_prompt_below_threshold_or_when_trusted() {
+    // Test that failures below the threshold, a granted permission, or an
+    // unknown permission state never prompt
+    assert_eq!(
+        recheck_decision(3, 10, Some(false), false),
+        PermissionAction::Nothing
+    );
+    assert_eq!(
+        recheck_decision(10, 10, Some(true), false),
+        PermissionAction::Nothing
+    );
+    assert_eq!(
+        recheck_decision(10, 10, None, false),
+        PermissionAction::Nothing
+    );
+}
+
+#[test]
+fn test_prompt_only_once() {
+    // Test that the user isn't prompted again on every failed heartbeat
+    assert_eq!(
+        recheck_decision(15, 10, Some(false), true),
+        PermissionAction::Nothing
+    );
+}
+
+#[cfg(target_os = "macos")]
+#[test]
+fn test_accessibility_status_is_known_on_macos() {
+    // Test that macOS always reports a definite accessibility status
+    assert!(rmm::platform::accessibility_trusted().is_some());
+}
+
+#[cfg(not(target_os = "macos"))]
+#[test]
+fn test_accessibility_status_unknown_elsewhere() {
+    // Test that non-macOS platforms never trigger the permission prompt
+    assert_eq!(rmm::platform::accessibility_trusted(), None);
+}
+
+#[test]
+fn test_failure_notification_fires_once() {
+    // Test that repeated failures past max_errors notify only once, again
+    // only after a successful move, and never when disabled
+    use rmm::mouse::record_failure;
+    use rmm::permissions::notify_failures;
+    use rmm::state::AppState;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SHOWN: AtomicUsize = AtomicUsize::new(0);
+    fn counting(_title: &str, _body: &str) {
+        SHOWN.fetch_add(1, Ordering::SeqCst);
+    }
+
+    let mut state = AppState::new();
+    for _ in 0..25 {
+        if record_failure(&mut state, 10) {
+            notify_failures(&mut state, true, counting);
+        }
+    }
+    assert_eq!(SHOWN.load(Ordering::SeqCst), 1);
+
+    // A successful move clears the flag, as check_and_move does
+    state.error_count = 0;
+    state.failure_notified = false;
+    assert!(notify_failures(&mut state, true, counting));
+    assert_eq!(SHOWN.load(Ordering::SeqCst), 2);
+
+    let mut disabled = AppState::new();
+    assert!(!notify_failures(&mut disabled, false, counting));
+    assert_eq!(SHOWN.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,129 @@
+use rmm::power::{should_pause_for_lid, should_skip_for_display_off};
+
+#[test]
+fn test_lid_gate_when_enabled() {
+    // Test the lid gate across closed, open and unknown lid states
+    assert!(should_pause_for_lid(true, || Some(true)));
+    assert!(!should_pause_for_lid(true, || Some(false)));
+    assert!(!should_pause_for_lid(true, || None));
+}
+
+#[test]
+fn test_lid_gate_when_disabled() {
+    // Test that a closed lid is ignored unless pause_when_lid_closed is set,
+    // without even querying the lid
+    assert!(!should_pause_for_lid(false, || Some(true)));
+    assert!(!should_pause_for_lid(false, || panic!(
+        "lid queried while disabled"
+    )));
+}
+
+#[test]
+fn test_display_gate_across_states() {
+    // Test that moves are skipped only when a display is required and all are known to be off
+    assert!(should_skip_for_display_off(true, || Some(false)));
+    assert!(!should_skip_for_display_off(true, || Some(true)));
+    assert!(!should_skip_for_display_off(true, || None));
+    // Not required: never skip, and don't even ask
+    assert!(!should_skip_for_display_off(false, || Some(false)));
+    assert!(!should_skip_for_display_off(false, || panic!(
+        "displays queried while not required"
+    )));
+}
+
+#[test]
+fn test_wake_detected_after_large_gap() {
+    // Test that a heartbeat far later than expected is reported as a wake
+    use rmm::power::WakeDetector;
+    use std::time::{Duration, SystemTime};
+
+    let start = SystemTime::now();
+    let mut detector = WakeDetector::new_at(Duration::from_secs(10), start);
+
+    assert!(!detector.tick(start + Duration::from_secs(10)));
+    assert!(!detector.tick(start + Duration::from_secs(25)));
+    // Lid closed for an hour
+    assert!(detector.tick(start + Duration::from_secs(3625)));
+    // Back to normal cadence afterwards
+    assert!(!detector.tick(start + Duration::from_secs(3635)));
+}
+
+#[test]
+fn test_wake_grace_gate() {
+    // Test that moves are held back until the grace period ends after a wake
+    use rmm::power::in_wake_grace;
+    use std::time::{Duration, Instant};
+
+    let now = Instant::now();
+    let grace_until = Some(now + Duration::from_secs(60));
+
+    assert!(in_wake_grace(grace_until, now));
+    assert!(in_wake_grace(grace_until, now + Duration::from_secs(59)));
+    assert!(!in_wake_grace(grace_until, now + Duration::from_secs(60)));
+    assert!(!in_wake_grace(None, now));
+}
+
+#[test]
+fn test_lock_screen_decision() {
+    // Test that the lock fires only past the configured idle time while running
+    use rmm::power::should_lock_screen;
+    use std::time::Duration;
+
+    let hour = Duration::from_secs(3600);
+    assert!(should_lock_screen(Some(3600), hour, true));
+    assert!(should_lock_screen(Some(3600), hour * 2, true));
+    assert!(!should_lock_screen(
+        Some(3600),
+        hour - Duration::from_secs(1),
+        true
+    ));
+    // Disabled, or already paused (e.g. after locking once)
+    assert!(!should_lock_screen(None, hour * 10, true));
+    assert!(!should_lock_screen(Some(3600), hour * 2, false));
+}
+
+#[test]
+fn test_lock_gate() {
+    // Test that only a known locked session blocks moves, and only when
+    // moving while locked isn't allowed
+    use rmm::desktop::should_skip_for_lock;
+
+    assert!(should_skip_for_lock(false, || Some(true)));
+    assert!(!should_skip_for_lock(false, || Some(false)));
+    assert!(!should_skip_for_lock(false, || None));
+    assert!(!should_skip_for_lock(true, || Some(true)));
+    assert!(!should_skip_for_lock(true, || panic!(
+        "lock state queried while moving when locked"
+    )));
+}
+
+#[test]
+fn test_fullscreen_gate() {
+    // Test that only a known fullscreen app blocks moves, and only when
+    // pause_on_fullscreen is set
+    use rmm::desktop::should_pause_for_fullscreen;
+
+    assert!(should_pause_for_fullscreen(true, || Some(true)));
+    assert!(!should_pause_for_fullscreen(true, || Some(false)));
+    assert!(!should_pause_for_fullscreen(true, || None));
+    assert!(!should_pause_for_fullscreen(false, || Some(true)));
+    assert!(!should_pause_for_fullscreen(false, || panic!(
+        "fullscreen state queried while disabled"
+    )));
+}
+
+#[test]
+fn test_blocklist_matching() {
+    // Test that blocklist entries match the focused process ignoring case
+    // and a trailing ".exe", and that unknown processes never match
+    use rmm::desktop::is_blocklisted;
+
+    let blocklist = vec!["zoom".to_string(), "Steam.exe".to_string()];
+    assert!(is_blocklisted(&blocklist, Some("Zoom.exe")));
+    assert!(is_blocklisted(&blocklist, Some("ZOOM")));
+    assert!(is_blocklisted(&blocklist, Some("steam")));
+    assert!(!is_blocklisted(&blocklist, Some("zoom.us")));
+    assert!(!is_blocklisted(&blocklist, Some("firefox")));
+    assert!(!is_blocklisted(&blocklist, None));
+    assert!(!is_blocklisted(&[], Some("zoom")));
+}
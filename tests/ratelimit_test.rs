@@ -0,0 +1,51 @@
+use rmm::ratelimit::MoveBudget;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_budget_denies_when_exhausted() {
+    // Test that a full bucket allows exactly its capacity, then denies
+    let start = Instant::now();
+    let mut budget = MoveBudget::new_at(3, start);
+
+    assert!(budget.try_acquire(start));
+    assert!(budget.try_acquire(start));
+    assert!(budget.try_acquire(start));
+    assert!(!budget.try_acquire(start));
+}
+
+#[test]
+fn test_budget_refills_over_time() {
+    // Test that tokens refill at max_moves_per_minute / 60 per second
+    let start = Instant::now();
+    let mut budget = MoveBudget::new_at(6, start);
+    for _ in 0..6 {
+        assert!(budget.try_acquire(start));
+    }
+    assert!(!budget.try_acquire(start));
+
+    // 6/min refills one token every 10 seconds
+    assert!(!budget.try_acquire(start + Duration::from_secs(5)));
+    assert!(budget.try_acquire(start + Duration::from_secs(10)));
+    assert!(!budget.try_acquire(start + Duration::from_secs(10)));
+}
+
+#[test]
+fn test_budget_refill_is_capped_at_capacity() {
+    // Test that a long idle period does not bank more than capacity
+    let start = Instant::now();
+    let mut budget = MoveBudget::new_at(2, start);
+
+    assert_eq!(budget.available(start + Duration::from_secs(3600)), 2);
+}
+
+#[test]
+fn test_zero_budget_is_unlimited() {
+    // Test that max_moves_per_minute = 0 disables the limiter
+    let start = Instant::now();
+    let mut budget = MoveBudget::new_at(0, start);
+
+    assert!(budget.is_unlimited());
+    for _ in 0..1000 {
+        assert!(budget.try_acquire(start));
+    }
+}
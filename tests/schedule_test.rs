@@ -0,0 +1,208 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+use rmm::config::{Config, TimeWindow};
+use rmm::schedule::{
+    format_windows, in_maintenance, is_active_at, summarize_windows, upcoming_windows, Window,
+};
+
+fn at(hour: u32, min: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 1, 1)
+        .unwrap()
+        .and_hms_opt(hour, min, 0)
+        .unwrap()
+}
+
+#[test]
+fn test_summarize_merges_samples_into_windows() {
+    // Test that samples are merged into alternating windows covering the whole span
+    let windows = summarize_windows(at(0, 0), Duration::hours(24), Duration::minutes(1), |t| {
+        (9..17).contains(&t.hour())
+    });
+
+    assert_eq!(
+        windows,
+        vec![
+            Window {
+                start: at(0, 0),
+                end: at(9, 0),
+                active: false
+            },
+            Window {
+                start: at(9, 0),
+                end: at(17, 0),
+                active: true
+            },
+            Window {
+                start: at(17, 0),
+                end: at(0, 0) + Duration::hours(24),
+                active: false
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_format_windows_output() {
+    // Test the printed summary, one line per window
+    let windows = summarize_windows(at(8, 30), Duration::hours(2), Duration::minutes(15), |t| {
+        t >= at(9, 0)
+    });
+
+    assert_eq!(
+        format_windows(&windows),
+        "2024-01-01 08:30 - 2024-01-01 09:00  inactive\n\
+         2024-01-01 09:00 - 2024-01-01 10:30  active\n"
+    );
+}
+
+#[test]
+fn test_default_config_always_active() {
+    // Test that without schedule settings the next 24 hours are one active window
+    let windows = upcoming_windows(&Config::default(), at(12, 0));
+    assert_eq!(windows.len(), 1);
+    assert!(windows[0].active);
+    assert_eq!(windows[0].end - windows[0].start, Duration::hours(24));
+}
+
+fn window(start: &str, end: &str, days: &[chrono::Weekday]) -> TimeWindow {
+    TimeWindow {
+        start: start.parse().unwrap(),
+        end: end.parse().unwrap(),
+        days: days.to_vec(),
+    }
+}
+
+#[test]
+fn test_maintenance_wins_over_active_time() {
+    // Test that time inside a maintenance window is inactive even though RMM is
+    // otherwise active around the clock, and overlapping windows merge
+    let config = Config {
+        maintenance_windows: vec![window("02:00", "03:00", &[]), window("02:30", "04:00", &[])],
+        ..Default::default()
+    };
+
+    assert!(in_maintenance(&config, at(2, 0)));
+    assert!(in_maintenance(&config, at(3, 30)));
+    assert!(!in_maintenance(&config, at(4, 0)));
+    assert!(!in_maintenance(&config, at(1, 59)));
+    assert!(!is_active_at(&config, at(2, 45)));
+    assert!(is_active_at(&config, at(12, 0)));
+
+    let windows = upcoming_windows(&config, at(0, 0));
+    assert_eq!(
+        windows,
+        vec![
+            Window {
+                start: at(0, 0),
+                end: at(2, 0),
+                active: true
+            },
+            Window {
+                start: at(2, 0),
+                end: at(4, 0),
+                active: false
+            },
+            Window {
+                start: at(4, 0),
+                end: at(0, 0) + Duration::hours(24),
+                active: true
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_maintenance_window_past_midnight_and_days() {
+    // Test a window running past midnight that only starts on Mondays
+    // (2024-01-01 is a Monday)
+    let config = Config {
+        maintenance_windows: vec![window("23:00", "01:00", &[chrono::Weekday::Mon])],
+        ..Default::default()
+    };
+
+    assert!(in_maintenance(&config, at(23, 30)));
+    // Tuesday 00:30 is still the Monday window
+    assert!(in_maintenance(&config, at(0, 30) + Duration::days(1)));
+    // Monday 00:30 belongs to a Sunday start, which isn't listed
+    assert!(!in_maintenance(&config, at(0, 30)));
+    assert!(!in_maintenance(&config, at(23, 30) + Duration::days(1)));
+}
+
+#[test]
+fn test_maintenance_windows_from_json() {
+    // Test the config format: "HH:MM" times and optional weekday names
+    let config: Config = serde_json::from_str(
+        r#"{"maintenance_windows": [{"start": "02:00", "end": "03:30", "days": ["sat", "sun"]}]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.maintenance_windows,
+        vec![window(
+            "02:00",
+            "03:30",
+            &[chrono::Weekday::Sat, chrono::Weekday::Sun]
+        )]
+    );
+}
+
+fn working_hours(start: &str, end: &str, days: &[chrono::Weekday]) -> Config {
+    Config {
+        active_start: Some(start.parse().unwrap()),
+        active_end: Some(end.parse().unwrap()),
+        active_days: days.to_vec(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_working_hours_on_weekdays() {
+    // Test that a 09:00-17:00 Mon-Fri schedule is active inside those hours
+    // on a Monday, and inactive before, at the end and on the weekend
+    use chrono::Weekday::*;
+    use rmm::schedule::in_working_hours;
+
+    let config = working_hours("09:00", "17:00", &[Mon, Tue, Wed, Thu, Fri]);
+    // 2024-01-01 is a Monday
+    assert!(in_working_hours(&config, at(9, 0)));
+    assert!(in_working_hours(&config, at(16, 59)));
+    assert!(!in_working_hours(&config, at(8, 59)));
+    assert!(!in_working_hours(&config, at(17, 0)));
+    let saturday = at(12, 0) + Duration::days(5);
+    assert!(!in_working_hours(&config, saturday));
+    assert!(!is_active_at(&config, saturday));
+
+    // No schedule at all means active around the clock
+    assert!(in_working_hours(&Config::default(), saturday));
+}
+
+#[test]
+fn test_overnight_working_hours_wrap_midnight() {
+    // Test that a 22:00-06:00 shift covers both sides of midnight, with the
+    // early hours belonging to the previous day's shift
+    use chrono::Weekday::*;
+    use rmm::schedule::in_working_hours;
+
+    let config = working_hours("22:00", "06:00", &[]);
+    assert!(in_working_hours(&config, at(23, 30)));
+    assert!(in_working_hours(&config, at(5, 59)));
+    assert!(!in_working_hours(&config, at(6, 0)));
+    assert!(!in_working_hours(&config, at(12, 0)));
+
+    // Only Sunday night's shift: Monday 03:00 is in it, Monday 23:00 isn't
+    let sunday_only = working_hours("22:00", "06:00", &[Sun]);
+    assert!(in_working_hours(&sunday_only, at(3, 0)));
+    assert!(!in_working_hours(&sunday_only, at(23, 0)));
+}
+
+#[test]
+fn test_working_hours_parse_from_config() {
+    // Test that the schedule fields accept "HH:MM" times and short day names
+    let config = Config::parse(
+        r#"{"active_start": "09:00", "active_end": "17:00", "active_days": ["Mon", "Tue"]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.active_days,
+        [chrono::Weekday::Mon, chrono::Weekday::Tue]
+    );
+    assert!(config.working_hours().unwrap().contains(at(10, 0)));
+}
@@ -0,0 +1,99 @@
+use rmm::shutdown::ShutdownHooks;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_hooks_run_in_lifo_order() {
+    // Test that hooks run in reverse registration order
+    let hooks = ShutdownHooks::new();
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    for name in ["pidfile", "lockfile", "socket"] {
+        let order = Arc::clone(&order);
+        hooks.register(name, move || order.lock().unwrap().push(name));
+    }
+    assert_eq!(hooks.len(), 3);
+
+    hooks.run();
+
+    assert_eq!(
+        *order.lock().unwrap(),
+        vec!["socket", "lockfile", "pidfile"]
+    );
+}
+
+#[test]
+fn test_hooks_run_only_once() {
+    // Test that running the hooks drains the registry
+    let hooks = ShutdownHooks::new();
+    let count = Arc::new(Mutex::new(0));
+
+    let counter = Arc::clone(&count);
+    hooks.register("counter", move || *counter.lock().unwrap() += 1);
+
+    hooks.run();
+    hooks.run();
+
+    assert_eq!(*count.lock().unwrap(), 1);
+    assert!(hooks.is_empty());
+}
+
+#[test]
+fn test_signal_stops_loop_within_one_tick() {
+    // Test that a loop sleeping through the signal exits right after a
+    // request instead of finishing its interval
+    use rmm::shutdown::ShutdownSignal;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::{Duration, Instant};
+
+    let signal = Arc::new(ShutdownSignal::new());
+    let ticks = Arc::new(AtomicU32::new(0));
+    let handle = {
+        let signal = Arc::clone(&signal);
+        let ticks = Arc::clone(&ticks);
+        std::thread::spawn(move || {
+            while !signal.sleep(Duration::from_secs(10)) {
+                ticks.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+    };
+    // Let the loop get into its first sleep
+    std::thread::sleep(Duration::from_millis(50));
+
+    let requested = Instant::now();
+    signal.request();
+    handle.join().unwrap();
+    // Well short of the interval, with room for a slow machine
+    assert!(requested.elapsed() < Duration::from_secs(1));
+    assert_eq!(ticks.load(Ordering::SeqCst), 0);
+    assert!(signal.is_requested());
+
+    // Once requested, sleeping returns straight away
+    let started = Instant::now();
+    assert!(signal.sleep(Duration::from_secs(10)));
+    signal.wait();
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_wait_blocks_until_requested() {
+    // Test that wait() keeps the caller parked until another thread requests
+    // shutdown, as the main thread does while the tray runs elsewhere
+    use rmm::shutdown::ShutdownSignal;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let signal = Arc::new(ShutdownSignal::new());
+    let (done_tx, done_rx) = mpsc::channel();
+    let waiter = {
+        let signal = Arc::clone(&signal);
+        std::thread::spawn(move || {
+            signal.wait();
+            done_tx.send(()).unwrap();
+        })
+    };
+
+    assert!(done_rx.recv_timeout(Duration::from_millis(100)).is_err());
+    signal.request();
+    assert!(done_rx.recv_timeout(Duration::from_secs(1)).is_ok());
+    waiter.join().unwrap();
+}
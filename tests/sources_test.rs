@@ -0,0 +1,221 @@
+use rmm::config::Config;
+use rmm::mouse::should_move;
+use rmm::sources::{apply_signal, ActivitySignal, ActivitySource, ListenerSource, SourceRegistry};
+use rmm::state::{AppState, InputKind};
+use std::time::{Duration, Instant};
+
+/// Hands out a scripted list of signals, one per poll
+struct MockSource {
+    name: &'static str,
+    pending: Vec<ActivitySignal>,
+}
+
+impl MockSource {
+    fn new(name: &'static str, mut signals: Vec<ActivitySignal>) -> Box<Self> {
+        signals.reverse();
+        Box::new(Self {
+            name,
+            pending: signals,
+        })
+    }
+}
+
+impl ActivitySource for MockSource {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn poll(&mut self) -> Option<ActivitySignal> {
+        self.pending.pop()
+    }
+}
+
+fn idle_state(config: &Config, now: Instant) -> AppState {
+    let mut state = AppState::new();
+    state.start(config);
+    state.last_activity = now - Duration::from_secs(60);
+    state.last_moved = now - Duration::from_secs(60);
+    state
+}
+
+#[test]
+fn test_two_sources_combine_into_idle_decision() {
+    // Test that the most recent signal from either source decides whether the
+    // user counts as idle
+    let config = Config {
+        inactivity_threshold: 10,
+        ..Default::default()
+    };
+    let now = Instant::now();
+    let mut state = idle_state(&config, now);
+    assert!(should_move(&state, &config));
+
+    let mut sources = SourceRegistry::new();
+    sources.register(MockSource::new(
+        "keyboard",
+        vec![ActivitySignal::new(
+            InputKind::Keyboard,
+            now - Duration::from_secs(30),
+        )],
+    ));
+    sources.register(MockSource::new(
+        "mouse",
+        vec![
+            ActivitySignal::new(InputKind::Mouse, now - Duration::from_secs(20)),
+            ActivitySignal::new(InputKind::Mouse, now - Duration::from_secs(2)),
+        ],
+    ));
+    assert_eq!(sources.names(), vec!["keyboard", "mouse"]);
+
+    assert_eq!(sources.poll_into(&mut state), 3);
+    assert_eq!(state.last_activity, now - Duration::from_secs(2));
+    assert!(!should_move(&state, &config));
+
+    // Drained: polling again changes nothing
+    assert_eq!(sources.poll_into(&mut state), 0);
+}
+
+#[test]
+fn test_old_signals_do_not_make_user_active() {
+    // Test that signals older than the threshold leave the user idle
+    let config = Config {
+        inactivity_threshold: 10,
+        ..Default::default()
+    };
+    let now = Instant::now();
+    let mut state = idle_state(&config, now);
+
+    let mut sources = SourceRegistry::new();
+    sources.register(MockSource::new(
+        "a",
+        vec![ActivitySignal::new(
+            InputKind::Keyboard,
+            now - Duration::from_secs(40),
+        )],
+    ));
+    sources.register(MockSource::new(
+        "b",
+        vec![ActivitySignal::new(
+            InputKind::Mouse,
+            now - Duration::from_secs(15),
+        )],
+    ));
+    sources.poll_into(&mut state);
+    assert!(should_move(&state, &config));
+}
+
+#[test]
+fn test_apply_signal_never_goes_backwards() {
+    // Test that a signal older than what is already known is dropped and
+    // last_activity never moves back in time
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.last_activity = now - Duration::from_secs(60);
+    state.last_moved = now - Duration::from_secs(60);
+
+    assert!(apply_signal(
+        &mut state,
+        ActivitySignal::new(InputKind::Mouse, now)
+    ));
+    assert!(!apply_signal(
+        &mut state,
+        ActivitySignal::new(InputKind::Mouse, now - Duration::from_secs(5))
+    ));
+    assert!(apply_signal(
+        &mut state,
+        ActivitySignal::new(InputKind::Keyboard, now - Duration::from_secs(5))
+    ));
+    assert_eq!(state.last_activity, now);
+}
+
+#[test]
+fn test_os_idle_reset_by_own_move_is_ignored() {
+    // Test that OS-level input right at our own move is not user activity,
+    // while later OS-level input is
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.last_moved = now;
+
+    assert!(!apply_signal(
+        &mut state,
+        ActivitySignal::new(InputKind::System, now + Duration::from_millis(500))
+    ));
+    assert!(apply_signal(
+        &mut state,
+        ActivitySignal::new(InputKind::System, now + Duration::from_secs(3))
+    ));
+}
+
+#[test]
+fn test_poll_mode_updates_idle_from_os() {
+    // Test that the OS idle source alone moves last_activity to the reported
+    // input time, and only reports each input once
+    use rmm::sources::OsIdleSource;
+
+    let config = Config {
+        inactivity_threshold: 10,
+        ..Default::default()
+    };
+    let now = Instant::now();
+    let mut state = idle_state(&config, now);
+    assert!(should_move(&state, &config));
+
+    let mut sources = SourceRegistry::new();
+    sources.register(Box::new(OsIdleSource::with_reader(|| Some(3))));
+    assert_eq!(sources.poll_into(&mut state), 1);
+    let idle = state.last_activity.elapsed();
+    assert!(idle >= Duration::from_secs(3) && idle < Duration::from_secs(5));
+    assert!(!should_move(&state, &config));
+    assert_eq!(sources.poll_into(&mut state), 0);
+
+    // Unknown idle time reports nothing
+    let mut unknown = SourceRegistry::new();
+    unknown.register(Box::new(OsIdleSource::with_reader(|| None)));
+    assert_eq!(unknown.poll_into(&mut state), 0);
+}
+
+#[test]
+fn test_poll_mode_skips_listener() {
+    // Test that poll mode only registers the OS idle source
+    use rmm::config::ActivityMode;
+    use rmm::heartbeat::activity_sources;
+    use std::sync::{Arc, Mutex};
+
+    let state = Arc::new(Mutex::new(AppState::new()));
+    let sources = activity_sources(ActivityMode::Poll, &state);
+    assert_eq!(sources.lock().unwrap().names(), vec!["os_idle"]);
+}
+
+#[test]
+fn test_listener_keeps_only_latest_input_per_kind() {
+    // Test that a flood of listener input between two polls is held as one
+    // signal per kind, the newest of each
+    let config = Config::default();
+    let now = Instant::now();
+    let mut state = idle_state(&config, now);
+    let (source, input) = ListenerSource::pair();
+    for ms in (0..10_000).rev() {
+        input.report(ActivitySignal::new(
+            InputKind::Mouse,
+            now - Duration::from_millis(ms),
+        ));
+    }
+    input.report(ActivitySignal::new(
+        InputKind::Keyboard,
+        now - Duration::from_secs(5),
+    ));
+    input.report(ActivitySignal::new(
+        InputKind::Mouse,
+        now - Duration::from_secs(3),
+    ));
+
+    let mut sources = SourceRegistry::new();
+    sources.register(Box::new(source));
+    assert_eq!(sources.poll_into(&mut state), 2);
+    assert_eq!(state.last_input[&InputKind::Mouse], now);
+    assert_eq!(
+        state.last_input[&InputKind::Keyboard],
+        now - Duration::from_secs(5)
+    );
+    assert_eq!(sources.poll_into(&mut state), 0);
+}
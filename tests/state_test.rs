@@ -0,0 +1,216 @@
+use rmm::state::{AppState, InputKind};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_idle_without_per_source_input_uses_last_activity() {
+    // Test that idle falls back to last_activity when no input was recorded
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.last_activity = now - Duration::from_secs(42);
+
+    assert_eq!(
+        state.idle_for(now, &BTreeMap::new(), 10),
+        Duration::from_secs(42)
+    );
+}
+
+#[test]
+fn test_idle_uses_most_recent_source() {
+    // Test that with default timeouts the most recent input determines idle time
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.record_input(InputKind::Keyboard, now - Duration::from_secs(100));
+    state.record_input(InputKind::Mouse, now - Duration::from_secs(30));
+
+    assert_eq!(
+        state.idle_for(now, &BTreeMap::new(), 60),
+        Duration::from_secs(30)
+    );
+}
+
+#[test]
+fn test_longer_keyboard_timeout_extends_activity() {
+    // Test that a longer keyboard timeout keeps the user active after typing
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.record_input(InputKind::Keyboard, now - Duration::from_secs(90));
+    state.record_input(InputKind::Mouse, now - Duration::from_secs(200));
+
+    let timeouts = BTreeMap::from([(InputKind::Keyboard, 120)]);
+
+    // Keyboard 90s ago with a 120s timeout: 30s short of the 60s threshold
+    let idle = state.idle_for(now, &timeouts, 60);
+    assert_eq!(idle, Duration::from_secs(30));
+    assert!(idle.as_secs() < 60);
+
+    // Once the keyboard timeout passes the user counts as idle
+    let later = now + Duration::from_secs(40);
+    assert!(state.idle_for(later, &timeouts, 60).as_secs() >= 60);
+}
+
+#[test]
+fn test_shorter_mouse_timeout_shortens_activity() {
+    // Test that a shorter mouse timeout lets mouse-only activity expire sooner
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.record_input(InputKind::Mouse, now - Duration::from_secs(20));
+
+    let timeouts = BTreeMap::from([(InputKind::Mouse, 15)]);
+
+    assert_eq!(state.idle_for(now, &timeouts, 60), Duration::from_secs(65));
+}
+
+#[test]
+fn test_source_timeouts_deserialize_from_config() {
+    // Test that the per-source timeout map reads from JSON
+    let config: rmm::Config =
+        serde_json::from_str(r#"{"source_timeouts": {"keyboard": 300, "mouse": 30}}"#).unwrap();
+
+    assert_eq!(config.source_timeouts.get(&InputKind::Keyboard), Some(&300));
+    assert_eq!(config.source_timeouts.get(&InputKind::Mouse), Some(&30));
+    assert_eq!(config.inactivity_threshold, 10);
+}
+
+#[test]
+fn test_initial_direction_fixed() {
+    // Test that Positive/Negative set the starting direction on start
+    use rmm::config::{Config, InitialDirection};
+
+    let mut state = AppState::new();
+    state.start(&Config {
+        initial_direction: InitialDirection::Negative,
+        ..Default::default()
+    });
+    assert_eq!(state.move_direction, -1);
+
+    let mut state = AppState::new();
+    state.start(&Config::default());
+    assert_eq!(state.move_direction, 1);
+    assert!(state.is_running);
+}
+
+#[test]
+fn test_initial_direction_random_is_deterministic_with_seed() {
+    // Test that Random picks both directions across seeds and is
+    // reproducible for the same seed
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rmm::config::{Config, InitialDirection};
+
+    let config = Config {
+        initial_direction: InitialDirection::Random,
+        ..Default::default()
+    };
+    let direction_for = |seed| {
+        let mut state = AppState::new();
+        state.start_with_rng(&config, &mut StdRng::seed_from_u64(seed));
+        state.move_direction
+    };
+
+    let directions: Vec<i32> = (0..32).map(direction_for).collect();
+    assert!(directions.iter().all(|d| *d == 1 || *d == -1));
+    assert!(directions.contains(&1) && directions.contains(&-1));
+    assert_eq!(directions, (0..32).map(direction_for).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_button_held_until_released() {
+    // Test that presses mark buttons held and releases clear them
+    use rdev::Button;
+
+    let now = Instant::now();
+    let mut state = AppState::new();
+    assert!(!state.button_held());
+
+    state.press_button(Button::Left, now);
+    state.press_button(Button::Right, now);
+    assert!(state.button_held());
+
+    state.release_button(Button::Left, now);
+    assert!(state.button_held());
+    state.release_button(Button::Right, now);
+    assert!(!state.button_held());
+
+    // A stray release doesn't underflow
+    state.release_button(Button::Middle, now);
+    assert!(!state.button_held());
+}
+
+#[test]
+#[tracing_test::traced_test]
+fn test_set_running_logs_transition() {
+    // Test that a transition through the setter logs old state, new state and cause
+    use rmm::state::Cause;
+
+    let mut state = AppState::new();
+    assert!(state.set_running(true, Cause::Ipc));
+    assert!(logs_contain(
+        "from=\"stopped\" to=\"running\" cause=\"ipc\""
+    ));
+
+    // No change, no record
+    assert!(!state.set_running(true, Cause::Tray));
+    assert!(!logs_contain("cause=\"tray\""));
+
+    assert!(state.set_running(false, Cause::Error));
+    assert!(logs_contain(
+        "from=\"running\" to=\"stopped\" cause=\"error\""
+    ));
+    assert!(!state.is_running);
+}
+
+#[test]
+fn test_prune_stale_buttons() {
+    // Test that a held button is only forgotten after max_age without button events
+    use rdev::Button;
+
+    let start = Instant::now();
+    let max_age = Duration::from_secs(600);
+    let mut state = AppState::new();
+    assert!(!state.prune_stale_buttons(start, max_age));
+
+    state.press_button(Button::Left, start);
+    assert!(!state.prune_stale_buttons(start + Duration::from_secs(599), max_age));
+    assert!(state.button_held());
+    assert!(state.prune_stale_buttons(start + Duration::from_secs(600), max_age));
+    assert!(!state.button_held());
+}
+
+#[test]
+fn test_state_kind_picks_tray_icon() {
+    // Test that running, paused and too-many-errors states map to their own
+    // icon files, with errors taking precedence over paused
+    use rmm::state::StateKind;
+
+    let mut state = AppState::new();
+    state.is_running = true;
+    assert_eq!(StateKind::of(&state, 10), StateKind::Active);
+    state.is_running = false;
+    assert_eq!(StateKind::of(&state, 10), StateKind::Paused);
+    state.error_count = 10;
+    assert_eq!(StateKind::of(&state, 10), StateKind::Error);
+
+    assert_eq!(StateKind::Active.icon_file(), "mouse.png");
+    assert_eq!(StateKind::Paused.icon_file(), "mouse-paused.png");
+    assert_eq!(StateKind::Error.icon_file(), "mouse-error.png");
+    for kind in [StateKind::Active, StateKind::Paused, StateKind::Error] {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join(kind.icon_file());
+        assert!(path.exists(), "{} missing", path.display());
+    }
+}
+
+#[test]
+fn test_stats_start_at_zero() {
+    // Test that a new state has no moves counted and measures uptime from
+    // its creation
+    let mut state = AppState::new();
+    assert_eq!(state.total_moves(), 0);
+    assert_eq!(state.failed_moves(), 0);
+
+    state.started_at -= Duration::from_secs(90);
+    assert!(state.uptime(Instant::now()) >= Duration::from_secs(90));
+    assert_eq!(state.uptime(state.started_at), Duration::ZERO);
+}
@@ -0,0 +1,54 @@
+use rmm::config::Config;
+use rmm::state::AppState;
+use rmm::status::{format_status_line, StatusSnapshot};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_status_line_formatting() {
+    // Test the status line for a running and a paused snapshot
+    let snapshot = StatusSnapshot {
+        running: true,
+        idle: Duration::from_secs(65),
+        threshold: Duration::from_secs(300),
+        moves: 12,
+        moves_today: 3,
+        errors: 0,
+    };
+    assert_eq!(
+        format_status_line(&snapshot),
+        "RMM running | idle 1m 05s / 5m 00s | moves 12 (3 today) | errors 0"
+    );
+
+    let paused = StatusSnapshot {
+        running: false,
+        idle: Duration::from_secs(7),
+        threshold: Duration::from_secs(10),
+        moves: 0,
+        moves_today: 0,
+        errors: 3,
+    };
+    assert_eq!(
+        format_status_line(&paused),
+        "RMM paused | idle 7s / 10s | moves 0 (0 today) | errors 3"
+    );
+}
+
+#[test]
+fn test_snapshot_from_state() {
+    // Test that a snapshot reflects the state's idle time and counters
+    let now = Instant::now();
+    let mut state = AppState::new();
+    state.is_running = true;
+    state.last_activity = now - Duration::from_secs(42);
+    (0..5).for_each(|_| state.count_move());
+    state.metrics.record_move(rmm::metrics::local_today());
+    state.error_count = 1;
+
+    let snapshot = StatusSnapshot::capture(&state, &Config::default(), now);
+    assert!(snapshot.running);
+    assert_eq!(snapshot.idle, Duration::from_secs(42));
+    assert_eq!(snapshot.threshold, Duration::from_secs(10));
+    assert_eq!(snapshot.moves, 5);
+    assert_eq!(snapshot.moves_today, 1);
+    assert_eq!(snapshot.errors, 1);
+}
@@ -0,0 +1,71 @@
+use rmm::supervise::{should_respawn, spawn_supervised, MAX_RESTARTS};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_respawn_decision() {
+    // Test that restarts only happen when enabled and below the cap
+    assert!(should_respawn(true, 0));
+    assert!(should_respawn(true, MAX_RESTARTS - 1));
+    assert!(!should_respawn(true, MAX_RESTARTS));
+    assert!(!should_respawn(false, 0));
+}
+
+#[test]
+fn test_supervised_thread_restarts_after_panic() {
+    // Test that a panicking body is run again and stops once it returns normally
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&runs);
+    spawn_supervised("flaky", true, move || {
+        if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+            panic!("flaky worker");
+        }
+    })
+    .join()
+    .unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_supervised_thread_gives_up() {
+    // Test that without restart_on_panic the body runs once, and with it the cap applies
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&runs);
+    spawn_supervised("once", false, move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        panic!("always");
+    })
+    .join()
+    .unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    let runs = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&runs);
+    spawn_supervised("capped", true, move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        panic!("always");
+    })
+    .join()
+    .unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), MAX_RESTARTS + 1);
+}
+
+#[test]
+fn test_restart_recovers_poisoned_state() {
+    // Test that a restarted body which clears the poison can lock state the
+    // previous run panicked while holding
+    let state = Arc::new(Mutex::new(0u32));
+    let shared = Arc::clone(&state);
+    spawn_supervised("poisoner", true, move || {
+        shared.clear_poison();
+        let mut runs = shared.lock().unwrap();
+        *runs += 1;
+        if *runs == 1 {
+            panic!("panicked holding the state");
+        }
+    })
+    .join()
+    .unwrap();
+    assert!(!state.is_poisoned());
+    assert_eq!(*state.lock().unwrap(), 2);
+}
@@ -0,0 +1,26 @@
+use rmm::system::should_skip_for_load;
+
+#[test]
+fn test_skip_when_load_exceeds_threshold() {
+    // Test the skip decision given load and threshold
+    assert!(should_skip_for_load(Some(8.5), Some(4.0)));
+    assert!(!should_skip_for_load(Some(4.0), Some(4.0)));
+    assert!(!should_skip_for_load(Some(0.3), Some(4.0)));
+}
+
+#[test]
+fn test_never_skip_without_threshold_or_load() {
+    // Test that an unset threshold or unknown load never skips
+    assert!(!should_skip_for_load(Some(100.0), None));
+    assert!(!should_skip_for_load(None, Some(1.0)));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_load_average_available_on_unix() {
+    // Test that getloadavg returns a sane value on Unix
+    use rmm::system::load_average;
+
+    let load = load_average().expect("load average should be available");
+    assert!(load >= 0.0);
+}
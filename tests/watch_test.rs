@@ -0,0 +1,57 @@
+use rmm::config::Config;
+use rmm::watch::ConfigWatcher;
+use std::fs;
+use std::sync::{Arc, RwLock};
+
+#[test]
+fn test_watcher_applies_edited_config() {
+    // Test that writing a new config file updates the in-memory config, that an
+    // invalid edit keeps the previous values, and that a deleted file is ignored
+    let path = std::env::temp_dir().join(format!("rmm-watch-{}.json", std::process::id()));
+    fs::write(&path, r#"{"inactivity_threshold": 60}"#).unwrap();
+    let shared = Arc::new(RwLock::new(Config::load_from(&path).unwrap()));
+    let mut watcher = ConfigWatcher::new(path.clone());
+
+    // Nothing changed yet
+    assert!(watcher.poll(&shared).is_none());
+
+    fs::write(
+        &path,
+        r#"{"inactivity_threshold": 300, "movement_delta": 4}"#,
+    )
+    .unwrap();
+    let changes = watcher.poll(&shared).unwrap().unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(shared.read().unwrap().inactivity_threshold, 300);
+    assert_eq!(shared.read().unwrap().movement_delta, 4);
+    assert!(watcher.poll(&shared).is_none());
+
+    fs::write(&path, r#"{"inactivity_threshold": "#).unwrap();
+    assert!(watcher.poll(&shared).unwrap().is_err());
+    assert_eq!(shared.read().unwrap().inactivity_threshold, 300);
+
+    fs::remove_file(&path).unwrap();
+    assert!(watcher.poll(&shared).is_none());
+    assert_eq!(shared.read().unwrap().inactivity_threshold, 300);
+}
+
+#[test]
+fn test_watch_thread_stops_on_shutdown() {
+    // Test that the background watcher ends promptly once shutdown is requested
+    // instead of sleeping out its interval forever
+    use rmm::shutdown::ShutdownSignal;
+    use rmm::state::AppState;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    static SIGNAL: ShutdownSignal = ShutdownSignal::new();
+    let shared = Arc::new(RwLock::new(Config::default()));
+    let state = Arc::new(Mutex::new(AppState::new()));
+    let path = std::env::temp_dir().join(format!("rmm-watch-stop-{}.json", std::process::id()));
+    let watcher = rmm::watch::start(shared, state, path, &SIGNAL);
+
+    let started = Instant::now();
+    SIGNAL.request();
+    watcher.join().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(1));
+}